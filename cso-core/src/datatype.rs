@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+pub const MAX_DECIMAL_PRECISION: u8 = 38; // max number of digits of a decimal
+
+/// The type of the values of a column or of a scalar expression.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum DataType {
+    /// The type is not known, e.g. a NULL literal or a column whose type was not declared. It converts to any type.
+    #[default]
+    Unknown,
+    Boolean,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Decimal {
+        precision: u8, // total number of digits
+        scale: u8,     // number of digits after the decimal point
+    },
+    String,
+    Bytes,
+    Date,
+    Time,
+    Timestamp,
+}
+
+impl DataType {
+    pub fn is_numeric(&self) -> bool {
+        self.numeric_rank().is_some()
+    }
+
+    pub fn is_temporal(&self) -> bool {
+        matches!(self, DataType::Date | DataType::Time | DataType::Timestamp)
+    }
+
+    /// Returns the width in bytes of the values of fixed-width types.
+    pub fn width(&self) -> Option<u32> {
+        match self {
+            DataType::Boolean => Some(1),
+            DataType::Int16 => Some(2),
+            DataType::Int32 | DataType::Float32 | DataType::Date => Some(4),
+            DataType::Int64 | DataType::Float64 | DataType::Time | DataType::Timestamp => Some(8),
+            DataType::Decimal { .. } => Some(16),
+            DataType::Unknown | DataType::String | DataType::Bytes => None,
+        }
+    }
+
+    /// Numeric types are implicitly converted to the types of higher rank: int2 < int4 < int8 < decimal < float4 <
+    /// float8, like PostgreSQL.
+    fn numeric_rank(&self) -> Option<u8> {
+        match self {
+            DataType::Int16 => Some(0),
+            DataType::Int32 => Some(1),
+            DataType::Int64 => Some(2),
+            DataType::Decimal { .. } => Some(3),
+            DataType::Float32 => Some(4),
+            DataType::Float64 => Some(5),
+            _ => None,
+        }
+    }
+
+    /// Returns the decimal type holding every value of an integer or decimal type.
+    fn as_decimal(&self) -> Option<(u8, u8)> {
+        match self {
+            DataType::Int16 => Some((5, 0)),
+            DataType::Int32 => Some((10, 0)),
+            DataType::Int64 => Some((19, 0)),
+            DataType::Decimal { precision, scale } => Some((*precision, *scale)),
+            _ => None,
+        }
+    }
+
+    /// Returns whether values of this type are converted to `target` without an explicit cast.
+    pub fn can_implicitly_cast_to(&self, target: &DataType) -> bool {
+        match (self, target) {
+            (DataType::Unknown, _) => true,
+            (source, target) if source == target => true,
+            (DataType::Date, DataType::Timestamp) => true,
+            (source, target) => match (source.numeric_rank(), target.numeric_rank()) {
+                (Some(source_rank), Some(target_rank)) => source_rank <= target_rank,
+                _ => false,
+            },
+        }
+    }
+
+    /// Returns the type both types are implicitly converted to when they are compared, if any.
+    pub fn common_type(&self, other: &DataType) -> Option<DataType> {
+        match (self, other) {
+            (DataType::Unknown, other) => Some(*other),
+            (this, DataType::Unknown) => Some(*this),
+            (DataType::Decimal { .. }, _) | (_, DataType::Decimal { .. })
+                if self.as_decimal().is_some() && other.as_decimal().is_some() =>
+            {
+                let (precision, scale) = self.as_decimal()?;
+                let (other_precision, other_scale) = other.as_decimal()?;
+                let integer_digits = precision
+                    .saturating_sub(scale)
+                    .max(other_precision.saturating_sub(other_scale));
+                let scale = scale.max(other_scale);
+                Some(DataType::Decimal {
+                    precision: (integer_digits + scale).min(MAX_DECIMAL_PRECISION),
+                    scale,
+                })
+            }
+            _ if self.can_implicitly_cast_to(other) => Some(*other),
+            _ if other.can_implicitly_cast_to(self) => Some(*self),
+            _ => None,
+        }
+    }
+}
@@ -1,24 +1,117 @@
 use crate::any::AsAny;
+use crate::datatype::DataType;
 use crate::ColumnRefSet;
 use dyn_clonable::clonable;
 use std::fmt::Debug;
 
+#[typetag::serde(tag = "type")]
 #[clonable]
 pub trait ScalarExpression: AsAny + Debug + Clone {
     fn is_boolean_expression(&self) -> bool {
-        false
+        self.return_type() == DataType::Boolean
     }
 
+    /// Returns the type of the values of the expression.
+    fn return_type(&self) -> DataType;
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool;
 
-    fn derive_used_columns(&self, col_set: &mut ColumnRefSet);
+    fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
+        self.children()
+            .iter()
+            .for_each(|child| child.derive_used_columns(col_set));
+    }
+
+    /// Returns the operands of the expression.
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![]
+    }
+
+    /// Returns a copy of the expression with its operands replaced by `children`, given in the order of `children()`.
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression>;
 }
 
+/// A visitor of expression trees, see `accept`.
+pub trait ExpressionVisitor {
+    /// Called before the children of `expr` are visited, returns false to skip the children.
+    fn pre_visit(&mut self, _expr: &dyn ScalarExpression) -> bool {
+        true
+    }
+
+    /// Called after the children of `expr` are visited.
+    fn post_visit(&mut self, _expr: &dyn ScalarExpression) {}
+}
+
+/// Rewrites a node of an expression tree, or returns `None` to keep it, see `transform_down`.
+pub type RewriteFn<'a> = dyn FnMut(&dyn ScalarExpression) -> Option<Box<dyn ScalarExpression>> + 'a;
+
 impl dyn ScalarExpression {
     #[inline]
     pub fn downcast_ref<T: ScalarExpression>(&self) -> Option<&T> {
         self.as_any().downcast_ref::<T>()
     }
+
+    #[inline]
+    pub fn clone_box(&self) -> Box<dyn ScalarExpression> {
+        dyn_clonable::dyn_clone::clone_box(self)
+    }
+
+    /// Walks the expression tree depth-first.
+    pub fn accept(&self, visitor: &mut dyn ExpressionVisitor) {
+        if visitor.pre_visit(self) {
+            self.children().into_iter().for_each(|child| child.accept(visitor));
+        }
+        visitor.post_visit(self);
+    }
+
+    /// Calls `f` on every node of the expression tree, parents before their children.
+    pub fn for_each(&self, f: &mut dyn FnMut(&dyn ScalarExpression)) {
+        f(self);
+        self.children().into_iter().for_each(|child| child.for_each(f));
+    }
+
+    /// Returns the nodes of the expression tree satisfying `predicate`, parents before their children.
+    pub fn collect(&self, predicate: &dyn Fn(&dyn ScalarExpression) -> bool) -> Vec<&dyn ScalarExpression> {
+        let mut nodes = vec![];
+        self.collect_into(predicate, &mut nodes);
+        nodes
+    }
+
+    fn collect_into<'a>(
+        &'a self,
+        predicate: &dyn Fn(&dyn ScalarExpression) -> bool,
+        nodes: &mut Vec<&'a dyn ScalarExpression>,
+    ) {
+        if predicate(self) {
+            nodes.push(self);
+        }
+        self.children()
+            .into_iter()
+            .for_each(|child| child.collect_into(predicate, nodes));
+    }
+
+    /// Rewrites the expression tree bottom-up: the children of a node are rewritten first, then `f` rewrites the
+    /// node with its rewritten children.
+    pub fn transform_up(
+        &self,
+        f: &mut dyn FnMut(Box<dyn ScalarExpression>) -> Box<dyn ScalarExpression>,
+    ) -> Box<dyn ScalarExpression> {
+        let children = self.children().into_iter().map(|child| child.transform_up(f)).collect();
+        f(self.with_children(children))
+    }
+
+    /// Rewrites the expression tree top-down: `f` rewrites a node first, returning `None` to keep it, then the
+    /// children of the rewritten node are rewritten.
+    pub fn transform_down(&self, f: &mut RewriteFn<'_>) -> Box<dyn ScalarExpression> {
+        let node = f(self);
+        let node = node.as_deref().unwrap_or(self);
+        let children = node
+            .children()
+            .into_iter()
+            .map(|child| child.transform_down(f))
+            .collect();
+        node.with_children(children)
+    }
 }
 
 impl PartialEq<Self> for dyn ScalarExpression {
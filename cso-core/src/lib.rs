@@ -5,23 +5,31 @@
 
 pub mod any;
 pub mod cost;
+pub mod datatype;
 pub mod expression;
 pub mod memo;
 pub mod metadata;
+pub mod minidump;
 pub mod operator;
 pub mod property;
 pub mod rule;
 
 mod task;
 
+use crate::expression::ScalarExpression;
 use crate::memo::{GroupPlanRef, Memo};
-use crate::metadata::MdAccessor;
-use crate::operator::{LogicalOperator, Operator, PhysicalOperator};
-use crate::property::{LogicalProperties, PhysicalProperties};
+use crate::metadata::{MdAccessor, MdProvider, RecordingMdProvider, VersionedMdId};
+use crate::minidump::Minidump;
+use crate::operator::{
+    deserialize_logical_op, deserialize_physical_op, serialize_logical_op, serialize_physical_op, LogicalOperator,
+    Operator, OperatorSerde, PhysicalOperator,
+};
+use crate::property::{LogicalProperties, PhysicalProperties, PropertySerde};
 use crate::rule::{RuleId, RuleSet};
 use crate::task::{OptimizeGroupTask, TaskRunner};
 use bit_set::BitSet;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::marker::PhantomData;
@@ -30,10 +38,16 @@ use std::rc::Rc;
 pub trait OptimizerType: 'static + PartialEq + Eq + Hash + Clone {
     type RuleId: RuleId;
     type OperatorId: PartialEq + Debug;
-    type MdId: PartialEq + Eq + Clone + Hash + Debug + Serialize + for<'a> Deserialize<'a>;
+    type MdId: PartialEq + Eq + Clone + Hash + Debug + Serialize + for<'a> Deserialize<'a> + VersionedMdId;
+    type OperatorSerde: OperatorSerde<Self>;
+    type PropertySerde: PropertySerde<Self>;
 }
 
+/// A logical plan, serialized with its operators, inputs and required properties.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct LogicalPlan<T: OptimizerType> {
+    #[serde(serialize_with = "serialize_logical_op", deserialize_with = "deserialize_logical_op")]
     op: Rc<dyn LogicalOperator<T>>,
     inputs: Vec<LogicalPlan<T>>,
     required_properties: Vec<PhysicalProperties<T>>,
@@ -53,13 +67,26 @@ impl<T: OptimizerType> LogicalPlan<T> {
         }
     }
 
+    pub fn operator(&self) -> &Rc<dyn LogicalOperator<T>> {
+        &self.op
+    }
+
+    pub fn inputs(&self) -> &[LogicalPlan<T>] {
+        &self.inputs
+    }
+
     pub fn required_properties(&self) -> &[PhysicalProperties<T>] {
         &self.required_properties
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct PhysicalPlan<T: OptimizerType> {
+    #[serde(
+        serialize_with = "serialize_physical_op",
+        deserialize_with = "deserialize_physical_op"
+    )]
     op: Rc<dyn PhysicalOperator<T>>,
     inputs: Vec<PhysicalPlan<T>>,
 }
@@ -122,18 +149,35 @@ impl<T: OptimizerType> Plan<T> {
     }
 }
 
-#[derive(Default)]
-pub struct Options {}
+/// Options of an optimization.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Options {
+    param_values: HashMap<u32, Box<dyn ScalarExpression>>, // parameter index -> bound value
+}
+
+impl Options {
+    /// Binds a value to the query parameter at `index`, e.g. `$1`. The plan still refers to the parameter, the bound
+    /// value is only used to estimate its selectivity, like a custom plan of a prepared statement.
+    pub fn with_param_value(mut self, index: u32, value: Box<dyn ScalarExpression>) -> Self {
+        self.param_values.insert(index, value);
+        self
+    }
+
+    /// Returns the value bound to the query parameter at `index`, `None` for an unbound parameter.
+    pub fn param_value(&self, index: u32) -> Option<&dyn ScalarExpression> {
+        self.param_values.get(&index).map(|value| value.as_ref())
+    }
+}
 
 pub struct Optimizer<T: OptimizerType> {
-    _options: Options,
+    options: Options,
     _mark: PhantomData<T>,
 }
 
 impl<T: OptimizerType> Optimizer<T> {
-    pub fn new(_options: Options) -> Optimizer<T> {
+    pub fn new(options: Options) -> Optimizer<T> {
         Optimizer {
-            _options,
+            options,
             _mark: PhantomData,
         }
     }
@@ -145,7 +189,7 @@ impl<T: OptimizerType> Optimizer<T> {
         md_accessor: MdAccessor<T>,
         rule_set: RuleSet<T>,
     ) -> PhysicalPlan<T> {
-        let mut optimizer_ctx = OptimizerContext::new(md_accessor, rule_set);
+        let mut optimizer_ctx = OptimizerContext::new(md_accessor, rule_set, self.options.clone());
         optimizer_ctx.memo_mut().init(plan);
         let mut task_runner = TaskRunner::new();
         let initial_task =
@@ -154,20 +198,48 @@ impl<T: OptimizerType> Optimizer<T> {
         task_runner.run(&mut optimizer_ctx);
         optimizer_ctx.memo().extract_best_plan(&required_properties)
     }
+
+    /// Optimizes the plan like `optimize` and captures a minidump of the optimization: the input plan, the required
+    /// properties, the options, the rules and the metadata retrieved from `md_provider`, with the optimized plan.
+    pub fn optimize_with_minidump(
+        &mut self,
+        plan: LogicalPlan<T>,
+        required_properties: Rc<PhysicalProperties<T>>,
+        md_provider: Rc<dyn MdProvider<T>>,
+        rule_set: RuleSet<T>,
+    ) -> (PhysicalPlan<T>, Minidump<T>) {
+        let recording_provider = Rc::new(RecordingMdProvider::new(md_provider));
+        let md_accessor = MdAccessor::new(recording_provider.clone());
+        let input_plan = plan.clone();
+        let rule_set_config = rule_set.config();
+
+        let physical_plan = self.optimize(plan, required_properties.clone(), md_accessor, rule_set);
+        let minidump = Minidump::new(
+            input_plan,
+            required_properties.as_ref().clone(),
+            self.options.clone(),
+            rule_set_config,
+            recording_provider.recorded_metadata(),
+            physical_plan.clone(),
+        );
+        (physical_plan, minidump)
+    }
 }
 
 pub struct OptimizerContext<T: OptimizerType> {
     memo: Memo<T>,
     rule_set: RuleSet<T>,
     md_accessor: MdAccessor<T>,
+    options: Options,
 }
 
 impl<T: OptimizerType> OptimizerContext<T> {
-    fn new(md_accessor: MdAccessor<T>, rule_set: RuleSet<T>) -> Self {
+    fn new(md_accessor: MdAccessor<T>, rule_set: RuleSet<T>, options: Options) -> Self {
         OptimizerContext {
             memo: Memo::new(),
             md_accessor,
             rule_set,
+            options,
         }
     }
 
@@ -190,6 +262,10 @@ impl<T: OptimizerType> OptimizerContext<T> {
     pub fn md_accessor(&self) -> &MdAccessor<T> {
         &self.md_accessor
     }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
 }
 
 #[repr(transparent)]
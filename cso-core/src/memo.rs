@@ -82,7 +82,9 @@ impl<T: OptimizerType> GroupPlan<T> {
 
         let md_accessor = optimizer_ctx.md_accessor();
         let input_stats = input_stats.as_slice();
-        self.op.logical_op().derive_statistics(md_accessor, input_stats)
+        self.op
+            .logical_op()
+            .derive_statistics(md_accessor, optimizer_ctx.options(), input_stats)
     }
 
     pub fn get_output_prop(&self, reqd_prop: &PhysicalProperties<T>) -> &Rc<PhysicalProperties<T>> {
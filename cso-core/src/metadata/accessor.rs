@@ -17,11 +17,17 @@ impl<T: OptimizerType> MdAccessor<T> {
         }
     }
 
+    /// Retrieves the current version of the object `md_id` names, the version retrieved first is kept for the rest of
+    /// the optimization.
     pub fn retrieve_metadata(&self, md_id: &T::MdId) -> Option<Box<dyn Metadata>> {
         let mut md_cache = self.md_cache.borrow_mut();
         match md_cache.get(md_id) {
             Some(md) => Some(md.clone()),
-            None => match self.md_provider.retrieve_metadata(md_id) {
+            None => match self
+                .md_provider
+                .current_version(md_id)
+                .and_then(|current| self.md_provider.retrieve_metadata(&current))
+            {
                 Some(md) => {
                     md_cache.insert(md_id.clone(), md.clone());
                     Some(md)
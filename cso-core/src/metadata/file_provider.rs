@@ -0,0 +1,118 @@
+use crate::metadata::provider::MdProvider;
+use crate::metadata::{MdCache, MdEntry, Metadata};
+use crate::OptimizerType;
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Validates the metadata loaded by a `FileMdProvider`, returns the reason the metadata is rejected.
+pub type MdValidator<T> = Box<dyn Fn(&MdCache<T>) -> Result<(), String>>;
+
+/// The metadata loaded from the files of a directory.
+struct Catalog<T: OptimizerType> {
+    md_cache: MdCache<T>,
+    stamps: Vec<(PathBuf, SystemTime, u64)>, // path, modification time and length of the files
+}
+
+/// A provider of the metadata stored in the JSON files of a directory.
+///
+/// A file holds one metadata object, `{"mdid": ..., "metadata": ...}`, or a catalog, an array of metadata objects
+/// like a serialized `MdCache`.
+/// The files are loaded and validated together: a file that does not parse, a metadata id defined twice or metadata
+/// rejected by the validator fails the whole load.
+pub struct FileMdProvider<T: OptimizerType> {
+    dir: PathBuf,
+    validator: MdValidator<T>,
+    catalog: RefCell<Catalog<T>>,
+}
+
+impl<T: OptimizerType> FileMdProvider<T> {
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        Self::with_validator(dir, Box::new(|_| Ok(())))
+    }
+
+    pub fn with_validator(dir: impl Into<PathBuf>, validator: MdValidator<T>) -> io::Result<Self> {
+        let dir = dir.into();
+        let catalog = load_catalog(&dir, &validator)?;
+        Ok(Self {
+            dir,
+            validator,
+            catalog: RefCell::new(catalog),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Loads the files again. The loaded metadata is kept if the files fail to load.
+    pub fn reload(&self) -> io::Result<()> {
+        let catalog = load_catalog(&self.dir, &self.validator)?;
+        *self.catalog.borrow_mut() = catalog;
+        Ok(())
+    }
+
+    /// Loads the files again if a file was added, removed or modified since they were loaded, returns whether they
+    /// were loaded.
+    pub fn reload_if_changed(&self) -> io::Result<bool> {
+        if file_stamps(&self.dir)? == self.catalog.borrow().stamps {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
+}
+
+impl<T: OptimizerType> MdProvider<T> for FileMdProvider<T> {
+    fn retrieve_metadata(&self, md_id: &T::MdId) -> Option<Box<dyn Metadata>> {
+        self.catalog.borrow().md_cache.get(md_id).cloned()
+    }
+
+    fn current_version(&self, md_id: &T::MdId) -> Option<T::MdId> {
+        self.catalog.borrow().md_cache.current_version(md_id).cloned()
+    }
+}
+
+/// Returns the JSON files of the directory with their modification times and lengths, ordered by path.
+fn file_stamps(dir: &Path) -> io::Result<Vec<(PathBuf, SystemTime, u64)>> {
+    let mut stamps = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|extension| extension == "json") {
+            let file_md = fs::metadata(&path)?;
+            stamps.push((path, file_md.modified()?, file_md.len()));
+        }
+    }
+    stamps.sort();
+    Ok(stamps)
+}
+
+fn load_catalog<T: OptimizerType>(dir: &Path, validator: &MdValidator<T>) -> io::Result<Catalog<T>> {
+    let stamps = file_stamps(dir)?;
+    let mut md_cache = MdCache::new();
+    for (path, _, _) in &stamps {
+        let invalid_data =
+            |reason: String| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), reason));
+
+        let value: serde_json::Value =
+            serde_json::from_slice(&fs::read(path)?).map_err(|e| invalid_data(e.to_string()))?;
+        let entries: Vec<MdEntry<T>> = match value {
+            serde_json::Value::Array(_) => serde_json::from_value(value),
+            _ => serde_json::from_value(value).map(|entry| vec![entry]),
+        }
+        .map_err(|e| invalid_data(e.to_string()))?;
+
+        for entry in entries {
+            let mdid = entry.mdid.clone();
+            if md_cache.insert(entry.mdid, entry.metadata).is_some() {
+                return Err(invalid_data(format!("duplicate metadata id {:?}", mdid)));
+            }
+        }
+    }
+
+    validator(&md_cache)
+        .map_err(|reason| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", dir.display(), reason)))?;
+    Ok(Catalog { md_cache, stamps })
+}
@@ -0,0 +1,143 @@
+use crate::metadata::{MdProvider, Metadata};
+use crate::OptimizerType;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+
+struct LruEntry {
+    md: Box<dyn Metadata>,
+    bytes: usize,
+    last_used: u64,
+}
+
+struct LruState<T: OptimizerType> {
+    entries: HashMap<T::MdId, LruEntry>,
+    lru: BTreeMap<u64, T::MdId>, // last use -> mdid, least recently used first
+    clock: u64,
+    bytes: usize,
+}
+
+impl<T: OptimizerType> LruState<T> {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn remove(&mut self, md_id: &T::MdId) -> bool {
+        match self.entries.remove(md_id) {
+            Some(entry) => {
+                self.lru.remove(&entry.last_used);
+                self.bytes -= entry.bytes;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A metadata cache bounded by the number of its entries and by their size, evicting the least recently used
+/// entries. The size of metadata is its `Metadata::size_hint`.
+///
+/// The cache is meant to be shared by the optimizations of a thread through a `CachingMdProvider`, its entries are
+/// kept until they are evicted or invalidated. It is single-threaded like the metadata it holds, which is neither
+/// `Send` nor `Sync`: a planner running on several threads keeps a cache per thread.
+pub struct LruMdCache<T: OptimizerType> {
+    max_entries: usize,
+    max_bytes: usize,
+    state: RefCell<LruState<T>>,
+}
+
+impl<T: OptimizerType> LruMdCache<T> {
+    pub fn new(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            max_entries,
+            max_bytes,
+            state: RefCell::new(LruState {
+                entries: HashMap::new(),
+                lru: BTreeMap::new(),
+                clock: 0,
+                bytes: 0,
+            }),
+        }
+    }
+
+    /// Returns the cached metadata and marks it as recently used.
+    pub fn get(&self, md_id: &T::MdId) -> Option<Box<dyn Metadata>> {
+        let mut state = self.state.borrow_mut();
+        let now = state.tick();
+        let entry = state.entries.get_mut(md_id)?;
+        let last_used = std::mem::replace(&mut entry.last_used, now);
+        let md = entry.md.clone();
+        let md_id = state.lru.remove(&last_used).expect("expect lru entry");
+        state.lru.insert(now, md_id);
+        Some(md)
+    }
+
+    /// Caches the metadata, evicting the least recently used entries beyond the bounds. Metadata larger than the
+    /// cache is not cached.
+    pub fn insert(&self, md_id: T::MdId, md: Box<dyn Metadata>) {
+        let bytes = md.size_hint();
+        let mut state = self.state.borrow_mut();
+        state.remove(&md_id);
+        if bytes > self.max_bytes || self.max_entries == 0 {
+            return;
+        }
+
+        while state.entries.len() >= self.max_entries || state.bytes + bytes > self.max_bytes {
+            let (_, lru_md_id) = state.lru.pop_first().expect("expect lru entry");
+            let entry = state.entries.remove(&lru_md_id).expect("expect cached metadata");
+            state.bytes -= entry.bytes;
+        }
+
+        let last_used = state.tick();
+        state.lru.insert(last_used, md_id.clone());
+        state.entries.insert(md_id, LruEntry { md, bytes, last_used });
+        state.bytes += bytes;
+    }
+
+    /// Removes the metadata from the cache, returns whether it was cached.
+    pub fn invalidate(&self, md_id: &T::MdId) -> bool {
+        self.state.borrow_mut().remove(md_id)
+    }
+
+    /// Removes the metadata whose ids match the predicate, returns the number of removed entries.
+    pub fn invalidate_if(&self, predicate: impl Fn(&T::MdId) -> bool) -> usize {
+        // the predicate may use the cache, e.g. through a provider
+        let md_ids: Vec<_> = self.state.borrow().entries.keys().cloned().collect();
+        let md_ids: Vec<_> = md_ids.into_iter().filter(|md_id| predicate(md_id)).collect();
+        let mut state = self.state.borrow_mut();
+        for md_id in &md_ids {
+            state.remove(md_id);
+        }
+        md_ids.len()
+    }
+
+    /// Removes the metadata that is not current anymore according to the provider, e.g. statistics replaced by a new
+    /// version after ANALYZE, returns the number of removed entries.
+    pub fn invalidate_stale(&self, md_provider: &dyn MdProvider<T>) -> usize {
+        self.invalidate_if(|md_id| !md_provider.is_current(md_id))
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.state.borrow_mut();
+        state.entries.clear();
+        state.lru.clear();
+        state.bytes = 0;
+    }
+
+    pub fn contains(&self, md_id: &T::MdId) -> bool {
+        self.state.borrow().entries.contains_key(md_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.borrow().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.borrow().entries.is_empty()
+    }
+
+    /// Returns the size of the cached metadata.
+    pub fn bytes(&self) -> usize {
+        self.state.borrow().bytes
+    }
+}
@@ -1,21 +1,44 @@
 mod accessor;
+mod file_provider;
+mod lru_cache;
 mod provider;
 mod statistics;
 
 pub use self::accessor::MdAccessor;
-pub use self::provider::{CachedMdProvider, MdProvider};
+pub use self::file_provider::{FileMdProvider, MdValidator};
+pub use self::lru_cache::LruMdCache;
+pub use self::provider::{CachedMdProvider, CachingMdProvider, LayeredMdProvider, MdProvider, RecordingMdProvider};
 pub use self::statistics::Stats;
 
 use crate::any::AsAny;
 use crate::OptimizerType;
 use dyn_clonable::clonable;
-use serde::{Deserialize, Serialize};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 use std::fmt::Debug;
 
 #[typetag::serde(tag = "type")]
 #[clonable]
-pub trait Metadata: AsAny + Clone + Debug {}
+pub trait Metadata: AsAny + Clone + Debug {
+    /// Returns an estimate of the bytes held by the metadata, used to bound the size of a metadata cache. The
+    /// default only counts the inline size of the value, metadata owning heap data should add it.
+    fn size_hint(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+}
+
+/// A metadata id naming a version of a metadata object.
+///
+/// Metadata references other metadata by unversioned ids, resolved to the current version of the object by
+/// `MdProvider::current_version`, so that a new version of an object does not change the objects referencing it.
+pub trait VersionedMdId {
+    /// Returns the id of the object without a version.
+    fn unversioned(&self) -> Self;
+
+    /// Returns the version the id names, later versions are greater.
+    fn version(&self) -> u64;
+}
 
 impl dyn Metadata {
     #[inline]
@@ -24,14 +47,40 @@ impl dyn Metadata {
     }
 }
 
+/// A metadata object with its id, the serialized form of the entries of a metadata cache.
 #[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub(crate) struct MdEntry<T: OptimizerType> {
+    pub(crate) mdid: T::MdId,
+    pub(crate) metadata: Box<dyn Metadata>,
+}
+
+#[derive(Serialize)]
+#[serde(bound = "")]
+struct MdEntryRef<'a, T: OptimizerType> {
+    mdid: &'a T::MdId,
+    metadata: &'a dyn Metadata,
+}
+
+/// A metadata cache, serialized as an array of `{"mdid": ..., "metadata": ...}` objects so that metadata ids do not
+/// have to be map keys.
+#[derive(Clone)]
 pub struct MdCache<T: OptimizerType> {
     cache: HashMap<T::MdId, Box<dyn Metadata>>,
+    versions: HashMap<T::MdId, T::MdId>, // unversioned id -> id of the latest version
 }
 
 impl<T: OptimizerType> MdCache<T> {
     pub fn new() -> Self {
-        Self { cache: HashMap::new() }
+        Self {
+            cache: HashMap::new(),
+            versions: HashMap::new(),
+        }
+    }
+
+    /// Returns the id of the latest cached version of the object `key` names a version of.
+    pub fn current_version(&self, key: &T::MdId) -> Option<&T::MdId> {
+        self.versions.get(&key.unversioned())
     }
 
     pub fn get(&self, key: &T::MdId) -> Option<&Box<dyn Metadata>> {
@@ -39,6 +88,44 @@ impl<T: OptimizerType> MdCache<T> {
     }
 
     pub fn insert(&mut self, key: T::MdId, val: Box<dyn Metadata>) -> Option<Box<dyn Metadata>> {
+        let current = self.versions.entry(key.unversioned()).or_insert_with(|| key.clone());
+        if current.version() < key.version() {
+            *current = key.clone();
+        }
         self.cache.insert(key, val)
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&T::MdId, &Box<dyn Metadata>)> {
+        self.cache.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+impl<T: OptimizerType> Serialize for MdCache<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.cache.iter().map(|(mdid, metadata)| MdEntryRef::<T> {
+            mdid,
+            metadata: metadata.as_ref(),
+        }))
+    }
+}
+
+impl<'de, T: OptimizerType> Deserialize<'de> for MdCache<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut md_cache = MdCache::new();
+        for entry in Vec::<MdEntry<T>>::deserialize(deserializer)? {
+            let mdid = entry.mdid.clone();
+            if md_cache.insert(entry.mdid, entry.metadata).is_some() {
+                return Err(D::Error::custom(format!("duplicate metadata id {:?}", mdid)));
+            }
+        }
+        Ok(md_cache)
+    }
 }
@@ -1,8 +1,20 @@
-use crate::metadata::{MdCache, Metadata};
+use crate::metadata::{LruMdCache, MdCache, Metadata};
 use crate::OptimizerType;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 pub trait MdProvider<T: OptimizerType> {
     fn retrieve_metadata(&self, md_id: &T::MdId) -> Option<Box<dyn Metadata>>;
+
+    /// Returns the id of the current version of the object `md_id` names a version of, `None` if the provider does
+    /// not provide the object. Unversioned ids stored in metadata are resolved with it.
+    fn current_version(&self, md_id: &T::MdId) -> Option<T::MdId>;
+
+    /// Returns whether `md_id` names the current version of its object. The ids of versioned metadata change with
+    /// their version, so metadata cached with an id stays valid as long as the id is current.
+    fn is_current(&self, md_id: &T::MdId) -> bool {
+        self.current_version(md_id).as_ref() == Some(md_id)
+    }
 }
 
 pub struct CachedMdProvider<T: OptimizerType> {
@@ -22,4 +34,100 @@ impl<T: OptimizerType> MdProvider<T> for CachedMdProvider<T> {
             None => None,
         }
     }
+
+    fn current_version(&self, md_id: &T::MdId) -> Option<T::MdId> {
+        self.md_cache.current_version(md_id).cloned()
+    }
+}
+
+/// A provider recording the metadata retrieved from another provider, e.g. to capture a minidump.
+pub struct RecordingMdProvider<T: OptimizerType> {
+    md_provider: Rc<dyn MdProvider<T>>,
+    recorded: RefCell<MdCache<T>>,
+}
+
+impl<T: OptimizerType> RecordingMdProvider<T> {
+    pub fn new(md_provider: Rc<dyn MdProvider<T>>) -> Self {
+        Self {
+            md_provider,
+            recorded: RefCell::new(MdCache::new()),
+        }
+    }
+
+    /// Returns the metadata retrieved so far, missing metadata is not recorded.
+    pub fn recorded_metadata(&self) -> MdCache<T> {
+        self.recorded.borrow().clone()
+    }
+}
+
+impl<T: OptimizerType> MdProvider<T> for RecordingMdProvider<T> {
+    fn retrieve_metadata(&self, md_id: &T::MdId) -> Option<Box<dyn Metadata>> {
+        let md = self.md_provider.retrieve_metadata(md_id)?;
+        self.recorded.borrow_mut().insert(md_id.clone(), md.clone());
+        Some(md)
+    }
+
+    fn current_version(&self, md_id: &T::MdId) -> Option<T::MdId> {
+        self.md_provider.current_version(md_id)
+    }
+}
+
+/// A provider caching the metadata retrieved from another provider in a shared cache.
+pub struct CachingMdProvider<T: OptimizerType> {
+    md_cache: Rc<LruMdCache<T>>,
+    md_provider: Rc<dyn MdProvider<T>>,
+}
+
+impl<T: OptimizerType> CachingMdProvider<T> {
+    pub fn new(md_cache: Rc<LruMdCache<T>>, md_provider: Rc<dyn MdProvider<T>>) -> Self {
+        Self { md_cache, md_provider }
+    }
+
+    pub fn md_cache(&self) -> &Rc<LruMdCache<T>> {
+        &self.md_cache
+    }
+}
+
+impl<T: OptimizerType> MdProvider<T> for CachingMdProvider<T> {
+    fn retrieve_metadata(&self, md_id: &T::MdId) -> Option<Box<dyn Metadata>> {
+        if let Some(md) = self.md_cache.get(md_id) {
+            return Some(md);
+        }
+        let md = self.md_provider.retrieve_metadata(md_id)?;
+        self.md_cache.insert(md_id.clone(), md.clone());
+        Some(md)
+    }
+
+    /// Asks the backing provider, the cached metadata may be stale.
+    fn current_version(&self, md_id: &T::MdId) -> Option<T::MdId> {
+        self.md_provider.current_version(md_id)
+    }
+}
+
+/// A chain of providers, metadata is retrieved from the first provider that has it.
+///
+/// E.g. the overrides of a query in a `CachedMdProvider`, then a `CachingMdProvider` in front of the catalog.
+pub struct LayeredMdProvider<T: OptimizerType> {
+    layers: Vec<Rc<dyn MdProvider<T>>>,
+}
+
+impl<T: OptimizerType> LayeredMdProvider<T> {
+    pub fn new(layers: Vec<Rc<dyn MdProvider<T>>>) -> Self {
+        Self { layers }
+    }
+
+    pub fn layers(&self) -> &[Rc<dyn MdProvider<T>>] {
+        &self.layers
+    }
+}
+
+impl<T: OptimizerType> MdProvider<T> for LayeredMdProvider<T> {
+    fn retrieve_metadata(&self, md_id: &T::MdId) -> Option<Box<dyn Metadata>> {
+        self.layers.iter().find_map(|layer| layer.retrieve_metadata(md_id))
+    }
+
+    /// The version of the first provider that has the object, the versions of the layers below are overridden.
+    fn current_version(&self, md_id: &T::MdId) -> Option<T::MdId> {
+        self.layers.iter().find_map(|layer| layer.current_version(md_id))
+    }
 }
@@ -1,8 +1,14 @@
 use crate::any::AsAny;
+use crate::metadata::Metadata;
 use std::fmt::Debug;
 use std::rc::Rc;
 
 pub trait Stats: Debug + AsAny {
     fn should_update(&self, new_stats: &Rc<dyn Stats>) -> bool;
     fn output_row_count(&self) -> u64;
+
+    /// Returns the statistics of the column with the global column id `col_id`, if they are known.
+    fn column_stats(&self, _col_id: u32) -> Option<&dyn Metadata> {
+        None
+    }
 }
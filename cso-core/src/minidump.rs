@@ -0,0 +1,97 @@
+//! Minidumps of optimizations.
+//!
+//! A minidump captures everything an optimization depends on, so that a plan can be reproduced away from the catalog
+//! it was optimized with: the input plan, the required properties, the options, the rules and the metadata the
+//! optimizer retrieved. It is written as a JSON document.
+
+use crate::metadata::{CachedMdProvider, MdAccessor, MdCache};
+use crate::property::PhysicalProperties;
+use crate::rule::{RuleSet, RuleSetConfig, UnknownRule};
+use crate::{LogicalPlan, Optimizer, OptimizerType, Options, PhysicalPlan};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+use std::rc::Rc;
+
+/// A captured optimization, see `Optimizer::optimize_with_minidump`.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Minidump<T: OptimizerType> {
+    plan: LogicalPlan<T>,
+    required_properties: PhysicalProperties<T>,
+    options: Options,
+    rule_set: RuleSetConfig,
+    metadata: MdCache<T>,
+    physical_plan: PhysicalPlan<T>,
+}
+
+impl<T: OptimizerType> Minidump<T> {
+    pub fn new(
+        plan: LogicalPlan<T>,
+        required_properties: PhysicalProperties<T>,
+        options: Options,
+        rule_set: RuleSetConfig,
+        metadata: MdCache<T>,
+        physical_plan: PhysicalPlan<T>,
+    ) -> Self {
+        Minidump {
+            plan,
+            required_properties,
+            options,
+            rule_set,
+            metadata,
+            physical_plan,
+        }
+    }
+
+    pub fn plan(&self) -> &LogicalPlan<T> {
+        &self.plan
+    }
+
+    pub fn required_properties(&self) -> &PhysicalProperties<T> {
+        &self.required_properties
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    pub fn rule_set(&self) -> &RuleSetConfig {
+        &self.rule_set
+    }
+
+    pub fn metadata(&self) -> &MdCache<T> {
+        &self.metadata
+    }
+
+    /// Returns the recorded optimized plan.
+    pub fn physical_plan(&self) -> &PhysicalPlan<T> {
+        &self.physical_plan
+    }
+
+    pub fn write_json<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, self).map_err(io::Error::from)
+    }
+
+    pub fn read_json<R: Read>(reader: R) -> io::Result<Self> {
+        serde_json::from_reader(reader).map_err(io::Error::from)
+    }
+
+    /// Optimizes the captured plan again with the captured metadata, `rule_set` holds the available rules the
+    /// captured rules are selected from. Fails if a captured rule is not in `rule_set`.
+    pub fn replay(&self, rule_set: &RuleSet<T>) -> Result<PhysicalPlan<T>, UnknownRule> {
+        let rule_set = rule_set.select(&self.rule_set)?;
+        let md_provider = Rc::new(CachedMdProvider::new(self.metadata.clone()));
+        let mut optimizer = Optimizer::new(self.options.clone());
+        Ok(optimizer.optimize(
+            self.plan.clone(),
+            Rc::new(self.required_properties.clone()),
+            MdAccessor::new(md_provider),
+            rule_set,
+        ))
+    }
+
+    /// Returns whether replaying the minidump reproduces the recorded plan.
+    pub fn verify(&self, rule_set: &RuleSet<T>) -> Result<bool, UnknownRule> {
+        Ok(self.replay(rule_set)? == self.physical_plan)
+    }
+}
@@ -3,15 +3,21 @@ use crate::cost::Cost;
 use crate::metadata::MdAccessor;
 use crate::metadata::Stats;
 use crate::property::PhysicalProperties;
-use crate::{ColumnRefSet, OptimizerType, Plan};
+use crate::{ColumnRefSet, OptimizerType, Options, Plan};
 use dyn_clonable::clonable;
+use serde::{Deserializer, Serializer};
 use std::fmt::Debug;
 use std::rc::Rc;
 
 pub trait LogicalOperator<T: OptimizerType>: AsAny + Debug {
     fn name(&self) -> &str;
     fn operator_id(&self) -> &T::OperatorId;
-    fn derive_statistics(&self, _md_accessor: &MdAccessor<T>, input_stats: &[Rc<dyn Stats>]) -> Rc<dyn Stats>;
+    fn derive_statistics(
+        &self,
+        md_accessor: &MdAccessor<T>,
+        options: &Options,
+        input_stats: &[Rc<dyn Stats>],
+    ) -> Rc<dyn Stats>;
     /// Returns the columns in the table needed for the current operator.
     fn derive_output_columns(&self, inputs: &[Plan<T>], column_set: &mut ColumnRefSet);
 }
@@ -87,3 +93,45 @@ impl<T: OptimizerType> Operator<T> {
         }
     }
 }
+
+/// Serializes and deserializes the operators of an optimizer type.
+///
+/// typetag does not support generic traits like `LogicalOperator<T>`, so an optimizer type serializes its operators
+/// through non-generic typetag traits of its own.
+pub trait OperatorSerde<T: OptimizerType> {
+    fn serialize_logical<S: Serializer>(op: &dyn LogicalOperator<T>, serializer: S) -> Result<S::Ok, S::Error>;
+
+    fn deserialize_logical<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rc<dyn LogicalOperator<T>>, D::Error>;
+
+    fn serialize_physical<S: Serializer>(op: &dyn PhysicalOperator<T>, serializer: S) -> Result<S::Ok, S::Error>;
+
+    fn deserialize_physical<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Rc<dyn PhysicalOperator<T>>, D::Error>;
+}
+
+pub(crate) fn serialize_logical_op<T: OptimizerType, S: Serializer>(
+    op: &Rc<dyn LogicalOperator<T>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    T::OperatorSerde::serialize_logical(op.as_ref(), serializer)
+}
+
+pub(crate) fn deserialize_logical_op<'de, T: OptimizerType, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Rc<dyn LogicalOperator<T>>, D::Error> {
+    T::OperatorSerde::deserialize_logical(deserializer)
+}
+
+pub(crate) fn serialize_physical_op<T: OptimizerType, S: Serializer>(
+    op: &Rc<dyn PhysicalOperator<T>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    T::OperatorSerde::serialize_physical(op.as_ref(), serializer)
+}
+
+pub(crate) fn deserialize_physical_op<'de, T: OptimizerType, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Rc<dyn PhysicalOperator<T>>, D::Error> {
+    T::OperatorSerde::deserialize_physical(deserializer)
+}
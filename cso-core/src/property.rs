@@ -2,6 +2,8 @@ use crate::any::AsAny;
 use crate::memo::{GroupPlan, GroupRef};
 use crate::OptimizerType;
 use dyn_clonable::clonable;
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
@@ -39,6 +41,31 @@ impl<T: OptimizerType> Hash for dyn PhysicalProperty<T> {
     }
 }
 
+/// Serializes and deserializes the physical properties of an optimizer type.
+///
+/// Like `OperatorSerde`, an optimizer type serializes its properties through a non-generic typetag trait of its own.
+pub trait PropertySerde<T: OptimizerType> {
+    fn serialize<S: Serializer>(property: &dyn PhysicalProperty<T>, serializer: S) -> Result<S::Ok, S::Error>;
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Box<dyn PhysicalProperty<T>>, D::Error>;
+}
+
+struct SerializeProperty<'a, T: OptimizerType>(&'a dyn PhysicalProperty<T>);
+
+impl<T: OptimizerType> Serialize for SerializeProperty<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::PropertySerde::serialize(self.0, serializer)
+    }
+}
+
+struct DeserializeProperty<T: OptimizerType>(Box<dyn PhysicalProperty<T>>);
+
+impl<'de, T: OptimizerType> Deserialize<'de> for DeserializeProperty<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(DeserializeProperty(T::PropertySerde::deserialize(deserializer)?))
+    }
+}
+
 #[derive(Clone)]
 pub struct LogicalProperties {}
 
@@ -65,9 +92,12 @@ impl<T: OptimizerType> PhysicalProperties<T> {
         match (self.properties.is_empty(), required_prop.properties.is_empty()) {
             (_, true) => true,
             (true, false) => false,
-            (false, false) => self.properties[0].satisfy(required_prop.properties[0].as_ref())
+            (false, false) => self.properties[0].satisfy(required_prop.properties[0].as_ref()),
         }
+    }
 
+    pub fn properties(&self) -> &[Box<dyn PhysicalProperty<T>>] {
+        &self.properties
     }
 
     // TODO: multiple properties
@@ -75,3 +105,22 @@ impl<T: OptimizerType> PhysicalProperties<T> {
         self.properties[0].make_enforcer(group)
     }
 }
+
+impl<T: OptimizerType> Serialize for PhysicalProperties<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.properties.len()))?;
+        for property in &self.properties {
+            seq.serialize_element(&SerializeProperty(property.as_ref()))?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, T: OptimizerType> Deserialize<'de> for PhysicalProperties<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let properties = Vec::<DeserializeProperty<T>>::deserialize(deserializer)?;
+        Ok(PhysicalProperties {
+            properties: properties.into_iter().map(|property| property.0).collect(),
+        })
+    }
+}
@@ -1,8 +1,9 @@
 use crate::memo::{Group, GroupPlan, GroupPlanRef};
 use crate::operator::Operator;
 use crate::{OptimizerContext, OptimizerType, Plan};
+use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::ops::Deref;
 use std::rc::Rc;
 
@@ -143,6 +144,70 @@ impl<T: OptimizerType> RuleSet<T> {
         assert!(rules.iter().all(|x| { x.is_implementation() }));
         self.implement_rules = rules;
     }
+
+    /// Returns the names of the rules of the rule set.
+    pub fn config(&self) -> RuleSetConfig {
+        let names = |rules: &[RuleRef<T>]| rules.iter().map(|rule| rule.name().to_string()).collect();
+        RuleSetConfig {
+            transform_rules: names(&self.transform_rules),
+            implement_rules: names(&self.implement_rules),
+        }
+    }
+
+    /// Returns the rule set of the rules named by the configuration, in its order, or the first rule that is not in
+    /// this rule set.
+    pub fn select(&self, config: &RuleSetConfig) -> Result<RuleSet<T>, UnknownRule> {
+        let select = |rules: &[RuleRef<T>], names: &[String]| {
+            names
+                .iter()
+                .map(|name| {
+                    let rule = rules.iter().find(|rule| rule.name() == name);
+                    rule.cloned().ok_or_else(|| UnknownRule { name: name.clone() })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(RuleSet {
+            transform_rules: select(&self.transform_rules, &config.transform_rules)?,
+            implement_rules: select(&self.implement_rules, &config.implement_rules)?,
+        })
+    }
+}
+
+/// A rule named by a `RuleSetConfig` that is missing from the rule set it is selected from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownRule {
+    name: String,
+}
+
+impl UnknownRule {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for UnknownRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown rule: {}", self.name)
+    }
+}
+
+impl std::error::Error for UnknownRule {}
+
+/// The configuration of a rule set, the names of its rules.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleSetConfig {
+    transform_rules: Vec<String>,
+    implement_rules: Vec<String>,
+}
+
+impl RuleSetConfig {
+    pub fn transform_rules(&self) -> &[String] {
+        &self.transform_rules
+    }
+
+    pub fn implement_rules(&self) -> &[String] {
+        &self.implement_rules
+    }
 }
 
 pub(crate) struct Binding<'a, T: OptimizerType> {
@@ -1,8 +1,70 @@
-pub const COST_INIT_SCAN_FACTOR: f64 = 431.0; // scan initialization cost factor
-pub const COST_TABLE_SCAN_COST_UNIT: f64 = 5.50e-07; // table scan cost per tuple
-pub const COST_INDEX_FILTER_COST_UNIT: f64 = 1.65e-04; // index filtering cost unit
-pub const COST_INDEX_SCAN_TUP_COST_UNIT: f64 = 3.66e-06; // index scan cost unit per tuple per width
-pub const COST_INDEX_SCAN_TUP_RANDOM_FACTOR: f64 = 6.0; // index scan random IO factor
+use crate::statistics::{RelationStorage, Statistics};
+use cso_core::cost::Cost;
+use cso_core::metadata::Stats;
+
+pub const PAGE_SIZE: u64 = 8192; // bytes of a page of a relation or an index
+pub const COST_SEQ_PAGE_COST_UNIT: f64 = 1.0; // cost of reading a page sequentially
+pub const COST_RANDOM_PAGE_COST_UNIT: f64 = 4.0; // cost of reading a page at random
+pub const COST_CPU_TUPLE_COST_UNIT: f64 = 0.01; // cost of processing a row of a relation
+pub const COST_CPU_INDEX_TUPLE_COST_UNIT: f64 = 0.005; // cost of processing an entry of an index
+pub const COST_CPU_OPERATOR_COST_UNIT: f64 = 0.0025; // cost of evaluating an operator, e.g. comparing an index key
+pub const COST_DECOMPRESS_PAGE_COST_UNIT: f64 = 0.05; // cost of decompressing a page worth of uncompressed data
 pub const COST_FILTER_COL_COST_UNIT: f64 = 3.29e-05; // filter column cost unit
 pub const COST_TUP_DEFAULT_PROC_COST_UNIT: f64 = 1.0e-06; // cost for processing per tuple with unit width
 pub const COST_SORT_TUP_WIDTH_COST_UNIT: f64 = 5.67e-06; // sorting cost per tuple with unit width
+
+/// The cost of reading rows from storage, split into pages read sequentially, pages read at random and CPU cost.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct IoCost {
+    pub seq_pages: f64,
+    pub random_pages: f64,
+    pub cpu: f64,
+}
+
+impl IoCost {
+    pub fn new(seq_pages: f64, random_pages: f64, cpu: f64) -> Self {
+        Self {
+            seq_pages,
+            random_pages,
+            cpu,
+        }
+    }
+
+    /// Weighs the pages by the cost of reading them.
+    pub fn cost(&self) -> Cost {
+        Cost::new(self.seq_pages * COST_SEQ_PAGE_COST_UNIT + self.random_pages * COST_RANDOM_PAGE_COST_UNIT + self.cpu)
+    }
+}
+
+impl std::ops::Add for IoCost {
+    type Output = IoCost;
+
+    fn add(self, rhs: IoCost) -> Self::Output {
+        IoCost::new(
+            self.seq_pages + rhs.seq_pages,
+            self.random_pages + rhs.random_pages,
+            self.cpu + rhs.cpu,
+        )
+    }
+}
+
+/// Returns the row count and the storage of the relation the rows described by `stats` are read from. Rows of unknown
+/// storage are assumed to fill a page each.
+pub fn relation_storage(stats: &dyn Stats) -> (u64, RelationStorage) {
+    let stats_of_relation = stats.as_any().downcast_ref::<Statistics>().and_then(|stats| {
+        let row_count = stats.relation_row_count()?;
+        Some((row_count, stats.relation_storage()?))
+    });
+    stats_of_relation.unwrap_or_else(|| {
+        let row_count = stats.output_row_count();
+        (row_count, RelationStorage::new(row_count.max(1), PAGE_SIZE as u32))
+    })
+}
+
+/// Returns the CPU cost of decompressing `pages` pages of a relation stored as `storage`.
+pub fn decompression_cost(storage: &RelationStorage, pages: f64) -> f64 {
+    match storage.compression_ratio() > 1.0 {
+        true => pages * storage.compression_ratio() * COST_DECOMPRESS_PAGE_COST_UNIT,
+        false => 0.0,
+    }
+}
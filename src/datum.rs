@@ -1,7 +1,447 @@
+use crate::expression::Const;
+use cso_core::datatype::DataType;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 
-/// Datum is the struct to represent a single value in optimizer.
+pub use cso_core::datatype::MAX_DECIMAL_PRECISION;
+
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// Decimal is an exact decimal number `mantissa * 10^-scale`.
+///
+/// Decimals with the same value are equal whatever their scale, `1.50` equals `1.5`.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u8,
+}
+
+impl Decimal {
+    pub fn new(mantissa: i128, scale: u8) -> Self {
+        assert!(scale <= MAX_DECIMAL_PRECISION, "decimal scale out of range");
+        assert!(
+            mantissa.unsigned_abs() < 10u128.pow(MAX_DECIMAL_PRECISION as u32),
+            "decimal precision out of range"
+        );
+        Self { mantissa, scale }
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Returns the number of digits of the decimal, at least its scale.
+    pub fn precision(&self) -> u8 {
+        let digits = self.mantissa.unsigned_abs().checked_ilog10().map_or(1, |log| log + 1) as u8;
+        digits.max(self.scale)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+
+    /// Returns the decimal with `scale` digits after the decimal point, rounded half away from zero, or `None` if it
+    /// does not fit in `precision` digits.
+    pub fn rescale(&self, precision: u8, scale: u8) -> Option<Decimal> {
+        let mantissa = if scale >= self.scale {
+            self.mantissa
+                .checked_mul(10i128.checked_pow((scale - self.scale) as u32)?)?
+        } else {
+            let unit = 10i128.pow((self.scale - scale) as u32);
+            let (quotient, remainder) = (self.mantissa / unit, self.mantissa % unit);
+            match remainder.unsigned_abs() * 2 >= unit as u128 {
+                true => quotient + self.mantissa.signum(),
+                false => quotient,
+            }
+        };
+        (mantissa.unsigned_abs() < 10u128.pow(precision.min(MAX_DECIMAL_PRECISION) as u32))
+            .then(|| Decimal::new(mantissa, scale))
+    }
+
+    /// Returns the decimal of the same value as `v`, if `v` is finite and has at most `MAX_DECIMAL_PRECISION` digits.
+    fn from_f64_exact(v: f64) -> Option<Decimal> {
+        if !v.is_finite() {
+            return None;
+        }
+        if v == 0.0 {
+            return Some(Decimal::from(0));
+        }
+        // v is mantissa * 2^exponent, with an odd mantissa
+        let bits = v.to_bits();
+        let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+        let (mut mantissa, mut exponent) = match biased_exponent {
+            0 => ((bits & 0xf_ffff_ffff_ffff) as i128, -1074),
+            _ => (
+                ((bits & 0xf_ffff_ffff_ffff) | (1 << 52)) as i128,
+                biased_exponent - 1075,
+            ),
+        };
+        let shift = mantissa.trailing_zeros();
+        mantissa >>= shift;
+        exponent += shift as i32;
+
+        // mantissa * 2^-k is mantissa * 5^k * 10^-k
+        let (mantissa, scale) = match exponent {
+            0.. => (mantissa.checked_mul(2i128.checked_pow(exponent as u32)?)?, 0),
+            _ => {
+                let scale = u8::try_from(-exponent)
+                    .ok()
+                    .filter(|scale| *scale <= MAX_DECIMAL_PRECISION)?;
+                (mantissa.checked_mul(5i128.checked_pow(scale as u32)?)?, scale)
+            }
+        };
+        let mantissa = if v < 0.0 { -mantissa } else { mantissa };
+        (mantissa.unsigned_abs() < 10u128.pow(MAX_DECIMAL_PRECISION as u32)).then(|| Decimal::new(mantissa, scale))
+    }
+
+    /// Returns the decimal rounded half away from zero to an integer, if it fits in an i64.
+    fn round_to_i64(&self) -> Option<i64> {
+        let rounded = self.rescale(MAX_DECIMAL_PRECISION, 0)?;
+        i64::try_from(rounded.mantissa).ok()
+    }
+
+    /// Returns the integer part and the fractional part of the decimal, the fractional part has the decimal scale.
+    fn split(&self) -> (i128, i128) {
+        let unit = 10i128.pow(self.scale as u32);
+        (self.mantissa / unit, self.mantissa % unit)
+    }
+
+    /// Returns the decimal with the smallest scale representing the same value.
+    fn normalize(&self) -> Decimal {
+        let mut decimal = *self;
+        while decimal.scale > 0 && decimal.mantissa % 10 == 0 {
+            decimal.mantissa /= 10;
+            decimal.scale -= 1;
+        }
+        decimal
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(v: i64) -> Self {
+        Decimal::new(v as i128, 0)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Fractional parts are below 10^38 once brought to the same scale, so they never overflow an i128.
+        let (int, frac) = self.split();
+        let (other_int, other_frac) = other.split();
+        let scale = self.scale.max(other.scale);
+        int.cmp(&other_int).then_with(|| {
+            let frac = frac * 10i128.pow((scale - self.scale) as u32);
+            let other_frac = other_frac * 10i128.pow((scale - other.scale) as u32);
+            frac.cmp(&other_frac)
+        })
+    }
+}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let decimal = self.normalize();
+        decimal.mantissa.hash(state);
+        decimal.scale.hash(state);
+    }
+}
+
+/// Datum is the struct to represent a single value in optimizer.
+///
+/// Datums have a total ordering: NULL comes first, then booleans, numbers, strings, bytes, dates, times and
+/// timestamps. Numbers of different types are ordered by value and numbers with the same value are equal, `1`, `1.0`
+/// and `1.00` are equal and so are `-0.0` and `0.0`. A floating-point number with more digits than a decimal holds,
+/// infinities and NaN are ordered after the exact numbers rounding to the same `f64` value.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Datum {
+    Null,
+    Bool(bool),
+    I16(i16),
     I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    Decimal(Decimal),
+    String(String),
+    Bytes(Vec<u8>),
+    Date(i32),      // days since 1970-01-01
+    Time(i64),      // microseconds since midnight
+    Timestamp(i64), // microseconds since 1970-01-01 00:00:00
+}
+
+/// The key datums are ordered and hashed by, numbers of all types share the same kind.
+#[derive(PartialEq, PartialOrd)]
+enum SortKey<'a> {
+    Null,
+    Bool(bool),
+    Number(f64, Option<Decimal>), // f64 value, exact value of integers, decimals and floats that fit in a decimal
+    String(&'a str),
+    Bytes(&'a [u8]),
+    Date(i32),
+    Time(i64),
+    Timestamp(i64),
+}
+
+impl Datum {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Datum::Null)
+    }
+
+    /// Returns the type of the datum, NULL has an unknown type.
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Datum::Null => DataType::Unknown,
+            Datum::Bool(_) => DataType::Boolean,
+            Datum::I16(_) => DataType::Int16,
+            Datum::I32(_) => DataType::Int32,
+            Datum::I64(_) => DataType::Int64,
+            Datum::F32(_) => DataType::Float32,
+            Datum::F64(_) => DataType::Float64,
+            Datum::Decimal(v) => DataType::Decimal {
+                precision: v.precision(),
+                scale: v.scale(),
+            },
+            Datum::String(_) => DataType::String,
+            Datum::Bytes(_) => DataType::Bytes,
+            Datum::Date(_) => DataType::Date,
+            Datum::Time(_) => DataType::Time,
+            Datum::Timestamp(_) => DataType::Timestamp,
+        }
+    }
+
+    /// Converts the datum to `data_type`, returns `None` if the conversion is not supported or the value does not fit
+    /// in the type. Numbers are rounded half away from zero, NULL converts to every type.
+    pub fn cast_to(&self, data_type: &DataType) -> Option<Datum> {
+        if self.is_null() || *data_type == DataType::Unknown {
+            return Some(self.clone());
+        }
+
+        match data_type {
+            DataType::Boolean => match self {
+                Datum::Bool(v) => Some(Datum::Bool(*v)),
+                _ => None,
+            },
+            DataType::Int16 => i16::try_from(self.round_to_i64()?).ok().map(Datum::I16),
+            DataType::Int32 => i32::try_from(self.round_to_i64()?).ok().map(Datum::I32),
+            DataType::Int64 => self.round_to_i64().map(Datum::I64),
+            DataType::Float32 => self.numeric_f64().map(|v| Datum::F32(v as f32)),
+            DataType::Float64 => self.numeric_f64().map(Datum::F64),
+            DataType::Decimal { precision, scale } => {
+                let decimal = match self {
+                    Datum::I16(_) | Datum::I32(_) | Datum::I64(_) => Decimal::from(self.round_to_i64()?),
+                    Datum::Decimal(v) => *v,
+                    Datum::F32(_) | Datum::F64(_) => {
+                        let scaled = (self.numeric_f64()? * 10f64.powi(*scale as i32)).round();
+                        if !scaled.is_finite() || scaled.abs() >= 1e38 {
+                            return None;
+                        }
+                        Decimal::new(scaled as i128, *scale)
+                    }
+                    _ => return None,
+                };
+                decimal.rescale(*precision, *scale).map(Datum::Decimal)
+            }
+            DataType::String => match self {
+                Datum::String(v) => Some(Datum::String(v.clone())),
+                _ => None,
+            },
+            DataType::Bytes => match self {
+                Datum::Bytes(v) => Some(Datum::Bytes(v.clone())),
+                _ => None,
+            },
+            DataType::Date => match self {
+                Datum::Date(v) => Some(Datum::Date(*v)),
+                Datum::Timestamp(v) => i32::try_from(v.div_euclid(MICROS_PER_DAY)).ok().map(Datum::Date),
+                _ => None,
+            },
+            DataType::Time => match self {
+                Datum::Time(v) => Some(Datum::Time(*v)),
+                Datum::Timestamp(v) => Some(Datum::Time(v.rem_euclid(MICROS_PER_DAY))),
+                _ => None,
+            },
+            DataType::Timestamp => match self {
+                Datum::Timestamp(v) => Some(Datum::Timestamp(*v)),
+                Datum::Date(v) => Some(Datum::Timestamp(*v as i64 * MICROS_PER_DAY)),
+                _ => None,
+            },
+            DataType::Unknown => unreachable!(),
+        }
+    }
+
+    /// Returns the value of a numeric datum as a `f64`.
+    fn numeric_f64(&self) -> Option<f64> {
+        match self {
+            Datum::I16(_) | Datum::I32(_) | Datum::I64(_) | Datum::F32(_) | Datum::F64(_) | Datum::Decimal(_) => {
+                self.to_f64()
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a numeric datum rounded half away from zero, if it fits in an i64.
+    fn round_to_i64(&self) -> Option<i64> {
+        match self {
+            Datum::I16(v) => Some(*v as i64),
+            Datum::I32(v) => Some(*v as i64),
+            Datum::I64(v) => Some(*v),
+            Datum::Decimal(v) => v.round_to_i64(),
+            Datum::F32(_) | Datum::F64(_) => {
+                let rounded = self.numeric_f64()?.round();
+                // i64::MAX is not a f64, 2^63 is the first value out of range
+                (rounded.is_finite() && rounded >= -(2f64.powi(63)) && rounded < 2f64.powi(63))
+                    .then_some(rounded as i64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a `f64` if the datum is numeric or temporal, used to interpolate inside histogram
+    /// buckets.
+    pub fn to_f64(&self) -> Option<f64> {
+        match self {
+            Datum::I16(v) => Some(*v as f64),
+            Datum::I32(v) => Some(*v as f64),
+            Datum::I64(v) => Some(*v as f64),
+            Datum::F32(v) => Some(*v as f64),
+            Datum::F64(v) => Some(*v),
+            Datum::Decimal(v) => Some(v.to_f64()),
+            Datum::Date(v) => Some(*v as f64),
+            Datum::Time(v) | Datum::Timestamp(v) => Some(*v as f64),
+            Datum::Null | Datum::Bool(_) | Datum::String(_) | Datum::Bytes(_) => None,
+        }
+    }
+
+    fn sort_key(&self) -> SortKey<'_> {
+        match self {
+            Datum::Null => SortKey::Null,
+            Datum::Bool(v) => SortKey::Bool(*v),
+            Datum::I16(v) => exact_key(Decimal::from(*v as i64)),
+            Datum::I32(v) => exact_key(Decimal::from(*v as i64)),
+            Datum::I64(v) => exact_key(Decimal::from(*v)),
+            Datum::F32(v) => float_key(*v as f64),
+            Datum::F64(v) => float_key(*v),
+            Datum::Decimal(v) => exact_key(*v),
+            Datum::String(v) => SortKey::String(v),
+            Datum::Bytes(v) => SortKey::Bytes(v),
+            Datum::Date(v) => SortKey::Date(*v),
+            Datum::Time(v) => SortKey::Time(*v),
+            Datum::Timestamp(v) => SortKey::Timestamp(*v),
+        }
+    }
+}
+
+/// The key of an exact number, the f64 value of the decimal with the smallest scale so that equal numbers have the
+/// same key.
+fn exact_key<'a>(v: Decimal) -> SortKey<'a> {
+    SortKey::Number(v.normalize().to_f64(), Some(v))
+}
+
+fn float_key<'a>(v: f64) -> SortKey<'a> {
+    match Decimal::from_f64_exact(v) {
+        Some(exact) => exact_key(exact),
+        None => SortKey::Number(v, None),
+    }
+}
+
+impl PartialEq for Datum {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Datum {}
+
+impl PartialOrd for Datum {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Datum {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.sort_key(), other.sort_key()) {
+            (SortKey::Number(f, exact), SortKey::Number(other_f, other_exact)) => {
+                // the f64 values order all numbers but those rounded to the same f64
+                f.total_cmp(&other_f).then_with(|| match (exact, other_exact) {
+                    (Some(exact), Some(other_exact)) => exact.cmp(&other_exact),
+                    (exact, other_exact) => exact.is_none().cmp(&other_exact.is_none()),
+                })
+            }
+            (key, other_key) => key.partial_cmp(&other_key).expect("only numbers are partially ordered"),
+        }
+    }
+}
+
+impl Hash for Datum {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self.sort_key() {
+            SortKey::Null => 0.hash(state),
+            SortKey::Bool(v) => (1, v).hash(state),
+            SortKey::Number(_, Some(exact)) => (2, exact).hash(state),
+            SortKey::Number(f, None) => (3, f.to_bits()).hash(state),
+            SortKey::String(v) => (4, v).hash(state),
+            SortKey::Bytes(v) => (5, v).hash(state),
+            SortKey::Date(v) => (6, v).hash(state),
+            SortKey::Time(v) => (7, v).hash(state),
+            SortKey::Timestamp(v) => (8, v).hash(state),
+        }
+    }
+}
+
+impl From<&Const> for Datum {
+    fn from(value: &Const) -> Self {
+        match value {
+            Const::Null => Datum::Null,
+            Const::Bool(v) => Datum::Bool(*v),
+            Const::Int16(v) => Datum::I16(*v),
+            Const::Int32(v) => Datum::I32(*v),
+            Const::Int64(v) => Datum::I64(*v),
+            Const::Float32(v) => Datum::F32(*v),
+            Const::Float64(v) => Datum::F64(*v),
+            Const::Decimal(v) => Datum::Decimal(*v),
+            Const::Str(v) => Datum::String(v.clone()),
+            Const::Bytes(v) => Datum::Bytes(v.clone()),
+            Const::Date(v) => Datum::Date(*v),
+            Const::Time(v) => Datum::Time(*v),
+            Const::Timestamp(v) => Datum::Timestamp(*v),
+        }
+    }
+}
+
+impl From<&Datum> for Const {
+    fn from(value: &Datum) -> Self {
+        match value {
+            Datum::Null => Const::Null,
+            Datum::Bool(v) => Const::Bool(*v),
+            Datum::I16(v) => Const::Int16(*v),
+            Datum::I32(v) => Const::Int32(*v),
+            Datum::I64(v) => Const::Int64(*v),
+            Datum::F32(v) => Const::Float32(*v),
+            Datum::F64(v) => Const::Float64(*v),
+            Datum::Decimal(v) => Const::Decimal(*v),
+            Datum::String(v) => Const::Str(v.clone()),
+            Datum::Bytes(v) => Const::Bytes(v.clone()),
+            Datum::Date(v) => Const::Date(*v),
+            Datum::Time(v) => Const::Time(*v),
+            Datum::Timestamp(v) => Const::Timestamp(*v),
+        }
+    }
 }
@@ -0,0 +1,233 @@
+use crate::datum::{Datum, Decimal, MAX_DECIMAL_PRECISION};
+use crate::expression::{
+    as_comparison, like_match, And, ArithOp, Arithmetic, Between, Case, Cast, CmpOp, Coalesce, ColumnVar, Const,
+    FunctionCall, InList, IsNotNull, IsNull, Like, Not, Or,
+};
+use cso_core::datatype::DataType;
+use cso_core::expression::ScalarExpression;
+use std::collections::HashMap;
+
+/// A row of values, global column id -> value.
+pub type Row = HashMap<u32, Datum>;
+
+/// Evaluates the expression against a row with SQL three-valued logic, a boolean expression evaluates to true, false
+/// or NULL.
+///
+/// Returns `None` if the expression cannot be evaluated: it references a column missing from the row, compares values
+/// of incomparable types or is not supported by the evaluator. A conjunction with a false operand is false and a
+/// disjunction with a true operand is true even if their other operands cannot be evaluated.
+pub fn evaluate(expr: &dyn ScalarExpression, row: &Row) -> Option<Datum> {
+    if let Some(column) = expr.downcast_ref::<ColumnVar>() {
+        row.get(&column.id()).cloned()
+    } else if let Some(constant) = expr.downcast_ref::<Const>() {
+        Some(constant.to_datum())
+    } else if let Some((op, left, right)) = as_comparison(expr) {
+        compare(op, &evaluate(left, row)?, &evaluate(right, row)?)
+    } else if let Some(and) = expr.downcast_ref::<And>() {
+        let operands = and.expressions().iter().map(|expr| evaluate(expr.as_ref(), row));
+        evaluate_connective(operands, false)
+    } else if let Some(or) = expr.downcast_ref::<Or>() {
+        let operands = or.expressions().iter().map(|expr| evaluate(expr.as_ref(), row));
+        evaluate_connective(operands, true)
+    } else if let Some(not) = expr.downcast_ref::<Not>() {
+        match truth_value(&evaluate(not.expression(), row)?)? {
+            Some(value) => Some(Datum::Bool(!value)),
+            None => Some(Datum::Null),
+        }
+    } else if let Some(is_null) = expr.downcast_ref::<IsNull>() {
+        Some(Datum::Bool(evaluate(is_null.inner(), row)?.is_null()))
+    } else if let Some(is_not_null) = expr.downcast_ref::<IsNotNull>() {
+        Some(Datum::Bool(!evaluate(is_not_null.inner(), row)?.is_null()))
+    } else if let Some(arithmetic) = expr.downcast_ref::<Arithmetic>() {
+        let (left, right) = (evaluate(arithmetic.left(), row)?, evaluate(arithmetic.right(), row)?);
+        arithmetic_value(arithmetic.op(), &left, &right, &arithmetic.return_type())
+    } else if let Some(cast) = expr.downcast_ref::<Cast>() {
+        evaluate(cast.expr(), row)?.cast_to(&cast.data_type())
+    } else if let Some(in_list) = expr.downcast_ref::<InList>() {
+        // x IN (a, b) is x = a OR x = b
+        let value = evaluate(in_list.expr(), row)?;
+        let operands = in_list
+            .list()
+            .iter()
+            .map(|expr| compare(CmpOp::Equal, &value, &evaluate(expr.as_ref(), row)?));
+        negate_if(evaluate_connective(operands, true)?, in_list.negated())
+    } else if let Some(between) = expr.downcast_ref::<Between>() {
+        let value = evaluate(between.expr(), row)?;
+        let operands = [
+            compare(CmpOp::GreaterThanEqual, &value, &evaluate(between.low(), row)?),
+            compare(CmpOp::LessThanEqual, &value, &evaluate(between.high(), row)?),
+        ];
+        negate_if(evaluate_connective(operands.into_iter(), false)?, between.negated())
+    } else if let Some(like) = expr.downcast_ref::<Like>() {
+        let matched = match (evaluate(like.expr(), row)?, evaluate(like.pattern(), row)?) {
+            (Datum::String(value), Datum::String(pattern)) => Datum::Bool(like_match(&value, &pattern)),
+            (Datum::Null, _) | (_, Datum::Null) => Datum::Null,
+            _ => return None,
+        };
+        negate_if(matched, like.negated())
+    } else if let Some(case) = expr.downcast_ref::<Case>() {
+        evaluate_case(case, row)?.cast_to(&case.return_type())
+    } else if let Some(coalesce) = expr.downcast_ref::<Coalesce>() {
+        for expr in coalesce.expressions() {
+            let value = evaluate(expr.as_ref(), row)?;
+            if !value.is_null() {
+                return value.cast_to(&coalesce.return_type());
+            }
+        }
+        Some(Datum::Null)
+    } else if let Some(call) = expr.downcast_ref::<FunctionCall>() {
+        let args: Option<Vec<_>> = call.args().iter().map(|expr| evaluate(expr.as_ref(), row)).collect();
+        call.function().invoke(&args?)
+    } else {
+        None
+    }
+}
+
+/// Returns whether the row satisfies the predicate, NULL does not satisfy a predicate.
+pub fn evaluate_predicate(predicate: &dyn ScalarExpression, row: &Row) -> Option<bool> {
+    match truth_value(&evaluate(predicate, row)?)? {
+        Some(value) => Some(value),
+        None => Some(false),
+    }
+}
+
+/// Evaluates `left op right`, a comparison with NULL is NULL.
+pub fn compare(op: CmpOp, left: &Datum, right: &Datum) -> Option<Datum> {
+    if left.is_null() || right.is_null() {
+        return Some(Datum::Null);
+    }
+    // datums of all types are ordered, but only values of comparable types can be compared, in their common type
+    let data_type = left.data_type().common_type(&right.data_type())?;
+    let (left, right) = (left.cast_to(&data_type)?, right.cast_to(&data_type)?);
+    Some(Datum::Bool(op.holds(left.cmp(&right))))
+}
+
+/// Evaluates `left op right` in `data_type`, the type of the result, NULL if an operand is NULL and `None` on overflow
+/// or division by zero. Operands of unknown types are evaluated in the common type of their values.
+fn arithmetic_value(op: ArithOp, left: &Datum, right: &Datum, data_type: &DataType) -> Option<Datum> {
+    if left.is_null() || right.is_null() {
+        return Some(Datum::Null);
+    }
+    let data_type = &match data_type {
+        DataType::Unknown => left.data_type().common_type(&right.data_type())?,
+        data_type => *data_type,
+    };
+    if !data_type.is_numeric() {
+        return None;
+    }
+    let (left, right) = (left.cast_to(data_type)?, right.cast_to(data_type)?);
+    match (&left, &right) {
+        (Datum::Decimal(l), Datum::Decimal(r)) => {
+            let DataType::Decimal { precision, scale } = *data_type else {
+                unreachable!()
+            };
+            let (l, r) = (l.mantissa(), r.mantissa());
+            let (mantissa, result_scale) = match op {
+                ArithOp::Add => (l.checked_add(r)?, scale),
+                ArithOp::Subtract => (l.checked_sub(r)?, scale),
+                ArithOp::Multiply => (l.checked_mul(r)?, scale.checked_mul(2)?),
+                // the quotient keeps the scale of the operands
+                ArithOp::Divide if r != 0 => (l.checked_mul(10i128.checked_pow(scale as u32)?)? / r, scale),
+                ArithOp::Divide => return None,
+            };
+            if result_scale > MAX_DECIMAL_PRECISION
+                || mantissa.unsigned_abs() >= 10u128.pow(MAX_DECIMAL_PRECISION as u32)
+            {
+                return None;
+            }
+            Decimal::new(mantissa, result_scale)
+                .rescale(precision, scale)
+                .map(Datum::Decimal)
+        }
+        (Datum::F32(_) | Datum::F64(_), _) => {
+            let (l, r) = (left.to_f64()?, right.to_f64()?);
+            let value = match op {
+                ArithOp::Add => l + r,
+                ArithOp::Subtract => l - r,
+                ArithOp::Multiply => l * r,
+                ArithOp::Divide if r != 0.0 => l / r,
+                ArithOp::Divide => return None,
+            };
+            Datum::F64(value).cast_to(data_type)
+        }
+        _ => {
+            // integers, the quotient is truncated
+            let (l, r) = (integer_value(&left)?, integer_value(&right)?);
+            let value = match op {
+                ArithOp::Add => l.checked_add(r)?,
+                ArithOp::Subtract => l.checked_sub(r)?,
+                ArithOp::Multiply => l.checked_mul(r)?,
+                ArithOp::Divide => l.checked_div(r)?,
+            };
+            Datum::I64(value).cast_to(data_type)
+        }
+    }
+}
+
+fn integer_value(datum: &Datum) -> Option<i64> {
+    match datum {
+        Datum::I16(v) => Some(*v as i64),
+        Datum::I32(v) => Some(*v as i64),
+        Datum::I64(v) => Some(*v),
+        _ => None,
+    }
+}
+
+/// Evaluates the result of the first branch whose condition holds, comparing the conditions with the operand if any.
+fn evaluate_case(case: &Case, row: &Row) -> Option<Datum> {
+    let operand = match case.operand() {
+        Some(operand) => Some(evaluate(operand, row)?),
+        None => None,
+    };
+    for (condition, result) in case.branches() {
+        let condition = evaluate(condition.as_ref(), row)?;
+        let holds = match &operand {
+            Some(operand) => compare(CmpOp::Equal, operand, &condition)?,
+            None => condition,
+        };
+        if truth_value(&holds)? == Some(true) {
+            return evaluate(result.as_ref(), row);
+        }
+    }
+    match case.else_result() {
+        Some(else_result) => evaluate(else_result, row),
+        None => Some(Datum::Null),
+    }
+}
+
+/// Negates a boolean datum if `negated`, NULL stays NULL.
+fn negate_if(datum: Datum, negated: bool) -> Option<Datum> {
+    match (truth_value(&datum)?, negated) {
+        (Some(value), true) => Some(Datum::Bool(!value)),
+        _ => Some(datum),
+    }
+}
+
+/// Returns the truth value of a boolean datum, `Some(None)` for NULL and `None` for a non-boolean datum.
+fn truth_value(datum: &Datum) -> Option<Option<bool>> {
+    match datum {
+        Datum::Bool(value) => Some(Some(*value)),
+        Datum::Null => Some(None),
+        _ => None,
+    }
+}
+
+/// Evaluates a conjunction (`dominant` is false) or a disjunction (`dominant` is true) of evaluated operands.
+fn evaluate_connective(operands: impl Iterator<Item = Option<Datum>>, dominant: bool) -> Option<Datum> {
+    let mut has_null = false;
+    let mut has_unknown = false;
+    for operand in operands {
+        match operand.as_ref().and_then(truth_value) {
+            Some(Some(value)) if value == dominant => return Some(Datum::Bool(dominant)),
+            Some(Some(_)) => {}
+            Some(None) => has_null = true,
+            None => has_unknown = true,
+        }
+    }
+
+    match (has_unknown, has_null) {
+        (true, _) => None,
+        (false, true) => Some(Datum::Null),
+        (false, false) => Some(Datum::Bool(!dominant)),
+    }
+}
@@ -0,0 +1,70 @@
+use cso_core::datatype::DataType;
+use cso_core::expression::ScalarExpression;
+use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
+
+/// Kind of a binary arithmetic operation.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum ArithOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// `left op right` on numbers, the operands are converted to their common type, which is the type of the result.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Arithmetic {
+    op: ArithOp,
+    left: Box<dyn ScalarExpression>,
+    right: Box<dyn ScalarExpression>,
+}
+
+impl Arithmetic {
+    pub fn new(op: ArithOp, left: Box<dyn ScalarExpression>, right: Box<dyn ScalarExpression>) -> Self {
+        Arithmetic { op, left, right }
+    }
+
+    pub fn op(&self) -> ArithOp {
+        self.op
+    }
+
+    pub fn left(&self) -> &dyn ScalarExpression {
+        self.left.as_ref()
+    }
+
+    pub fn right(&self) -> &dyn ScalarExpression {
+        self.right.as_ref()
+    }
+}
+
+#[typetag::serde]
+impl ScalarExpression for Arithmetic {
+    fn return_type(&self) -> DataType {
+        self.left
+            .return_type()
+            .common_type(&self.right.return_type())
+            .unwrap_or_default()
+    }
+
+    fn equal(&self, other: &dyn ScalarExpression) -> bool {
+        match other.downcast_ref::<Arithmetic>() {
+            Some(other) => self.op == other.op && self.left.eq(&other.left) && self.right.eq(&other.right),
+            None => false,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.left.as_ref(), self.right.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [left, right] = <[_; 2]>::try_from(children).expect("two children expected");
+        Box::new(Arithmetic::new(self.op, left, right))
+    }
+
+    fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
+        self.left.derive_used_columns(col_set);
+        self.right.derive_used_columns(col_set);
+    }
+}
@@ -0,0 +1,84 @@
+use cso_core::datatype::DataType;
+use cso_core::expression::ScalarExpression;
+use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
+
+/// `expr BETWEEN low AND high`, which is `low <= expr AND expr <= high`, or `expr NOT BETWEEN low AND high` if
+/// negated.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Between {
+    expr: Box<dyn ScalarExpression>,
+    low: Box<dyn ScalarExpression>,
+    high: Box<dyn ScalarExpression>,
+    negated: bool,
+}
+
+impl Between {
+    pub fn new(
+        expr: Box<dyn ScalarExpression>,
+        low: Box<dyn ScalarExpression>,
+        high: Box<dyn ScalarExpression>,
+        negated: bool,
+    ) -> Self {
+        Between {
+            expr,
+            low,
+            high,
+            negated,
+        }
+    }
+
+    pub fn expr(&self) -> &dyn ScalarExpression {
+        self.expr.as_ref()
+    }
+
+    pub fn low(&self) -> &dyn ScalarExpression {
+        self.low.as_ref()
+    }
+
+    pub fn high(&self) -> &dyn ScalarExpression {
+        self.high.as_ref()
+    }
+
+    pub fn negated(&self) -> bool {
+        self.negated
+    }
+}
+
+#[typetag::serde]
+impl ScalarExpression for Between {
+    fn is_boolean_expression(&self) -> bool {
+        true
+    }
+
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
+    fn equal(&self, other: &dyn ScalarExpression) -> bool {
+        match other.downcast_ref::<Between>() {
+            Some(other) => {
+                self.negated == other.negated
+                    && self.expr.eq(&other.expr)
+                    && self.low.eq(&other.low)
+                    && self.high.eq(&other.high)
+            }
+            None => false,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.expr.as_ref(), self.low.as_ref(), self.high.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [expr, low, high] = <[_; 3]>::try_from(children).expect("three children expected");
+        Box::new(Between::new(expr, low, high, self.negated))
+    }
+
+    fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
+        self.expr.derive_used_columns(col_set);
+        self.low.derive_used_columns(col_set);
+        self.high.derive_used_columns(col_set);
+    }
+}
@@ -0,0 +1,52 @@
+use cso_core::datatype::DataType;
+use cso_core::expression::ScalarExpression;
+use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
+
+/// `CAST(expr AS data_type)`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Cast {
+    expr: Box<dyn ScalarExpression>,
+    data_type: DataType,
+}
+
+impl Cast {
+    pub fn new(expr: Box<dyn ScalarExpression>, data_type: DataType) -> Self {
+        Cast { expr, data_type }
+    }
+
+    pub fn expr(&self) -> &dyn ScalarExpression {
+        self.expr.as_ref()
+    }
+
+    pub fn data_type(&self) -> DataType {
+        self.data_type
+    }
+}
+
+#[typetag::serde]
+impl ScalarExpression for Cast {
+    fn return_type(&self) -> DataType {
+        self.data_type
+    }
+
+    fn equal(&self, other: &dyn ScalarExpression) -> bool {
+        match other.downcast_ref::<Cast>() {
+            Some(other) => self.data_type == other.data_type && self.expr.eq(&other.expr),
+            None => false,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.expr.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [expr] = <[_; 1]>::try_from(children).expect("one child expected");
+        Box::new(Cast::new(expr, self.data_type))
+    }
+
+    fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
+        self.expr.derive_used_columns(col_set);
+    }
+}
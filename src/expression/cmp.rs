@@ -1,7 +1,92 @@
+use cso_core::datatype::DataType;
 use cso_core::expression::ScalarExpression;
 use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
+
+/// Kind of a binary comparison.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum CmpOp {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    LessThan,
+    GreaterThanEqual,
+    LessThanEqual,
+}
+
+impl CmpOp {
+    /// Returns the operator to use when the operands are swapped, e.g. `a < b` is `b > a`.
+    pub fn commute(self) -> CmpOp {
+        match self {
+            CmpOp::Equal => CmpOp::Equal,
+            CmpOp::NotEqual => CmpOp::NotEqual,
+            CmpOp::GreaterThan => CmpOp::LessThan,
+            CmpOp::LessThan => CmpOp::GreaterThan,
+            CmpOp::GreaterThanEqual => CmpOp::LessThanEqual,
+            CmpOp::LessThanEqual => CmpOp::GreaterThanEqual,
+        }
+    }
+
+    /// Returns the operator of the negated comparison, e.g. `NOT (a < b)` is `a >= b`.
+    pub fn negate(self) -> CmpOp {
+        match self {
+            CmpOp::Equal => CmpOp::NotEqual,
+            CmpOp::NotEqual => CmpOp::Equal,
+            CmpOp::GreaterThan => CmpOp::LessThanEqual,
+            CmpOp::LessThan => CmpOp::GreaterThanEqual,
+            CmpOp::GreaterThanEqual => CmpOp::LessThan,
+            CmpOp::LessThanEqual => CmpOp::GreaterThan,
+        }
+    }
+
+    /// Returns whether `left op right` holds, given the ordering of `left` to `right`.
+    pub fn holds(self, ordering: std::cmp::Ordering) -> bool {
+        match self {
+            CmpOp::Equal => ordering.is_eq(),
+            CmpOp::NotEqual => ordering.is_ne(),
+            CmpOp::GreaterThan => ordering.is_gt(),
+            CmpOp::LessThan => ordering.is_lt(),
+            CmpOp::GreaterThanEqual => ordering.is_ge(),
+            CmpOp::LessThanEqual => ordering.is_le(),
+        }
+    }
+}
+
+/// Splits a comparison expression into its operator and operands, returns `None` for other expressions.
+pub fn as_comparison(expr: &dyn ScalarExpression) -> Option<(CmpOp, &dyn ScalarExpression, &dyn ScalarExpression)> {
+    if let Some(cmp) = expr.downcast_ref::<Equal>() {
+        Some((CmpOp::Equal, cmp.left(), cmp.right()))
+    } else if let Some(cmp) = expr.downcast_ref::<NotEqual>() {
+        Some((CmpOp::NotEqual, cmp.left(), cmp.right()))
+    } else if let Some(cmp) = expr.downcast_ref::<GreaterThan>() {
+        Some((CmpOp::GreaterThan, cmp.left(), cmp.right()))
+    } else if let Some(cmp) = expr.downcast_ref::<LessThan>() {
+        Some((CmpOp::LessThan, cmp.left(), cmp.right()))
+    } else if let Some(cmp) = expr.downcast_ref::<GreaterThanEqual>() {
+        Some((CmpOp::GreaterThanEqual, cmp.left(), cmp.right()))
+    } else {
+        expr.downcast_ref::<LessThanEqual>()
+            .map(|cmp| (CmpOp::LessThanEqual, cmp.left(), cmp.right()))
+    }
+}
 
-#[derive(Debug, Clone)]
+/// Builds the comparison `left op right`.
+pub fn new_comparison(
+    op: CmpOp,
+    left: Box<dyn ScalarExpression>,
+    right: Box<dyn ScalarExpression>,
+) -> Box<dyn ScalarExpression> {
+    match op {
+        CmpOp::Equal => Box::new(Equal::new(left, right)),
+        CmpOp::NotEqual => Box::new(NotEqual::new(left, right)),
+        CmpOp::GreaterThan => Box::new(GreaterThan::new(left, right)),
+        CmpOp::LessThan => Box::new(LessThan::new(left, right)),
+        CmpOp::GreaterThanEqual => Box::new(GreaterThanEqual::new(left, right)),
+        CmpOp::LessThanEqual => Box::new(LessThanEqual::new(left, right)),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Equal {
     left: Box<dyn ScalarExpression>,
     right: Box<dyn ScalarExpression>,
@@ -21,11 +106,16 @@ impl Equal {
     }
 }
 
+#[typetag::serde]
 impl ScalarExpression for Equal {
     fn is_boolean_expression(&self) -> bool {
         true
     }
 
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<Equal>() {
             Some(other) => self.left.eq(&other.left) && self.right.eq(&other.right),
@@ -33,13 +123,22 @@ impl ScalarExpression for Equal {
         }
     }
 
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.left.as_ref(), self.right.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [left, right] = <[_; 2]>::try_from(children).expect("two children expected");
+        Box::new(Equal::new(left, right))
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         self.left.derive_used_columns(col_set);
         self.right.derive_used_columns(col_set);
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotEqual {
     left: Box<dyn ScalarExpression>,
     right: Box<dyn ScalarExpression>,
@@ -59,11 +158,16 @@ impl NotEqual {
     }
 }
 
+#[typetag::serde]
 impl ScalarExpression for NotEqual {
     fn is_boolean_expression(&self) -> bool {
         true
     }
 
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<NotEqual>() {
             Some(other) => self.left.eq(&other.left) && self.right.eq(&other.right),
@@ -71,13 +175,22 @@ impl ScalarExpression for NotEqual {
         }
     }
 
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.left.as_ref(), self.right.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [left, right] = <[_; 2]>::try_from(children).expect("two children expected");
+        Box::new(NotEqual::new(left, right))
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         self.left.derive_used_columns(col_set);
         self.right.derive_used_columns(col_set);
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GreaterThan {
     left: Box<dyn ScalarExpression>,
     right: Box<dyn ScalarExpression>,
@@ -97,11 +210,16 @@ impl GreaterThan {
     }
 }
 
+#[typetag::serde]
 impl ScalarExpression for GreaterThan {
     fn is_boolean_expression(&self) -> bool {
         true
     }
 
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<GreaterThan>() {
             Some(other) => self.left.eq(&other.left) && self.right.eq(&other.right),
@@ -109,13 +227,22 @@ impl ScalarExpression for GreaterThan {
         }
     }
 
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.left.as_ref(), self.right.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [left, right] = <[_; 2]>::try_from(children).expect("two children expected");
+        Box::new(GreaterThan::new(left, right))
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         self.left.derive_used_columns(col_set);
         self.right.derive_used_columns(col_set);
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LessThan {
     left: Box<dyn ScalarExpression>,
     right: Box<dyn ScalarExpression>,
@@ -135,11 +262,16 @@ impl LessThan {
     }
 }
 
+#[typetag::serde]
 impl ScalarExpression for LessThan {
     fn is_boolean_expression(&self) -> bool {
         true
     }
 
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<LessThan>() {
             Some(other) => self.left.eq(&other.left) && self.right.eq(&other.right),
@@ -147,13 +279,22 @@ impl ScalarExpression for LessThan {
         }
     }
 
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.left.as_ref(), self.right.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [left, right] = <[_; 2]>::try_from(children).expect("two children expected");
+        Box::new(LessThan::new(left, right))
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         self.left.derive_used_columns(col_set);
         self.right.derive_used_columns(col_set);
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GreaterThanEqual {
     left: Box<dyn ScalarExpression>,
     right: Box<dyn ScalarExpression>,
@@ -173,11 +314,16 @@ impl GreaterThanEqual {
     }
 }
 
+#[typetag::serde]
 impl ScalarExpression for GreaterThanEqual {
     fn is_boolean_expression(&self) -> bool {
         true
     }
 
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<GreaterThanEqual>() {
             Some(other) => self.left.eq(&other.left) && self.right.eq(&other.right),
@@ -185,13 +331,22 @@ impl ScalarExpression for GreaterThanEqual {
         }
     }
 
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.left.as_ref(), self.right.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [left, right] = <[_; 2]>::try_from(children).expect("two children expected");
+        Box::new(GreaterThanEqual::new(left, right))
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         self.left.derive_used_columns(col_set);
         self.right.derive_used_columns(col_set);
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LessThanEqual {
     left: Box<dyn ScalarExpression>,
     right: Box<dyn ScalarExpression>,
@@ -211,11 +366,16 @@ impl LessThanEqual {
     }
 }
 
+#[typetag::serde]
 impl ScalarExpression for LessThanEqual {
     fn is_boolean_expression(&self) -> bool {
         true
     }
 
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<LessThanEqual>() {
             Some(other) => self.left.eq(&other.left) && self.right.eq(&other.right),
@@ -223,6 +383,15 @@ impl ScalarExpression for LessThanEqual {
         }
     }
 
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.left.as_ref(), self.right.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [left, right] = <[_; 2]>::try_from(children).expect("two children expected");
+        Box::new(LessThanEqual::new(left, right))
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         self.left.derive_used_columns(col_set);
         self.right.derive_used_columns(col_set);
@@ -0,0 +1,137 @@
+use cso_core::datatype::DataType;
+use cso_core::expression::ScalarExpression;
+use serde::{Deserialize, Serialize};
+
+/// Returns the type all the expressions are converted to, `Unknown` if they have no common type.
+fn common_type<'a>(exprs: impl Iterator<Item = &'a dyn ScalarExpression>) -> DataType {
+    exprs
+        .map(|expr| expr.return_type())
+        .try_fold(DataType::Unknown, |common, data_type| common.common_type(&data_type))
+        .unwrap_or_default()
+}
+
+/// A branch of a CASE, (condition, result).
+pub type CaseBranch = (Box<dyn ScalarExpression>, Box<dyn ScalarExpression>);
+
+/// `CASE [operand] WHEN condition THEN result ... [ELSE result] END`.
+///
+/// Without an operand, the result of the first true condition is returned. With an operand, the result of the first
+/// condition equal to the operand is returned. The result is NULL if no condition holds and there is no else result.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Case {
+    operand: Option<Box<dyn ScalarExpression>>,
+    branches: Vec<CaseBranch>,
+    else_result: Option<Box<dyn ScalarExpression>>,
+}
+
+impl Case {
+    pub fn new(
+        operand: Option<Box<dyn ScalarExpression>>,
+        branches: Vec<CaseBranch>,
+        else_result: Option<Box<dyn ScalarExpression>>,
+    ) -> Self {
+        assert!(!branches.is_empty(), "CASE without WHEN");
+        if operand.is_none() {
+            assert!(branches.iter().all(|(condition, _)| condition.is_boolean_expression()));
+        }
+        Case {
+            operand,
+            branches,
+            else_result,
+        }
+    }
+
+    pub fn operand(&self) -> Option<&dyn ScalarExpression> {
+        self.operand.as_deref()
+    }
+
+    pub fn branches(&self) -> &[CaseBranch] {
+        &self.branches
+    }
+
+    pub fn else_result(&self) -> Option<&dyn ScalarExpression> {
+        self.else_result.as_deref()
+    }
+}
+
+#[typetag::serde]
+impl ScalarExpression for Case {
+    fn return_type(&self) -> DataType {
+        let results = self.branches.iter().map(|(_, result)| result.as_ref());
+        common_type(results.chain(self.else_result.as_deref()))
+    }
+
+    fn equal(&self, other: &dyn ScalarExpression) -> bool {
+        match other.downcast_ref::<Case>() {
+            Some(other) => {
+                self.operand == other.operand
+                    && self.branches == other.branches
+                    && self.else_result == other.else_result
+            }
+            None => false,
+        }
+    }
+
+    /// The operand, the condition and the result of every branch, then the else result.
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        let mut children: Vec<&dyn ScalarExpression> = self.operand.iter().map(|expr| expr.as_ref()).collect();
+        for (condition, result) in &self.branches {
+            children.push(condition.as_ref());
+            children.push(result.as_ref());
+        }
+        children.extend(self.else_result.as_deref());
+        children
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        assert_eq!(children.len(), self.children().len(), "unexpected number of children");
+        let mut children = children.into_iter();
+        let operand = self.operand.as_ref().map(|_| children.next().unwrap());
+        let branches = self
+            .branches
+            .iter()
+            .map(|_| (children.next().unwrap(), children.next().unwrap()))
+            .collect();
+        let else_result = children.next();
+        Box::new(Case::new(operand, branches, else_result))
+    }
+}
+
+/// `COALESCE(expr, ...)`, the first non-null value of the expressions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Coalesce {
+    expressions: Vec<Box<dyn ScalarExpression>>,
+}
+
+impl Coalesce {
+    pub fn new(expressions: Vec<Box<dyn ScalarExpression>>) -> Self {
+        assert!(!expressions.is_empty(), "COALESCE without argument");
+        Coalesce { expressions }
+    }
+
+    pub fn expressions(&self) -> &[Box<dyn ScalarExpression>] {
+        &self.expressions
+    }
+}
+
+#[typetag::serde]
+impl ScalarExpression for Coalesce {
+    fn return_type(&self) -> DataType {
+        common_type(self.expressions.iter().map(|expr| expr.as_ref()))
+    }
+
+    fn equal(&self, other: &dyn ScalarExpression) -> bool {
+        match other.downcast_ref::<Coalesce>() {
+            Some(other) => self.expressions == other.expressions,
+            None => false,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        self.expressions.iter().map(|expr| expr.as_ref()).collect()
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        Box::new(Coalesce::new(children))
+    }
+}
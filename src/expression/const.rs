@@ -1,14 +1,57 @@
+use crate::datum::{Datum, Decimal};
+use cso_core::datatype::DataType;
 use cso_core::expression::ScalarExpression;
 use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::mem;
 
-#[derive(Clone, Eq, Hash, PartialEq, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Const {
+    Null,
+    Bool(bool),
+    Int16(i16),
     Int32(i32),
     Int64(i64),
+    Float32(f32),
+    Float64(f64),
+    Decimal(Decimal),
     Str(String),
+    Bytes(Vec<u8>),
+    Date(i32),
+    Time(i64),
+    Timestamp(i64),
 }
 
+impl Const {
+    pub fn to_datum(&self) -> Datum {
+        Datum::from(self)
+    }
+}
+
+// Constants of different types are different, constants of the same type compare like datums so that they are
+// reflexive even for NaN.
+impl PartialEq for Const {
+    fn eq(&self, other: &Self) -> bool {
+        mem::discriminant(self) == mem::discriminant(other) && self.to_datum() == other.to_datum()
+    }
+}
+
+impl Eq for Const {}
+
+impl Hash for Const {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        self.to_datum().hash(state);
+    }
+}
+
+#[typetag::serde]
 impl ScalarExpression for Const {
+    fn return_type(&self) -> DataType {
+        self.to_datum().data_type()
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<Const>() {
             Some(other) => self == other,
@@ -16,6 +59,11 @@ impl ScalarExpression for Const {
         }
     }
 
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        assert!(children.is_empty(), "no child expected");
+        Box::new(self.clone())
+    }
+
     fn derive_used_columns(&self, _col_set: &mut ColumnRefSet) {
         // no column
     }
@@ -0,0 +1,277 @@
+use crate::datum::{Datum, Decimal};
+use cso_core::datatype::DataType;
+use cso_core::expression::ScalarExpression;
+use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Computes the result of a function from the values of its arguments, `None` if it cannot be computed.
+pub type FunctionImpl = fn(&[Datum]) -> Option<Datum>;
+
+/// Whether the result of a function only depends on its arguments, like PostgreSQL.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum Volatility {
+    /// Same arguments, same result. Calls with constant arguments are folded.
+    Immutable,
+    /// Same arguments, same result within a statement, e.g. `now()`.
+    Stable,
+    /// The result may change at every call, e.g. `random()`.
+    Volatile,
+}
+
+/// The type of the result of a function.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum ReturnType {
+    Fixed(DataType),
+    Argument(usize), // the type of the argument at this position
+}
+
+/// Describes a function: its arity, return type, strictness and volatility.
+///
+/// The implementation is not serialized, the implementation of a built-in function is restored by name when it is
+/// deserialized.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "FunctionDef")]
+pub struct Function {
+    name: String,
+    min_args: usize,
+    max_args: usize,
+    return_type: ReturnType,
+    strict: bool,
+    volatility: Volatility,
+    #[serde(skip)]
+    implementation: Option<FunctionImpl>,
+}
+
+/// A deserialized function, without implementation.
+#[derive(Deserialize)]
+struct FunctionDef {
+    name: String,
+    min_args: usize,
+    max_args: usize,
+    return_type: ReturnType,
+    strict: bool,
+    volatility: Volatility,
+}
+
+impl From<FunctionDef> for Function {
+    fn from(def: FunctionDef) -> Self {
+        let implementation = FunctionRegistry::with_builtins()
+            .get(&def.name)
+            .and_then(|builtin| builtin.implementation);
+        Function {
+            name: def.name,
+            min_args: def.min_args,
+            max_args: def.max_args,
+            return_type: def.return_type,
+            strict: def.strict,
+            volatility: def.volatility,
+            implementation,
+        }
+    }
+}
+
+impl Function {
+    /// Creates an immutable strict function without implementation.
+    pub fn new(name: &str, min_args: usize, max_args: usize, return_type: ReturnType) -> Self {
+        assert!(min_args <= max_args);
+        if let ReturnType::Argument(position) = return_type {
+            assert!(position < min_args, "return type of an optional argument");
+        }
+        Function {
+            name: name.to_lowercase(),
+            min_args,
+            max_args,
+            return_type,
+            strict: true,
+            volatility: Volatility::Immutable,
+            implementation: None,
+        }
+    }
+
+    /// A strict function returns NULL if any of its arguments is NULL, without being called.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn with_volatility(mut self, volatility: Volatility) -> Self {
+        self.volatility = volatility;
+        self
+    }
+
+    pub fn with_implementation(mut self, implementation: FunctionImpl) -> Self {
+        self.implementation = Some(implementation);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn accepts(&self, arg_count: usize) -> bool {
+        (self.min_args..=self.max_args).contains(&arg_count)
+    }
+
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+
+    pub fn volatility(&self) -> Volatility {
+        self.volatility
+    }
+
+    /// Calls the function, `None` if it has no implementation or cannot compute its result.
+    pub fn invoke(&self, args: &[Datum]) -> Option<Datum> {
+        if self.strict && args.iter().any(Datum::is_null) {
+            return Some(Datum::Null);
+        }
+        (self.implementation?)(args)
+    }
+}
+
+/// The functions known to the optimizer, by case-insensitive name.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FunctionRegistry {
+    functions: HashMap<String, Function>,
+}
+
+impl FunctionRegistry {
+    pub fn new() -> Self {
+        FunctionRegistry {
+            functions: HashMap::new(),
+        }
+    }
+
+    /// Creates a registry of the built-in functions: `abs`, `lower`, `upper`, `length`, `now` and `random`.
+    pub fn with_builtins() -> Self {
+        let mut registry = FunctionRegistry::new();
+        registry.register(Function::new("abs", 1, 1, ReturnType::Argument(0)).with_implementation(abs));
+        registry.register(Function::new("lower", 1, 1, ReturnType::Fixed(DataType::String)).with_implementation(lower));
+        registry.register(Function::new("upper", 1, 1, ReturnType::Fixed(DataType::String)).with_implementation(upper));
+        registry
+            .register(Function::new("length", 1, 1, ReturnType::Fixed(DataType::Int64)).with_implementation(length));
+        registry.register(
+            Function::new("now", 0, 0, ReturnType::Fixed(DataType::Timestamp)).with_volatility(Volatility::Stable),
+        );
+        registry.register(
+            Function::new("random", 0, 0, ReturnType::Fixed(DataType::Float64)).with_volatility(Volatility::Volatile),
+        );
+        registry
+    }
+
+    /// Registers a function, replacing the function of the same name.
+    pub fn register(&mut self, function: Function) {
+        self.functions.insert(function.name.clone(), function);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Function> {
+        self.functions.get(&name.to_lowercase())
+    }
+
+    /// Builds a call of the function named `name`, `None` if there is no such function taking that many arguments.
+    pub fn call(&self, name: &str, args: Vec<Box<dyn ScalarExpression>>) -> Option<FunctionCall> {
+        let function = self.get(name)?;
+        function
+            .accepts(args.len())
+            .then(|| FunctionCall::new(function.clone(), args))
+    }
+}
+
+fn abs(args: &[Datum]) -> Option<Datum> {
+    match &args[0] {
+        Datum::I16(v) => v.checked_abs().map(Datum::I16),
+        Datum::I32(v) => v.checked_abs().map(Datum::I32),
+        Datum::I64(v) => v.checked_abs().map(Datum::I64),
+        Datum::F32(v) => Some(Datum::F32(v.abs())),
+        Datum::F64(v) => Some(Datum::F64(v.abs())),
+        Datum::Decimal(v) => Some(Datum::Decimal(Decimal::new(v.mantissa().abs(), v.scale()))),
+        _ => None,
+    }
+}
+
+fn lower(args: &[Datum]) -> Option<Datum> {
+    match &args[0] {
+        Datum::String(v) => Some(Datum::String(v.to_lowercase())),
+        _ => None,
+    }
+}
+
+fn upper(args: &[Datum]) -> Option<Datum> {
+    match &args[0] {
+        Datum::String(v) => Some(Datum::String(v.to_uppercase())),
+        _ => None,
+    }
+}
+
+fn length(args: &[Datum]) -> Option<Datum> {
+    match &args[0] {
+        Datum::String(v) => Some(Datum::I64(v.chars().count() as i64)),
+        Datum::Bytes(v) => Some(Datum::I64(v.len() as i64)),
+        _ => None,
+    }
+}
+
+/// A call of a function.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FunctionCall {
+    function: Function,
+    args: Vec<Box<dyn ScalarExpression>>,
+}
+
+impl FunctionCall {
+    pub fn new(function: Function, args: Vec<Box<dyn ScalarExpression>>) -> Self {
+        assert!(
+            function.accepts(args.len()),
+            "wrong number of arguments for {}",
+            function.name
+        );
+        FunctionCall { function, args }
+    }
+
+    pub fn function(&self) -> &Function {
+        &self.function
+    }
+
+    pub fn args(&self) -> &[Box<dyn ScalarExpression>] {
+        &self.args
+    }
+}
+
+#[typetag::serde]
+impl ScalarExpression for FunctionCall {
+    fn return_type(&self) -> DataType {
+        match self.function.return_type {
+            ReturnType::Fixed(data_type) => data_type,
+            ReturnType::Argument(position) => self.args[position].return_type(),
+        }
+    }
+
+    fn equal(&self, other: &dyn ScalarExpression) -> bool {
+        match other.downcast_ref::<FunctionCall>() {
+            Some(other) => self.function.name == other.function.name && self.args == other.args,
+            None => false,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        self.args.iter().map(|expr| expr.as_ref()).collect()
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        Box::new(FunctionCall::new(self.function.clone(), children))
+    }
+
+    fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
+        self.args.iter().for_each(|e| e.derive_used_columns(col_set));
+    }
+}
+
+/// Returns whether the expression calls no stable or volatile function, its value then only depends on its columns.
+pub fn is_immutable(expr: &dyn ScalarExpression) -> bool {
+    expr.collect(&|expr| {
+        expr.downcast_ref::<FunctionCall>()
+            .is_some_and(|call| call.function.volatility != Volatility::Immutable)
+    })
+    .is_empty()
+}
@@ -0,0 +1,72 @@
+use cso_core::datatype::DataType;
+use cso_core::expression::ScalarExpression;
+use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
+
+/// `expr IN (list)`, or `expr NOT IN (list)` if negated.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InList {
+    expr: Box<dyn ScalarExpression>,
+    list: Vec<Box<dyn ScalarExpression>>,
+    negated: bool,
+}
+
+impl InList {
+    pub fn new(expr: Box<dyn ScalarExpression>, list: Vec<Box<dyn ScalarExpression>>, negated: bool) -> Self {
+        assert!(!list.is_empty(), "empty IN-list");
+        InList { expr, list, negated }
+    }
+
+    pub fn expr(&self) -> &dyn ScalarExpression {
+        self.expr.as_ref()
+    }
+
+    pub fn list(&self) -> &[Box<dyn ScalarExpression>] {
+        &self.list
+    }
+
+    pub fn negated(&self) -> bool {
+        self.negated
+    }
+
+    /// Returns `expr NOT IN (list)` for `expr IN (list)` and conversely.
+    pub fn negate(&self) -> InList {
+        InList::new(self.expr.clone(), self.list.clone(), !self.negated)
+    }
+}
+
+#[typetag::serde]
+impl ScalarExpression for InList {
+    fn is_boolean_expression(&self) -> bool {
+        true
+    }
+
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
+    fn equal(&self, other: &dyn ScalarExpression) -> bool {
+        match other.downcast_ref::<InList>() {
+            Some(other) => self.negated == other.negated && self.expr.eq(&other.expr) && self.list == other.list,
+            None => false,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        let mut children = vec![self.expr.as_ref()];
+        children.extend(self.list.iter().map(|expr| expr.as_ref()));
+        children
+    }
+
+    fn with_children(&self, mut children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        assert!(children.len() >= 2, "at least two children expected");
+        let list = children.split_off(1);
+        let expr = children.pop().unwrap();
+        Box::new(InList::new(expr, list, self.negated))
+    }
+
+    fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
+        self.expr.derive_used_columns(col_set);
+        self.list.iter().for_each(|e| e.derive_used_columns(col_set));
+    }
+}
@@ -1,7 +1,9 @@
+use cso_core::datatype::DataType;
 use cso_core::expression::ScalarExpression;
 use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct IsNull {
     inner: Box<dyn ScalarExpression>,
 }
@@ -10,13 +12,22 @@ impl IsNull {
     pub fn new(inner: Box<dyn ScalarExpression>) -> Self {
         Self { inner }
     }
+
+    pub fn inner(&self) -> &dyn ScalarExpression {
+        self.inner.as_ref()
+    }
 }
 
+#[typetag::serde]
 impl ScalarExpression for IsNull {
     fn is_boolean_expression(&self) -> bool {
         true
     }
 
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<IsNull>() {
             Some(other) => self.inner.eq(&other.inner),
@@ -24,12 +35,21 @@ impl ScalarExpression for IsNull {
         }
     }
 
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.inner.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [inner] = <[_; 1]>::try_from(children).expect("one child expected");
+        Box::new(IsNull::new(inner))
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         self.inner.derive_used_columns(col_set);
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct IsNotNull {
     inner: Box<dyn ScalarExpression>,
 }
@@ -38,13 +58,22 @@ impl IsNotNull {
     pub fn new(inner: Box<dyn ScalarExpression>) -> Self {
         Self { inner }
     }
+
+    pub fn inner(&self) -> &dyn ScalarExpression {
+        self.inner.as_ref()
+    }
 }
 
+#[typetag::serde]
 impl ScalarExpression for IsNotNull {
     fn is_boolean_expression(&self) -> bool {
         true
     }
 
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<IsNotNull>() {
             Some(other) => self.inner.eq(&other.inner),
@@ -52,6 +81,15 @@ impl ScalarExpression for IsNotNull {
         }
     }
 
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.inner.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [inner] = <[_; 1]>::try_from(children).expect("one child expected");
+        Box::new(IsNotNull::new(inner))
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         self.inner.derive_used_columns(col_set);
     }
@@ -0,0 +1,157 @@
+use cso_core::datatype::DataType;
+use cso_core::expression::ScalarExpression;
+use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
+
+const ESCAPE: char = '\\';
+
+/// `expr LIKE pattern`, or `expr NOT LIKE pattern` if negated.
+///
+/// In the pattern `%` matches any sequence of characters, `_` matches any character and `\` escapes the next
+/// character.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Like {
+    expr: Box<dyn ScalarExpression>,
+    pattern: Box<dyn ScalarExpression>,
+    negated: bool,
+}
+
+impl Like {
+    pub fn new(expr: Box<dyn ScalarExpression>, pattern: Box<dyn ScalarExpression>, negated: bool) -> Self {
+        Like { expr, pattern, negated }
+    }
+
+    pub fn expr(&self) -> &dyn ScalarExpression {
+        self.expr.as_ref()
+    }
+
+    pub fn pattern(&self) -> &dyn ScalarExpression {
+        self.pattern.as_ref()
+    }
+
+    pub fn negated(&self) -> bool {
+        self.negated
+    }
+
+    /// Returns `expr NOT LIKE pattern` for `expr LIKE pattern` and conversely.
+    pub fn negate(&self) -> Like {
+        Like::new(self.expr.clone(), self.pattern.clone(), !self.negated)
+    }
+}
+
+#[typetag::serde]
+impl ScalarExpression for Like {
+    fn is_boolean_expression(&self) -> bool {
+        true
+    }
+
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
+    fn equal(&self, other: &dyn ScalarExpression) -> bool {
+        match other.downcast_ref::<Like>() {
+            Some(other) => {
+                self.negated == other.negated && self.expr.eq(&other.expr) && self.pattern.eq(&other.pattern)
+            }
+            None => false,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.expr.as_ref(), self.pattern.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [expr, pattern] = <[_; 2]>::try_from(children).expect("two children expected");
+        Box::new(Like::new(expr, pattern, self.negated))
+    }
+
+    fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
+        self.expr.derive_used_columns(col_set);
+        self.pattern.derive_used_columns(col_set);
+    }
+}
+
+/// A character of a LIKE pattern.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PatternChar {
+    Literal(char),
+    Any,      // _
+    Sequence, // %
+}
+
+fn parse_pattern(pattern: &str) -> Vec<PatternChar> {
+    let mut parsed = vec![];
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        parsed.push(match c {
+            '%' => PatternChar::Sequence,
+            '_' => PatternChar::Any,
+            // a trailing escape character matches itself
+            ESCAPE => PatternChar::Literal(chars.next().unwrap_or(ESCAPE)),
+            c => PatternChar::Literal(c),
+        });
+    }
+    parsed
+}
+
+/// Returns whether `value` matches the LIKE `pattern`.
+pub fn like_match(value: &str, pattern: &str) -> bool {
+    let value: Vec<char> = value.chars().collect();
+    let pattern = parse_pattern(pattern);
+
+    // backtracks to the last `%`, letting it match one more character
+    let (mut v, mut p) = (0, 0);
+    let mut last_sequence: Option<(usize, usize)> = None;
+    while v < value.len() {
+        match pattern.get(p) {
+            Some(PatternChar::Sequence) => {
+                last_sequence = Some((p, v));
+                p += 1;
+            }
+            Some(PatternChar::Any) => {
+                v += 1;
+                p += 1;
+            }
+            Some(PatternChar::Literal(c)) if *c == value[v] => {
+                v += 1;
+                p += 1;
+            }
+            _ => match last_sequence {
+                Some((sequence_p, sequence_v)) => {
+                    last_sequence = Some((sequence_p, sequence_v + 1));
+                    p = sequence_p + 1;
+                    v = sequence_v + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == PatternChar::Sequence)
+}
+
+/// The literal prefix of a LIKE pattern, the characters before its first wildcard.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LikePrefix {
+    /// The pattern has no wildcard, it only matches the prefix.
+    Exact(String),
+    /// The pattern is the prefix followed by `%`, it matches the values starting with the prefix.
+    Prefix(String),
+    /// The pattern matches some of the values starting with the prefix.
+    Partial(String),
+}
+
+/// Returns the literal prefix of a LIKE pattern.
+pub fn like_prefix(pattern: &str) -> LikePrefix {
+    let pattern = parse_pattern(pattern);
+    let mut prefix = String::new();
+    for (i, c) in pattern.iter().enumerate() {
+        match c {
+            PatternChar::Literal(c) => prefix.push(*c),
+            _ if pattern[i..].iter().all(|c| *c == PatternChar::Sequence) => return LikePrefix::Prefix(prefix),
+            _ => return LikePrefix::Partial(prefix),
+        }
+    }
+    LikePrefix::Exact(prefix)
+}
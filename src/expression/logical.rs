@@ -1,8 +1,10 @@
+use cso_core::datatype::DataType;
 use cso_core::expression::ScalarExpression;
 use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct And {
     expressions: Vec<Rc<dyn ScalarExpression>>,
 }
@@ -18,11 +20,16 @@ impl And {
     }
 }
 
+#[typetag::serde]
 impl ScalarExpression for And {
     fn is_boolean_expression(&self) -> bool {
         true
     }
 
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<And>() {
             Some(other) => self.expressions == other.expressions,
@@ -30,12 +37,20 @@ impl ScalarExpression for And {
         }
     }
 
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        self.expressions.iter().map(|expr| expr.as_ref()).collect()
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        Box::new(And::new(children.into_iter().map(Rc::from).collect()))
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         self.expressions.iter().for_each(|e| e.derive_used_columns(col_set));
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Or {
     expressions: Vec<Box<dyn ScalarExpression>>,
 }
@@ -45,13 +60,22 @@ impl Or {
         assert!(expressions.iter().all(|expr| expr.is_boolean_expression()));
         Or { expressions }
     }
+
+    pub fn expressions(&self) -> &[Box<dyn ScalarExpression>] {
+        &self.expressions
+    }
 }
 
+#[typetag::serde]
 impl ScalarExpression for Or {
     fn is_boolean_expression(&self) -> bool {
         true
     }
 
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<Or>() {
             Some(other) => self.expressions == other.expressions,
@@ -59,12 +83,20 @@ impl ScalarExpression for Or {
         }
     }
 
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        self.expressions.iter().map(|expr| expr.as_ref()).collect()
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        Box::new(Or::new(children))
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         self.expressions.iter().for_each(|e| e.derive_used_columns(col_set));
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Not {
     expression: Box<dyn ScalarExpression>,
 }
@@ -74,12 +106,22 @@ impl Not {
         assert!(expression.is_boolean_expression());
         Not { expression }
     }
+
+    pub fn expression(&self) -> &dyn ScalarExpression {
+        self.expression.as_ref()
+    }
 }
 
+#[typetag::serde]
 impl ScalarExpression for Not {
     fn is_boolean_expression(&self) -> bool {
         true
     }
+
+    fn return_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<Not>() {
             Some(other) => self.expression.eq(&other.expression),
@@ -87,6 +129,15 @@ impl ScalarExpression for Not {
         }
     }
 
+    fn children(&self) -> Vec<&dyn ScalarExpression> {
+        vec![self.expression.as_ref()]
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        let [expression] = <[_; 1]>::try_from(children).expect("one child expected");
+        Box::new(Not::new(expression))
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         self.expression.derive_used_columns(col_set);
     }
@@ -1,12 +1,33 @@
+mod arith;
+mod between;
+mod cast;
 mod cmp;
+mod conditional;
 mod r#const;
+mod function;
+mod in_list;
 mod is_null;
+mod like;
 mod logical;
+mod param;
+mod rewrite;
 mod var;
 
+pub use self::arith::{ArithOp, Arithmetic};
+pub use self::between::Between;
+pub use self::cast::Cast;
+pub use self::cmp::{as_comparison, new_comparison, CmpOp};
 pub use self::cmp::{Equal, GreaterThan, GreaterThanEqual, LessThan, LessThanEqual, NotEqual};
+pub use self::conditional::{Case, CaseBranch, Coalesce};
+pub use self::function::{
+    is_immutable, Function, FunctionCall, FunctionImpl, FunctionRegistry, ReturnType, Volatility,
+};
+pub use self::in_list::InList;
 pub use self::is_null::{IsNotNull, IsNull};
+pub use self::like::{like_match, like_prefix, Like, LikePrefix};
 pub use self::logical::{And, Not, Or};
+pub use self::param::Param;
 pub use self::r#const::Const;
+pub use self::rewrite::{bind_params, referenced_columns, substitute_columns};
 pub use self::var::ColumnVar;
-pub use cso_core::expression::{AggregateExpression, ScalarExpression};
+pub use cso_core::expression::{AggregateExpression, ExpressionVisitor, ScalarExpression};
@@ -0,0 +1,54 @@
+use cso_core::datatype::DataType;
+use cso_core::expression::ScalarExpression;
+use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
+
+/// A query parameter of a prepared statement, `$index`. Its value is not known when the plan is built, but may be
+/// bound through the options of the optimizer to estimate its selectivity.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Param {
+    index: u32, // 1-based, like `$1`
+    data_type: DataType,
+}
+
+impl Param {
+    pub fn new(index: u32) -> Self {
+        assert!(index > 0, "parameters are numbered from 1");
+        Param {
+            index,
+            data_type: DataType::Unknown,
+        }
+    }
+
+    pub fn with_data_type(mut self, data_type: DataType) -> Self {
+        self.data_type = data_type;
+        self
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+#[typetag::serde]
+impl ScalarExpression for Param {
+    fn return_type(&self) -> DataType {
+        self.data_type
+    }
+
+    fn equal(&self, other: &dyn ScalarExpression) -> bool {
+        match other.downcast_ref::<Param>() {
+            Some(other) => self.index == other.index,
+            None => false,
+        }
+    }
+
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        assert!(children.is_empty(), "no child expected");
+        Box::new(self.clone())
+    }
+
+    fn derive_used_columns(&self, _col_set: &mut ColumnRefSet) {
+        // no column
+    }
+}
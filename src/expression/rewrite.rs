@@ -0,0 +1,47 @@
+use crate::expression::{ColumnVar, Param};
+use cso_core::expression::ScalarExpression;
+use cso_core::Options;
+use std::collections::HashMap;
+
+/// Replaces the columns of the expression by the expressions `mapping` maps their ids to, other columns are kept.
+/// The replacements are not substituted again.
+pub fn substitute_columns(
+    expr: &dyn ScalarExpression,
+    mapping: &HashMap<u32, Box<dyn ScalarExpression>>,
+) -> Box<dyn ScalarExpression> {
+    expr.transform_up(&mut |node| {
+        let replacement = node
+            .downcast_ref::<ColumnVar>()
+            .and_then(|column| mapping.get(&column.id()));
+        match replacement {
+            Some(replacement) => replacement.clone(),
+            None => node,
+        }
+    })
+}
+
+/// Replaces the parameters of the expression by the values bound to them in `options`, unbound parameters are kept.
+pub fn bind_params(expr: &dyn ScalarExpression, options: &Options) -> Box<dyn ScalarExpression> {
+    expr.transform_up(&mut |node| {
+        let value = node
+            .downcast_ref::<Param>()
+            .and_then(|param| options.param_value(param.index()));
+        match value {
+            Some(value) => value.clone_box(),
+            None => node,
+        }
+    })
+}
+
+/// Returns the columns referenced by the expression, in the order they first appear.
+pub fn referenced_columns(expr: &dyn ScalarExpression) -> Vec<ColumnVar> {
+    let mut columns: Vec<ColumnVar> = vec![];
+    expr.for_each(&mut |node| {
+        if let Some(column) = node.downcast_ref::<ColumnVar>() {
+            if !columns.contains(column) {
+                columns.push(column.clone());
+            }
+        }
+    });
+    columns
+}
@@ -1,24 +1,59 @@
+use cso_core::datatype::DataType;
 use cso_core::expression::ScalarExpression;
 use cso_core::ColumnRefSet;
 use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
 
-#[derive(Clone, Eq, Hash, PartialEq, Debug, Serialize, Deserialize)]
-#[repr(transparent)]
+/// A reference to a column, column vars with the same id are the same column whatever their types.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ColumnVar {
     id: u32, // global column id
+    #[serde(default)]
+    data_type: DataType,
 }
 
 impl ColumnVar {
     pub fn new(id: u32) -> Self {
-        ColumnVar { id }
+        ColumnVar {
+            id,
+            data_type: DataType::Unknown,
+        }
+    }
+
+    pub fn with_data_type(mut self, data_type: DataType) -> Self {
+        self.data_type = data_type;
+        self
     }
 
     pub fn id(&self) -> u32 {
         self.id
     }
+
+    pub fn data_type(&self) -> DataType {
+        self.data_type
+    }
+}
+
+impl PartialEq for ColumnVar {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
 }
 
+impl Eq for ColumnVar {}
+
+impl Hash for ColumnVar {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+#[typetag::serde]
 impl ScalarExpression for ColumnVar {
+    fn return_type(&self) -> DataType {
+        self.data_type
+    }
+
     fn equal(&self, other: &dyn ScalarExpression) -> bool {
         match other.downcast_ref::<ColumnVar>() {
             Some(other) => self.id() == other.id(),
@@ -26,6 +61,11 @@ impl ScalarExpression for ColumnVar {
         }
     }
 
+    fn with_children(&self, children: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+        assert!(children.is_empty(), "no child expected");
+        Box::new(self.clone())
+    }
+
     fn derive_used_columns(&self, col_set: &mut ColumnRefSet) {
         col_set.insert(self.id);
     }
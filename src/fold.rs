@@ -0,0 +1,252 @@
+use crate::datum::Datum;
+use crate::eval::{evaluate, Row};
+use crate::expression::{as_comparison, is_immutable, new_comparison, And, CmpOp, Const, Not, Or};
+use crate::selectivity::{column_predicate, ColumnPredicate};
+use cso_core::expression::ScalarExpression;
+use cso_core::ColumnRefSet;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Folds the constants of a filter predicate.
+///
+/// Constant sub-expressions are evaluated, true and false operands of conjunctions and disjunctions are simplified
+/// away and contradicting conjuncts on the same column make the predicate false, e.g. `1 = 1 AND c1 IS NULL` is
+/// `c1 IS NULL` and `c1 < 5 AND c1 > 10` is false. A filter rejects the rows its predicate is NULL for, so a NULL
+/// predicate is false. The predicate is returned as is if nothing is folded.
+pub fn fold_predicate(predicate: &Rc<dyn ScalarExpression>) -> Rc<dyn ScalarExpression> {
+    let folded = fold_boolean(predicate.as_ref(), true);
+    if folded.equal(predicate.as_ref()) {
+        predicate.clone()
+    } else {
+        Rc::from(folded)
+    }
+}
+
+/// Folds the constants of an expression.
+pub fn fold_constants(expr: &dyn ScalarExpression) -> Box<dyn ScalarExpression> {
+    fold(expr, false)
+}
+
+/// Folds a boolean expression, `null_is_false` tells whether NULL and false are equivalent where the expression is,
+/// which holds for a filter predicate and the operands of its conjunctions and disjunctions but not under a negation.
+fn fold_boolean(expr: &dyn ScalarExpression, null_is_false: bool) -> Box<dyn ScalarExpression> {
+    let folded = fold(expr, null_is_false);
+    match folded.downcast_ref::<Const>() {
+        Some(Const::Null) if null_is_false => Box::new(Const::Bool(false)),
+        // a NULL literal is not a boolean operand, the expression is kept as is
+        Some(Const::Null) => expr.clone_box(),
+        _ => folded,
+    }
+}
+
+fn fold(expr: &dyn ScalarExpression, null_is_false: bool) -> Box<dyn ScalarExpression> {
+    // an expression without columns nor stable or volatile functions is a constant
+    let mut columns = ColumnRefSet::new();
+    expr.derive_used_columns(&mut columns);
+    if columns.len() == 0 && is_immutable(expr) {
+        if let Some(datum) = evaluate(expr, &Row::new()) {
+            return Box::new(Const::from(&datum));
+        }
+    }
+
+    if let Some((op, left, right)) = as_comparison(expr) {
+        let (left, right) = (fold(left, false), fold(right, false));
+        if is_null(left.as_ref()) || is_null(right.as_ref()) {
+            return Box::new(Const::Null);
+        }
+        new_comparison(op, left, right)
+    } else if let Some(and) = expr.downcast_ref::<And>() {
+        let operands = and.expressions().iter().map(|expr| expr.as_ref());
+        let operands = fold_connective(operands, false, null_is_false);
+        if operands.is_empty() {
+            return Box::new(Const::Bool(true));
+        }
+        if operands.iter().any(|operand| is_bool(operand.as_ref(), false))
+            || (null_is_false && is_contradiction(&operands))
+        {
+            return Box::new(Const::Bool(false));
+        }
+        match operands.len() {
+            1 => operands.into_iter().next().unwrap(),
+            _ => Box::new(And::new(operands.into_iter().map(Rc::from).collect())),
+        }
+    } else if let Some(or) = expr.downcast_ref::<Or>() {
+        let operands = or.expressions().iter().map(|expr| expr.as_ref());
+        let operands = fold_connective(operands, true, null_is_false);
+        if operands.is_empty() {
+            return Box::new(Const::Bool(false));
+        }
+        if operands.iter().any(|operand| is_bool(operand.as_ref(), true)) {
+            return Box::new(Const::Bool(true));
+        }
+        match operands.len() {
+            1 => operands.into_iter().next().unwrap(),
+            _ => Box::new(Or::new(operands)),
+        }
+    } else if let Some(not) = expr.downcast_ref::<Not>() {
+        let operand = fold(not.expression(), false);
+        match operand.downcast_ref::<Const>() {
+            Some(Const::Bool(value)) => return Box::new(Const::Bool(!value)),
+            Some(Const::Null) => return Box::new(Const::Null),
+            _ => {}
+        }
+        match operand.downcast_ref::<Not>() {
+            Some(not) => not.expression().clone_box(),
+            None => Box::new(Not::new(operand)),
+        }
+    } else {
+        // other expressions are rebuilt with their folded operands
+        let children = expr.children().into_iter().map(|child| fold(child, false)).collect();
+        expr.with_children(children)
+    }
+}
+
+/// Folds and flattens the operands of a conjunction (`dominant` is false) or of a disjunction (`dominant` is true),
+/// operands that do not change the result and duplicated operands are removed.
+fn fold_connective<'a>(
+    operands: impl Iterator<Item = &'a dyn ScalarExpression>,
+    dominant: bool,
+    null_is_false: bool,
+) -> Vec<Box<dyn ScalarExpression>> {
+    let mut folded_operands: Vec<Box<dyn ScalarExpression>> = Vec::new();
+    for operand in operands {
+        let folded = fold_boolean(operand, null_is_false);
+        let flattened = match (folded.downcast_ref::<And>(), folded.downcast_ref::<Or>()) {
+            (Some(and), _) if !dominant => and.expressions().iter().map(|expr| expr.as_ref().clone_box()).collect(),
+            (_, Some(or)) if dominant => or.expressions().to_vec(),
+            _ => vec![folded],
+        };
+        for operand in flattened {
+            if !is_bool(operand.as_ref(), !dominant) && !folded_operands.iter().any(|expr| expr.equal(operand.as_ref()))
+            {
+                folded_operands.push(operand);
+            }
+        }
+    }
+    folded_operands
+}
+
+fn is_null(expr: &dyn ScalarExpression) -> bool {
+    matches!(expr.downcast_ref::<Const>(), Some(Const::Null))
+}
+
+fn is_bool(expr: &dyn ScalarExpression, value: bool) -> bool {
+    matches!(expr.downcast_ref::<Const>(), Some(Const::Bool(v)) if *v == value)
+}
+
+/// The values of a column allowed by the conjuncts on the column.
+#[derive(Default)]
+struct ColumnRange {
+    lower: Option<(Datum, bool)>, // lower bound, inclusive
+    upper: Option<(Datum, bool)>, // upper bound, inclusive
+    values: Option<Vec<Datum>>,   // the only allowed values
+    excluded: Vec<Datum>,
+    is_null: bool,
+    is_not_null: bool,
+}
+
+impl ColumnRange {
+    fn restrict(&mut self, predicate: ColumnPredicate) {
+        match predicate {
+            ColumnPredicate::IsNull => self.is_null = true,
+            ColumnPredicate::IsNotNull => self.is_not_null = true,
+            ColumnPredicate::InList(values) => {
+                self.is_not_null = true;
+                self.restrict_values(values);
+            }
+            ColumnPredicate::Compare(op, datum) => {
+                self.is_not_null = true;
+                match op {
+                    CmpOp::Equal => self.restrict_values(vec![datum]),
+                    CmpOp::NotEqual => self.excluded.push(datum),
+                    CmpOp::LessThan => self.restrict_upper(datum, false),
+                    CmpOp::LessThanEqual => self.restrict_upper(datum, true),
+                    CmpOp::GreaterThan => self.restrict_lower(datum, false),
+                    CmpOp::GreaterThanEqual => self.restrict_lower(datum, true),
+                }
+            }
+        }
+    }
+
+    fn restrict_values(&mut self, values: Vec<Datum>) {
+        self.values = Some(match self.values.take() {
+            None => values,
+            Some(current) => current.into_iter().filter(|value| values.contains(value)).collect(),
+        });
+    }
+
+    fn restrict_lower(&mut self, datum: Datum, inclusive: bool) {
+        let tighter = match &self.lower {
+            None => true,
+            Some((lower, lower_inclusive)) => datum > *lower || (datum == *lower && *lower_inclusive && !inclusive),
+        };
+        if tighter {
+            self.lower = Some((datum, inclusive));
+        }
+    }
+
+    fn restrict_upper(&mut self, datum: Datum, inclusive: bool) {
+        let tighter = match &self.upper {
+            None => true,
+            Some((upper, upper_inclusive)) => datum < *upper || (datum == *upper && *upper_inclusive && !inclusive),
+        };
+        if tighter {
+            self.upper = Some((datum, inclusive));
+        }
+    }
+
+    fn contains(&self, value: &Datum) -> bool {
+        let above_lower = self.lower.as_ref().is_none_or(|(lower, inclusive)| match *inclusive {
+            true => value >= lower,
+            false => value > lower,
+        });
+        let below_upper = self.upper.as_ref().is_none_or(|(upper, inclusive)| match *inclusive {
+            true => value <= upper,
+            false => value < upper,
+        });
+        above_lower && below_upper && !self.excluded.contains(value)
+    }
+
+    fn is_empty(&self) -> bool {
+        if self.is_null {
+            return self.is_not_null;
+        }
+        if let Some(values) = &self.values {
+            return !values.iter().any(|value| self.contains(value));
+        }
+        match (&self.lower, &self.upper) {
+            (Some((lower, lower_inclusive)), Some((upper, upper_inclusive))) => {
+                lower > upper || (lower == upper && !(*lower_inclusive && *upper_inclusive))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns whether the conjuncts on a column allow no value of the column.
+fn is_contradiction(conjuncts: &[Box<dyn ScalarExpression>]) -> bool {
+    let mut ranges: HashMap<u32, ColumnRange> = HashMap::new();
+    for conjunct in conjuncts {
+        if let Some((column, predicate)) = column_predicate(conjunct.as_ref()) {
+            // the column is compared with a value in their common type, bounds of types the column cannot be compared
+            // with say nothing about the column
+            let cast = |datum: Datum| {
+                let data_type = column.data_type().common_type(&datum.data_type())?;
+                datum.cast_to(&data_type)
+            };
+            let predicate = match predicate {
+                ColumnPredicate::Compare(op, datum) => cast(datum).map(|datum| ColumnPredicate::Compare(op, datum)),
+                ColumnPredicate::InList(values) => values
+                    .into_iter()
+                    .map(cast)
+                    .collect::<Option<Vec<_>>>()
+                    .map(ColumnPredicate::InList),
+                predicate @ (ColumnPredicate::IsNull | ColumnPredicate::IsNotNull) => Some(predicate),
+            };
+            if let Some(predicate) = predicate {
+                ranges.entry(column.id()).or_default().restrict(predicate);
+            }
+        }
+    }
+    ranges.values().any(ColumnRange::is_empty)
+}
@@ -0,0 +1,56 @@
+use crate::expression::{And, Const, IsNull, Not, Or};
+use crate::fold::fold_predicate;
+use crate::normalize::{normalize, to_cnf, DEFAULT_CNF_MAX_CONJUNCTS};
+use crate::operator::logical_filter::split_predicate;
+use crate::selectivity::{column_predicate, ColumnPredicate};
+use cso_core::expression::ScalarExpression;
+use std::rc::Rc;
+
+/// Returns whether `conclusion` is true for every row `premise` is true for, e.g. `c1 = 5 AND c2 IS NULL` implies
+/// `c1 > 0` and `c1 >= 5 OR c3 < 0`.
+///
+/// Both predicates are normalized to CNF. Every conjunct of the conclusion is a conjunct of the premise, a disjunction
+/// with an implied operand, or a predicate on a column that the premise contradicts when it is false or NULL. The check
+/// is conservative, an implication it does not prove is false.
+pub fn implies(premise: &dyn ScalarExpression, conclusion: &dyn ScalarExpression) -> bool {
+    let premise = cnf_conjuncts(premise);
+    cnf_conjuncts(conclusion)
+        .iter()
+        .all(|conjunct| conjunct_implied(&premise, conjunct.as_ref()))
+}
+
+fn cnf_conjuncts(predicate: &dyn ScalarExpression) -> Vec<Rc<dyn ScalarExpression>> {
+    let cnf = Rc::from(to_cnf(predicate, DEFAULT_CNF_MAX_CONJUNCTS));
+    let mut conjuncts = vec![];
+    split_predicate(&cnf, &mut conjuncts);
+    conjuncts
+}
+
+fn conjunct_implied(premise: &[Rc<dyn ScalarExpression>], conjunct: &dyn ScalarExpression) -> bool {
+    if premise.iter().any(|expr| expr.equal(conjunct)) {
+        return true;
+    }
+    if let Some(or) = conjunct.downcast_ref::<Or>() {
+        return or
+            .expressions()
+            .iter()
+            .any(|operand| conjunct_implied(premise, operand.as_ref()));
+    }
+    let Some((column, predicate)) = column_predicate(conjunct) else {
+        return false;
+    };
+
+    // the premise and the conjunct being false or NULL contradict
+    let contradicts = |expr: Box<dyn ScalarExpression>| {
+        let mut operands = premise.to_vec();
+        operands.push(Rc::from(expr));
+        let conjunction: Rc<dyn ScalarExpression> = Rc::new(And::new(operands));
+        matches!(
+            fold_predicate(&conjunction).downcast_ref::<Const>(),
+            Some(Const::Bool(false))
+        )
+    };
+    let never_null = matches!(predicate, ColumnPredicate::IsNull | ColumnPredicate::IsNotNull)
+        || contradicts(Box::new(IsNull::new(Box::new(column.clone()))));
+    never_null && contradicts(normalize(&Not::new(conjunct.clone_box())))
+}
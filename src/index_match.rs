@@ -0,0 +1,309 @@
+use crate::expression::{as_comparison, CmpOp, ColumnVar, Const, IsNull, Param};
+use crate::operator::logical_index_scan::IndexDesc;
+use crate::selectivity::{column_predicate, param_comparison, ColumnPredicate};
+use crate::statistics::IndexType;
+use cso_core::expression::ScalarExpression;
+use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+/// A bound of the keys scanned in an index: the values of the leading key columns, which the keys are compared with
+/// in the order of the index, and whether the keys equal to the values are scanned.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyBound {
+    values: Vec<Rc<dyn ScalarExpression>>,
+    inclusive: bool,
+}
+
+impl KeyBound {
+    pub fn new(values: Vec<Rc<dyn ScalarExpression>>, inclusive: bool) -> Self {
+        Self { values, inclusive }
+    }
+
+    pub fn values(&self) -> &[Rc<dyn ScalarExpression>] {
+        &self.values
+    }
+
+    pub fn inclusive(&self) -> bool {
+        self.inclusive
+    }
+}
+
+/// The keys scanned in an index, from `start` to `stop` in the order of the index, unbounded on a side without bound.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexRange {
+    start: Option<KeyBound>,
+    stop: Option<KeyBound>,
+}
+
+impl IndexRange {
+    pub fn new(start: Option<KeyBound>, stop: Option<KeyBound>) -> Self {
+        Self { start, stop }
+    }
+
+    pub fn start(&self) -> Option<&KeyBound> {
+        self.start.as_ref()
+    }
+
+    pub fn stop(&self) -> Option<&KeyBound> {
+        self.stop.as_ref()
+    }
+
+    /// Returns whether every key of the index is scanned.
+    pub fn is_full(&self) -> bool {
+        self.start.is_none() && self.stop.is_none()
+    }
+}
+
+/// The conjuncts of a predicate classified by how a scan of an index evaluates them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexMatch {
+    /// Conjuncts the index finds the rows of, which bound the range of scanned keys of an ordered index
+    conditions: Vec<Rc<dyn ScalarExpression>>,
+    /// Conjuncts on the columns of the index evaluated on the scanned entries
+    filters: Vec<Rc<dyn ScalarExpression>>,
+    /// Conjuncts evaluated on the rows of the relation
+    residual: Vec<Rc<dyn ScalarExpression>>,
+    range: IndexRange,
+}
+
+impl IndexMatch {
+    pub fn conditions(&self) -> &[Rc<dyn ScalarExpression>] {
+        &self.conditions
+    }
+
+    pub fn filters(&self) -> &[Rc<dyn ScalarExpression>] {
+        &self.filters
+    }
+
+    pub fn residual(&self) -> &[Rc<dyn ScalarExpression>] {
+        &self.residual
+    }
+
+    pub fn range(&self) -> &IndexRange {
+        &self.range
+    }
+}
+
+/// Classifies the conjuncts of a predicate against an index.
+///
+/// An ordered index is searched by equalities on a prefix of its keys followed by at most one range of the next key,
+/// which bound the range of scanned keys. A `key IS NULL` is an equality, the index keeps the NULL keys together.
+/// Other index types are searched by the comparisons they support on any key, a hash index only by equalities on all
+/// its keys. A key on an expression, e.g. `lower(name)`, is matched by the comparisons of the same expression. The
+/// other conjuncts are filters if the index has their columns, residual otherwise.
+pub fn match_index(index_desc: &IndexDesc, conjuncts: &[Rc<dyn ScalarExpression>]) -> IndexMatch {
+    let index_type = index_desc.index_type();
+    let keys = index_desc.keys();
+    let mut unmatched: Vec<_> = conjuncts.iter().map(Some).collect();
+    let mut conditions = vec![];
+    let mut range = IndexRange::default();
+
+    if index_type.delivers_order() {
+        let mut start_values = vec![];
+        let mut stop_values = vec![];
+        let (mut start_inclusive, mut stop_inclusive) = (true, true);
+        for (key, key_order) in keys.iter().zip(index_desc.key_orders()) {
+            let equality = take_comparison(&mut unmatched, key.as_ref(), |op| op == CmpOp::Equal);
+            if let Some((conjunct, _, value)) = equality {
+                conditions.push(conjunct);
+                start_values.push(value.clone());
+                stop_values.push(value);
+                continue;
+            }
+
+            // the first bound of each side, the others are filters
+            let lower = take_comparison(&mut unmatched, key.as_ref(), |op| {
+                matches!(op, CmpOp::GreaterThan | CmpOp::GreaterThanEqual)
+            });
+            let upper = take_comparison(&mut unmatched, key.as_ref(), |op| {
+                matches!(op, CmpOp::LessThan | CmpOp::LessThanEqual)
+            });
+            let (lower, upper) = match key_order.ascending {
+                true => (lower, upper),
+                false => (upper, lower),
+            };
+            if let Some((conjunct, op, value)) = lower {
+                conditions.push(conjunct);
+                start_values.push(value);
+                start_inclusive = matches!(op, CmpOp::GreaterThanEqual | CmpOp::LessThanEqual);
+            }
+            if let Some((conjunct, op, value)) = upper {
+                conditions.push(conjunct);
+                stop_values.push(value);
+                stop_inclusive = matches!(op, CmpOp::GreaterThanEqual | CmpOp::LessThanEqual);
+            }
+            break;
+        }
+        let bound = |values: Vec<_>, inclusive| (!values.is_empty()).then(|| KeyBound::new(values, inclusive));
+        range = IndexRange::new(bound(start_values, start_inclusive), bound(stop_values, stop_inclusive));
+    } else {
+        let mut key_columns = ColumnRefSet::new();
+        index_desc
+            .key_columns()
+            .for_each(|key| key.derive_used_columns(&mut key_columns));
+        for conjunct in unmatched.iter_mut() {
+            let matched = conjunct.filter(|conjunct| {
+                let mut used_columns = ColumnRefSet::new();
+                conjunct.derive_used_columns(&mut used_columns);
+                let on_key_columns =
+                    key_columns.is_superset(&used_columns) && index_supports(index_type, conjunct.as_ref());
+                on_key_columns || expression_key_supports(index_desc, conjunct.as_ref())
+            });
+            if let Some(matched) = matched {
+                conditions.push(matched.clone());
+                *conjunct = None;
+            }
+        }
+
+        let equal_keys = keys.iter().all(|key| {
+            conditions
+                .iter()
+                .any(|condition| is_key_equality(condition.as_ref(), key.as_ref()))
+        });
+        if index_type.requires_all_keys() && !equal_keys {
+            // the index cannot be searched, the conditions are left to be filters
+            unmatched = conjuncts.iter().map(Some).collect();
+            conditions.clear();
+        }
+    }
+
+    let mut index_columns = ColumnRefSet::new();
+    index_desc
+        .key_columns()
+        .chain(index_desc.included_columns())
+        .for_each(|column| column.derive_used_columns(&mut index_columns));
+    let (mut filters, mut residual) = (vec![], vec![]);
+    for conjunct in unmatched.into_iter().flatten() {
+        let mut used_columns = ColumnRefSet::new();
+        conjunct.derive_used_columns(&mut used_columns);
+        match !index_type.is_lossy() && index_columns.is_superset(&used_columns) {
+            true => filters.push(conjunct.clone()),
+            false => residual.push(conjunct.clone()),
+        }
+    }
+
+    IndexMatch {
+        conditions,
+        filters,
+        residual,
+        range,
+    }
+}
+
+/// Returns whether an index of `index_type` finds the rows satisfying the conjunct: a B-tree index any predicate on its
+/// key columns, the other index types only the comparisons with constants and parameters they support.
+pub fn index_supports(index_type: IndexType, conjunct: &dyn ScalarExpression) -> bool {
+    if index_type == IndexType::Btree {
+        return true;
+    }
+    match column_predicate(conjunct) {
+        Some((_, ColumnPredicate::Compare(CmpOp::Equal, _) | ColumnPredicate::InList(_))) => true,
+        Some((_, ColumnPredicate::Compare(CmpOp::NotEqual, _))) => false,
+        Some((_, ColumnPredicate::Compare(..))) => index_type.supports_range(),
+        Some((_, ColumnPredicate::IsNull | ColumnPredicate::IsNotNull)) => index_type.supports_null_test(),
+        None => match param_comparison(conjunct) {
+            Some((_, CmpOp::Equal)) => true,
+            Some((_, CmpOp::NotEqual)) | None => false,
+            Some(_) => index_type.supports_range(),
+        },
+    }
+}
+
+/// Returns whether an index finds the rows satisfying a comparison of one of its key expressions.
+fn expression_key_supports(index_desc: &IndexDesc, conjunct: &dyn ScalarExpression) -> bool {
+    let index_type = index_desc.index_type();
+    index_desc
+        .keys()
+        .iter()
+        .filter(|key| key.downcast_ref::<ColumnVar>().is_none())
+        .any(|key| match key_comparison(conjunct, key.as_ref()) {
+            Some((CmpOp::Equal, value)) if is_null(value.as_ref()) => index_type.supports_null_test(),
+            Some((CmpOp::Equal, _)) => true,
+            Some((CmpOp::NotEqual, _)) | None => false,
+            Some(_) => index_type.supports_range(),
+        })
+}
+
+/// Returns whether a conjunct compares `key` for equality with constants or a parameter.
+fn is_key_equality(conjunct: &dyn ScalarExpression, key: &dyn ScalarExpression) -> bool {
+    let column_equality = equality_column(conjunct).is_some_and(|column| key.equal(column));
+    column_equality || matches!(key_comparison(conjunct, key), Some((CmpOp::Equal, value)) if !is_null(value.as_ref()))
+}
+
+/// Returns the column of a conjunct comparing it for equality with constants or a parameter.
+fn equality_column(conjunct: &dyn ScalarExpression) -> Option<&ColumnVar> {
+    match column_predicate(conjunct) {
+        Some((column, ColumnPredicate::Compare(CmpOp::Equal, _) | ColumnPredicate::InList(_))) => Some(column),
+        Some(_) => None,
+        None => match param_comparison(conjunct) {
+            Some((column, CmpOp::Equal)) => Some(column),
+            _ => None,
+        },
+    }
+}
+
+/// A conjunct comparing a key, with the operator and the value compared with.
+type KeyComparison = (Rc<dyn ScalarExpression>, CmpOp, Rc<dyn ScalarExpression>);
+
+/// Removes the first unmatched conjunct comparing `key` with a value by an operator satisfying `op`, and returns it
+/// with the operator and the value.
+fn take_comparison(
+    unmatched: &mut [Option<&Rc<dyn ScalarExpression>>],
+    key: &dyn ScalarExpression,
+    op: impl Fn(CmpOp) -> bool,
+) -> Option<KeyComparison> {
+    for conjunct in unmatched.iter_mut() {
+        let comparison = conjunct.and_then(|conjunct| key_comparison(conjunct.as_ref(), key));
+        match comparison {
+            Some((cmp_op, value)) if op(cmp_op) => {
+                let conjunct = conjunct.take().unwrap().clone();
+                return Some((conjunct, cmp_op, value));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Matches `key op value` and `value op key` with a constant or a parameter value, and `key IS NULL`, an equality with
+/// NULL. A key column may be converted to a type it is implicitly converted to, see `column_predicate`.
+fn key_comparison(
+    conjunct: &dyn ScalarExpression,
+    key: &dyn ScalarExpression,
+) -> Option<(CmpOp, Rc<dyn ScalarExpression>)> {
+    if let Some((column, predicate)) = column_predicate(conjunct) {
+        return match predicate {
+            ColumnPredicate::Compare(op, datum) if key.equal(column) => Some((op, Rc::new(Const::from(&datum)))),
+            ColumnPredicate::IsNull if key.equal(column) => Some((CmpOp::Equal, Rc::new(Const::Null))),
+            _ => None,
+        };
+    }
+    if let Some((column, op)) = param_comparison(conjunct) {
+        let (_, left, right) = as_comparison(conjunct)?;
+        let param = match left.downcast_ref::<Param>() {
+            Some(_) => left,
+            None => right,
+        };
+        return key.equal(column).then(|| (op, Rc::from(param.clone_box())));
+    }
+
+    if let Some(is_null) = conjunct.downcast_ref::<IsNull>() {
+        return is_null
+            .inner()
+            .equal(key)
+            .then(|| (CmpOp::Equal, Rc::new(Const::Null) as Rc<dyn ScalarExpression>));
+    }
+    let is_value = |expr: &dyn ScalarExpression| {
+        expr.downcast_ref::<Param>().is_some() || expr.downcast_ref::<Const>().is_some_and(|value| !is_null(value))
+    };
+    match as_comparison(conjunct)? {
+        (op, left, right) if left.equal(key) && is_value(right) => Some((op, Rc::from(right.clone_box()))),
+        (op, left, right) if right.equal(key) && is_value(left) => Some((op.commute(), Rc::from(left.clone_box()))),
+        _ => None,
+    }
+}
+
+fn is_null(expr: &dyn ScalarExpression) -> bool {
+    matches!(expr.downcast_ref::<Const>(), Some(Const::Null))
+}
@@ -4,17 +4,27 @@
 #![allow(clippy::new_without_default)]
 #![allow(clippy::borrowed_box)]
 
-use crate::operator::OperatorId;
+use crate::metadata::MdId;
+use crate::operator::{DemoOperatorSerde, OperatorId};
+use crate::property::DemoPropertySerde;
 use crate::rule::RuleId;
 use cso_core::OptimizerType;
 
 pub mod cost;
 pub mod datum;
+pub mod eval;
 pub mod expression;
+pub mod fold;
+pub mod implication;
+pub mod index_match;
+pub mod metadata;
+pub mod normalize;
 pub mod operator;
 pub mod property;
 pub mod rule;
+pub mod selectivity;
 pub mod statistics;
+pub mod typecheck;
 
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub struct Demo;
@@ -22,22 +32,14 @@ pub struct Demo;
 impl OptimizerType for Demo {
     type RuleId = RuleId;
     type OperatorId = OperatorId;
-    type MdId = u64;
+    type MdId = MdId;
+    type OperatorSerde = DemoOperatorSerde;
+    type PropertySerde = DemoPropertySerde;
 }
 
+pub use cso_core::datatype::DataType;
 pub use cso_core::Options;
 
-pub mod metadata {
-    use crate::Demo;
-
-    pub type MdAccessor = cso_core::metadata::MdAccessor<Demo>;
-    pub type MdCache = cso_core::metadata::MdCache<Demo>;
-    pub type MdProvider = dyn cso_core::metadata::MdProvider<Demo>;
-    pub type CachedMdProvider = cso_core::metadata::CachedMdProvider<Demo>;
-    pub use cso_core::metadata::Metadata;
-    pub use cso_core::metadata::Stats;
-}
-
 pub(crate) type GroupPlan = cso_core::memo::GroupPlan<Demo>;
 pub(crate) type GroupRef = cso_core::memo::GroupRef<Demo>;
 pub(crate) type Pattern = cso_core::rule::Pattern<Demo>;
@@ -48,3 +50,4 @@ pub type OptimizerContext = cso_core::OptimizerContext<Demo>;
 pub type LogicalPlan = cso_core::LogicalPlan<Demo>;
 pub type PhysicalPlan = cso_core::PhysicalPlan<Demo>;
 pub type Optimizer = cso_core::Optimizer<Demo>;
+pub type Minidump = cso_core::minidump::Minidump<Demo>;
@@ -0,0 +1,89 @@
+use crate::Demo;
+use serde::{Deserialize, Serialize};
+
+pub type MdAccessor = cso_core::metadata::MdAccessor<Demo>;
+pub type MdCache = cso_core::metadata::MdCache<Demo>;
+pub type MdProvider = dyn cso_core::metadata::MdProvider<Demo>;
+pub type CachedMdProvider = cso_core::metadata::CachedMdProvider<Demo>;
+pub type CachingMdProvider = cso_core::metadata::CachingMdProvider<Demo>;
+pub type LayeredMdProvider = cso_core::metadata::LayeredMdProvider<Demo>;
+pub type LruMdCache = cso_core::metadata::LruMdCache<Demo>;
+pub type FileMdProvider = cso_core::metadata::FileMdProvider<Demo>;
+pub type MdValidator = cso_core::metadata::MdValidator<Demo>;
+pub use cso_core::metadata::Metadata;
+pub use cso_core::metadata::Stats;
+pub use cso_core::metadata::VersionedMdId;
+
+/// The kind of the metadata object a metadata id refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MdKind {
+    Relation,
+    RelationStats,
+    ColumnStats,
+    Index,
+}
+
+/// The id of a version of a metadata object.
+///
+/// A new version of an object, e.g. the statistics of a relation after ANALYZE or a relation after DDL, has a new id,
+/// so cached metadata never has to be compared with the catalog: it is current as long as the catalog still names its
+/// id as the current version, see `MdProvider::is_current`.
+///
+/// Metadata references other metadata with version 0, which stands for the current version of the object, so that a
+/// new version of the statistics of a relation does not change the id of the relation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MdId {
+    kind: MdKind,
+    oid: u64, // object id
+    version: u64,
+}
+
+impl MdId {
+    pub const fn new(kind: MdKind, oid: u64) -> Self {
+        MdId { kind, oid, version: 0 }
+    }
+
+    pub const fn relation(oid: u64) -> Self {
+        MdId::new(MdKind::Relation, oid)
+    }
+
+    pub const fn relation_stats(oid: u64) -> Self {
+        MdId::new(MdKind::RelationStats, oid)
+    }
+
+    pub const fn column_stats(oid: u64) -> Self {
+        MdId::new(MdKind::ColumnStats, oid)
+    }
+
+    pub const fn index(oid: u64) -> Self {
+        MdId::new(MdKind::Index, oid)
+    }
+
+    pub const fn with_version(mut self, version: u64) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn kind(&self) -> MdKind {
+        self.kind
+    }
+
+    pub fn oid(&self) -> u64 {
+        self.oid
+    }
+
+    /// Returns whether both ids refer to versions of the same object.
+    pub fn same_object(&self, other: &MdId) -> bool {
+        self.kind == other.kind && self.oid == other.oid
+    }
+}
+
+impl VersionedMdId for MdId {
+    fn unversioned(&self) -> Self {
+        self.with_version(0)
+    }
+
+    fn version(&self) -> u64 {
+        self.version
+    }
+}
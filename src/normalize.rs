@@ -0,0 +1,244 @@
+use crate::expression::{
+    as_comparison, new_comparison, And, Between, CmpOp, Const, InList, IsNotNull, IsNull, Like, Not, Or,
+};
+use cso_core::expression::ScalarExpression;
+use std::rc::Rc;
+
+pub const DEFAULT_CNF_MAX_CONJUNCTS: usize = 64; // max number of conjuncts of a predicate converted to CNF
+pub const DEFAULT_DNF_MAX_DISJUNCTS: usize = 64; // max number of disjuncts of a predicate converted to DNF
+
+/// Normalizes a predicate.
+///
+/// Nested conjunctions and disjunctions are flattened, negations are pushed down to the comparisons and null tests
+/// (`NOT (a AND b)` is `NOT a OR NOT b`, `NOT (a < b)` is `a >= b`, `NOT a IS NULL` is `a IS NOT NULL`, `NOT a IN
+/// (b)` is `a NOT IN (b)`), `a BETWEEN b AND c` is expanded to `a >= b AND a <= c`, duplicated operands are removed
+/// and the conjuncts common to all the operands of a disjunction are factored out of it (`(a AND b) OR (a AND c)` is
+/// `a AND (b OR c)`). The rewrites hold in three-valued logic.
+pub fn normalize(expr: &dyn ScalarExpression) -> Box<dyn ScalarExpression> {
+    normalize_node(expr, false)
+}
+
+/// Normalizes a filter predicate, the predicate is returned as is if it is already normalized.
+pub fn normalize_predicate(predicate: &Rc<dyn ScalarExpression>) -> Rc<dyn ScalarExpression> {
+    let normalized = normalize(predicate.as_ref());
+    if normalized.equal(predicate.as_ref()) {
+        predicate.clone()
+    } else {
+        Rc::from(normalized)
+    }
+}
+
+/// Normalizes a predicate and converts it to conjunctive normal form, a conjunction of disjunctions.
+///
+/// Converting to CNF may multiply the size of the predicate, the normalized predicate is returned as is if its CNF
+/// has more than `max_conjuncts` conjuncts.
+pub fn to_cnf(expr: &dyn ScalarExpression, max_conjuncts: usize) -> Box<dyn ScalarExpression> {
+    let normalized = normalize(expr);
+    match normal_form_clauses(normalized.as_ref(), true, max_conjuncts) {
+        Some(clauses) => {
+            let conjuncts = clauses
+                .into_iter()
+                .map(|disjuncts| connective(false, disjuncts))
+                .collect();
+            connective(true, conjuncts)
+        }
+        None => normalized,
+    }
+}
+
+/// Normalizes a predicate and converts it to disjunctive normal form, a disjunction of conjunctions.
+///
+/// The normalized predicate is returned as is if its DNF has more than `max_disjuncts` disjuncts. Unlike `normalize`,
+/// the conjuncts common to all the disjuncts are not factored out.
+pub fn to_dnf(expr: &dyn ScalarExpression, max_disjuncts: usize) -> Box<dyn ScalarExpression> {
+    let normalized = normalize(expr);
+    match normal_form_clauses(normalized.as_ref(), false, max_disjuncts) {
+        Some(clauses) => {
+            let mut disjuncts = vec![];
+            for conjuncts in clauses {
+                push_unique(&mut disjuncts, connective(true, conjuncts));
+            }
+            match disjuncts.len() {
+                1 => disjuncts.pop().unwrap(),
+                _ => Box::new(Or::new(disjuncts)),
+            }
+        }
+        None => normalized,
+    }
+}
+
+/// Normalizes an expression, or its negation if `negated` is true.
+fn normalize_node(expr: &dyn ScalarExpression, negated: bool) -> Box<dyn ScalarExpression> {
+    if let Some(not) = expr.downcast_ref::<Not>() {
+        normalize_node(not.expression(), !negated)
+    } else if let Some(and) = expr.downcast_ref::<And>() {
+        let operands = and
+            .expressions()
+            .iter()
+            .map(|expr| normalize_node(expr.as_ref(), negated));
+        connective(!negated, operands.collect())
+    } else if let Some(or) = expr.downcast_ref::<Or>() {
+        let operands = or
+            .expressions()
+            .iter()
+            .map(|expr| normalize_node(expr.as_ref(), negated));
+        connective(negated, operands.collect())
+    } else if let (true, Some((op, left, right))) = (negated, as_comparison(expr)) {
+        new_comparison(op.negate(), left.clone_box(), right.clone_box())
+    } else if let (true, Some(is_null)) = (negated, expr.downcast_ref::<IsNull>()) {
+        Box::new(IsNotNull::new(is_null.inner().clone_box()))
+    } else if let (true, Some(is_not_null)) = (negated, expr.downcast_ref::<IsNotNull>()) {
+        Box::new(IsNull::new(is_not_null.inner().clone_box()))
+    } else if let Some(between) = expr.downcast_ref::<Between>() {
+        // a NOT BETWEEN b AND c is a < b OR a > c
+        let (low, high) = (
+            new_comparison(
+                CmpOp::GreaterThanEqual,
+                between.expr().clone_box(),
+                between.low().clone_box(),
+            ),
+            new_comparison(
+                CmpOp::LessThanEqual,
+                between.expr().clone_box(),
+                between.high().clone_box(),
+            ),
+        );
+        normalize_node(
+            &And::new(vec![Rc::from(low), Rc::from(high)]),
+            between.negated() != negated,
+        )
+    } else if let (true, Some(in_list)) = (negated, expr.downcast_ref::<InList>()) {
+        Box::new(in_list.negate())
+    } else if let (true, Some(like)) = (negated, expr.downcast_ref::<Like>()) {
+        Box::new(like.negate())
+    } else if negated {
+        Box::new(Not::new(expr.clone_box()))
+    } else {
+        expr.clone_box()
+    }
+}
+
+/// Returns the operands of a conjunction (`is_and` is true) or of a disjunction (`is_and` is false), or the expression
+/// itself.
+fn operands_of(expr: &dyn ScalarExpression, is_and: bool) -> Vec<Box<dyn ScalarExpression>> {
+    match (is_and, expr.downcast_ref::<And>(), expr.downcast_ref::<Or>()) {
+        (true, Some(and), _) => and.expressions().iter().map(|expr| expr.as_ref().clone_box()).collect(),
+        (false, _, Some(or)) => or.expressions().to_vec(),
+        _ => vec![expr.clone_box()],
+    }
+}
+
+fn push_unique(operands: &mut Vec<Box<dyn ScalarExpression>>, operand: Box<dyn ScalarExpression>) {
+    if !operands.iter().any(|expr| expr.equal(operand.as_ref())) {
+        operands.push(operand);
+    }
+}
+
+/// Builds a flattened conjunction (`is_and` is true) or disjunction (`is_and` is false) of distinct normalized
+/// operands, factoring the common conjuncts out of a disjunction.
+fn connective(is_and: bool, operands: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+    let mut flattened = vec![];
+    for operand in operands {
+        for operand in operands_of(operand.as_ref(), is_and) {
+            push_unique(&mut flattened, operand);
+        }
+    }
+
+    if flattened.len() == 1 {
+        return flattened.pop().unwrap();
+    }
+    // an empty conjunction is true, an empty disjunction is false
+    if flattened.is_empty() && !is_and {
+        return Box::new(Const::Bool(false));
+    }
+    if is_and {
+        return Box::new(And::new(flattened.into_iter().map(Rc::from).collect()));
+    }
+
+    // (a AND b) OR (a AND c) is a AND (b OR c)
+    let conjuncts: Vec<_> = flattened
+        .iter()
+        .map(|operand| operands_of(operand.as_ref(), true))
+        .collect();
+    let common: Vec<_> = conjuncts[0]
+        .iter()
+        .filter(|conjunct| {
+            conjuncts[1..]
+                .iter()
+                .all(|others| others.iter().any(|other| other.equal(conjunct.as_ref())))
+        })
+        .cloned()
+        .collect();
+    if common.is_empty() {
+        return Box::new(Or::new(flattened));
+    }
+
+    let mut remainders = vec![];
+    for conjuncts in conjuncts {
+        let remainder: Vec<_> = conjuncts
+            .into_iter()
+            .filter(|conjunct| !common.iter().any(|expr| expr.equal(conjunct.as_ref())))
+            .collect();
+        // a OR (a AND b) is a
+        if remainder.is_empty() {
+            return connective(true, common);
+        }
+        remainders.push(connective(true, remainder));
+    }
+
+    let mut factored = common;
+    factored.push(connective(false, remainders));
+    connective(true, factored)
+}
+
+/// Returns the conjuncts of the CNF (`is_and` is true) or the disjuncts of the DNF (`is_and` is false) of a
+/// normalized expression as lists of operands, or `None` if there are more than `max_clauses` of them.
+fn normal_form_clauses(
+    expr: &dyn ScalarExpression,
+    is_and: bool,
+    max_clauses: usize,
+) -> Option<Vec<Vec<Box<dyn ScalarExpression>>>> {
+    let (outer, inner) = match is_and {
+        true => (
+            expr.downcast_ref::<And>().is_some(),
+            expr.downcast_ref::<Or>().is_some(),
+        ),
+        false => (
+            expr.downcast_ref::<Or>().is_some(),
+            expr.downcast_ref::<And>().is_some(),
+        ),
+    };
+    let clauses = if outer {
+        let mut clauses = vec![];
+        for operand in operands_of(expr, is_and) {
+            clauses.extend(normal_form_clauses(operand.as_ref(), is_and, max_clauses)?);
+        }
+        clauses
+    } else if inner {
+        // in CNF (a AND b) OR c is (a OR c) AND (b OR c), in DNF (a OR b) AND c is (a AND c) OR (b AND c)
+        let mut clauses = vec![vec![]];
+        for operand in operands_of(expr, !is_and) {
+            let operand_clauses = normal_form_clauses(operand.as_ref(), is_and, max_clauses)?;
+            if clauses.len() * operand_clauses.len() > max_clauses {
+                return None;
+            }
+            clauses = clauses
+                .iter()
+                .flat_map(|clause: &Vec<Box<dyn ScalarExpression>>| {
+                    operand_clauses.iter().map(move |operand_clause| {
+                        let mut clause = clause.clone();
+                        operand_clause
+                            .iter()
+                            .for_each(|operand| push_unique(&mut clause, operand.clone()));
+                        clause
+                    })
+                })
+                .collect();
+        }
+        clauses
+    } else {
+        vec![vec![expr.clone_box()]]
+    };
+
+    (clauses.len() <= max_clauses).then_some(clauses)
+}
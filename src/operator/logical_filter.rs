@@ -1,11 +1,16 @@
-use crate::expression::And;
+use crate::expression::{bind_params, And};
+use crate::fold::fold_predicate;
 use crate::metadata::MdAccessor;
-use crate::operator::OperatorId;
-use crate::{Demo, Plan};
+use crate::normalize::normalize_predicate;
+use crate::operator::{OperatorId, SerializableLogicalOperator};
+use crate::selectivity::derive_filter_stats;
+use crate::statistics::Statistics;
+use crate::{Demo, Options, Plan};
 use cso_core::expression::ScalarExpression;
 use cso_core::metadata::Stats;
 use cso_core::operator::LogicalOperator;
 use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
 pub fn split_predicate(input: &Rc<dyn ScalarExpression>, predicates: &mut Vec<Rc<dyn ScalarExpression>>) {
@@ -19,14 +24,16 @@ pub fn split_predicate(input: &Rc<dyn ScalarExpression>, predicates: &mut Vec<Rc
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LogicalFilter {
     predicate: Rc<dyn ScalarExpression>,
 }
 
 impl LogicalFilter {
+    /// Creates a filter, the predicate is normalized and its constants are folded.
     pub fn new(predicate: Rc<dyn ScalarExpression>) -> Self {
         assert!(predicate.is_boolean_expression());
+        let predicate = fold_predicate(&normalize_predicate(&predicate));
         LogicalFilter { predicate }
     }
 
@@ -50,8 +57,20 @@ impl LogicalOperator<Demo> for LogicalFilter {
         &OperatorId::LogicalFilter
     }
 
-    fn derive_statistics(&self, _md_accessor: &MdAccessor, input_stats: &[Rc<dyn Stats>]) -> Rc<dyn Stats> {
-        input_stats[0].clone()
+    fn derive_statistics(
+        &self,
+        _md_accessor: &MdAccessor,
+        options: &Options,
+        input_stats: &[Rc<dyn Stats>],
+    ) -> Rc<dyn Stats> {
+        debug_assert_eq!(input_stats.len(), 1);
+        let input_stats = input_stats[0]
+            .as_any()
+            .downcast_ref::<Statistics>()
+            .expect("Statistics expected");
+        // the values bound to the parameters of the predicate are estimated like constants
+        let predicate = bind_params(self.predicate.as_ref(), options);
+        Rc::new(derive_filter_stats(input_stats, predicate.as_ref()))
     }
 
     fn derive_output_columns(&self, inputs: &[Plan], column_set: &mut ColumnRefSet) {
@@ -59,3 +78,6 @@ impl LogicalOperator<Demo> for LogicalFilter {
         inputs[0].derive_output_columns(column_set);
     }
 }
+
+#[typetag::serde]
+impl SerializableLogicalOperator for LogicalFilter {}
@@ -1,43 +1,109 @@
-use crate::expression::ColumnVar;
-use crate::metadata::MdAccessor;
-use crate::operator::logical_scan::{derive_scan_stats, TableDesc};
-use crate::operator::OperatorId;
-use crate::statistics::{IndexMd, IndexType};
-use crate::{Demo, Plan};
+use crate::expression::{bind_params, And, ColumnVar};
+use crate::metadata::{MdAccessor, MdId};
+use crate::operator::logical_scan::{derive_scan_stats, relation_column_vars, remap_columns, TableDesc};
+use crate::operator::{OperatorId, SerializableLogicalOperator};
+use crate::selectivity::derive_filter_stats;
+use crate::statistics::{IndexKeyOrder, IndexMd, IndexType, Statistics};
+use crate::{Demo, Options, Plan};
 use cso_core::expression::ScalarExpression;
 use cso_core::metadata::Stats;
 use cso_core::operator::LogicalOperator;
 use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IndexDesc {
-    mdid: u64,
+    mdid: MdId,
     name: String,
     index_type: IndexType,
-    key_columns: Vec<ColumnVar>,
+    keys: Vec<Rc<dyn ScalarExpression>>,
     included_columns: Vec<ColumnVar>,
+    unique: bool,
+    leaf_pages: u64,
+    height: u32,
+    correlation: f64,
+    key_orders: Vec<IndexKeyOrder>,
+    predicate: Option<Rc<dyn ScalarExpression>>,
 }
 
 impl IndexDesc {
     pub fn new(
-        mdid: u64,
+        mdid: MdId,
         name: String,
         index_type: IndexType,
         key_columns: Vec<ColumnVar>,
         included_columns: Vec<ColumnVar>,
     ) -> Self {
+        let key_orders = vec![IndexKeyOrder::default(); key_columns.len()];
         Self {
             mdid,
             name,
             index_type,
-            key_columns,
+            keys: key_columns
+                .into_iter()
+                .map(|column| Rc::new(column) as Rc<dyn ScalarExpression>)
+                .collect(),
             included_columns,
+            unique: false,
+            leaf_pages: 1,
+            height: 1,
+            correlation: 0.0,
+            key_orders,
+            predicate: None,
         }
     }
 
-    pub fn key_columns(&self) -> &[ColumnVar] {
-        &self.key_columns
+    /// Describes the index of `index_md`, with its storage, ordering and predicate, on `columns`, the column vars of
+    /// the columns of the relation in order, e.g. the output columns of its scan. The columns of the index metadata
+    /// are positions of columns in the relation. Returns `None` if a column of the index has no column var.
+    pub fn from_index_md(index_md: &IndexMd, columns: &[ColumnVar]) -> Option<Self> {
+        let column_vars = relation_column_vars(columns);
+        let keys = index_md
+            .keys()
+            .iter()
+            .map(|key| remap_columns(key.as_ref(), &column_vars).map(Rc::from))
+            .collect::<Option<_>>()?;
+        let included_columns = index_md
+            .included_columns()
+            .iter()
+            .map(|column| columns.get(column.id() as usize).cloned())
+            .collect::<Option<_>>()?;
+        let predicate = match index_md.predicate() {
+            Some(predicate) => Some(Rc::from(remap_columns(predicate, &column_vars)?)),
+            None => None,
+        };
+        let index_desc = IndexDesc::new(
+            index_md.mdid(),
+            index_md.index_name().to_string(),
+            index_md.index_type(),
+            vec![],
+            included_columns,
+        );
+        Some(Self {
+            keys,
+            unique: index_md.unique(),
+            leaf_pages: index_md.leaf_pages(),
+            height: index_md.height(),
+            correlation: index_md.correlation(),
+            key_orders: index_md.key_orders(),
+            predicate,
+            ..index_desc
+        })
+    }
+
+    pub fn index_type(&self) -> IndexType {
+        self.index_type
+    }
+
+    /// Returns the keys of the index in order, columns or expressions of the columns.
+    pub fn keys(&self) -> &[Rc<dyn ScalarExpression>] {
+        &self.keys
+    }
+
+    /// Returns the keys that are columns, in order.
+    pub fn key_columns(&self) -> impl Iterator<Item = &ColumnVar> {
+        self.keys.iter().filter_map(|key| key.downcast_ref::<ColumnVar>())
     }
 
     pub fn included_columns(&self) -> &[ColumnVar] {
@@ -45,16 +111,58 @@ impl IndexDesc {
     }
 
     pub fn key_columns_count(&self) -> usize {
-        self.key_columns.len()
+        self.key_columns().count()
+    }
+
+    /// Returns the number of keys, on columns and on expressions.
+    pub fn key_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns the predicate of the rows of a partial index.
+    pub fn predicate(&self) -> Option<&Rc<dyn ScalarExpression>> {
+        self.predicate.as_ref()
+    }
+
+    pub fn unique(&self) -> bool {
+        self.unique
+    }
+
+    pub fn leaf_pages(&self) -> u64 {
+        self.leaf_pages
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn correlation(&self) -> f64 {
+        self.correlation
+    }
+
+    pub fn key_orders(&self) -> &[IndexKeyOrder] {
+        &self.key_orders
+    }
+
+    /// Returns whether the key and included columns of the index contain `columns`, which an index-only scan reads
+    /// without looking up the rows in the table. A lossy index covers no column.
+    pub fn covers(&self, columns: &[ColumnVar]) -> bool {
+        !self.index_type.is_lossy()
+            && columns
+                .iter()
+                .all(|column| self.key_columns().any(|key| key == column) || self.included_columns.contains(column))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LogicalIndexScan {
     index_desc: IndexDesc,
     table_desc: TableDesc,
     output_columns: Vec<ColumnVar>,
     predicate: Rc<dyn ScalarExpression>,
+    index_only: bool,
+    #[serde(default)]
+    implied_conjuncts_dropped: bool, // conjuncts of the filter implied by the predicate of the index are not scanned for
 }
 
 impl LogicalIndexScan {
@@ -64,22 +172,34 @@ impl LogicalIndexScan {
         output_columns: Vec<ColumnVar>,
         predicate: Rc<dyn ScalarExpression>,
     ) -> Self {
-        let index_desc = IndexDesc::new(
-            index_md.mdid(),
-            index_md.index_name().to_string(),
-            index_md.index_type(),
-            index_md.key_columns().to_vec(),
-            index_md.included_columns().to_vec(),
-        );
+        let index_desc = IndexDesc::from_index_md(index_md, &output_columns)
+            .expect("expect the index columns among the output columns");
+        let index_only = index_desc.covers(&output_columns);
 
         Self {
             index_desc,
             table_desc: table_desc.clone(),
             output_columns,
             predicate,
+            index_only,
+            implied_conjuncts_dropped: false,
         }
     }
 
+    /// Sets whether the columns used by the plan are in the index, so the rows need not be looked up in the table.
+    /// By default, whether the index covers the output columns.
+    pub fn with_index_only(mut self, index_only: bool) -> Self {
+        self.index_only = index_only;
+        self
+    }
+
+    /// Sets whether conjuncts of the filter were left out of the predicate because the predicate of the partial index
+    /// implies them. The statistics then estimate the predicate of the index in their place.
+    pub fn with_implied_conjuncts_dropped(mut self, implied_conjuncts_dropped: bool) -> Self {
+        self.implied_conjuncts_dropped = implied_conjuncts_dropped;
+        self
+    }
+
     pub fn index_desc(&self) -> &IndexDesc {
         &self.index_desc
     }
@@ -95,6 +215,10 @@ impl LogicalIndexScan {
     pub fn predicate(&self) -> &Rc<dyn ScalarExpression> {
         &self.predicate
     }
+
+    pub fn index_only(&self) -> bool {
+        self.index_only
+    }
 }
 
 impl LogicalOperator<Demo> for LogicalIndexScan {
@@ -106,11 +230,29 @@ impl LogicalOperator<Demo> for LogicalIndexScan {
         &OperatorId::LogicalIndexScan
     }
 
-    fn derive_statistics(&self, md_accessor: &MdAccessor, input_stats: &[Rc<dyn Stats>]) -> Rc<dyn Stats> {
-        let base_table_stats = derive_scan_stats(md_accessor, input_stats, self.table_desc());
+    fn derive_statistics(
+        &self,
+        md_accessor: &MdAccessor,
+        options: &Options,
+        input_stats: &[Rc<dyn Stats>],
+    ) -> Rc<dyn Stats> {
+        let base_table_stats = derive_scan_stats(md_accessor, input_stats, self.table_desc(), self.output_columns());
+        let base_table_stats = base_table_stats
+            .as_any()
+            .downcast_ref::<Statistics>()
+            .expect("Statistics expected");
 
-        // todo: derive index scan stats from base_table_stats and index desc.
-        base_table_stats
+        // The rows are estimated like a filter of the predicate over a scan of the relation, so that the statistics
+        // agree with the other alternatives of the group. The conjuncts implied by the predicate of a partial index
+        // are estimated by the predicate of the index, which is not estimated otherwise: the filter implies it, so
+        // adding it would count its selectivity twice. The values bound to the parameters are estimated like
+        // constants.
+        let predicate = match (self.implied_conjuncts_dropped, self.index_desc.predicate()) {
+            (true, Some(index_predicate)) => Rc::new(And::new(vec![self.predicate.clone(), index_predicate.clone()])),
+            _ => self.predicate.clone(),
+        };
+        let predicate = bind_params(predicate.as_ref(), options);
+        Rc::new(derive_filter_stats(base_table_stats, predicate.as_ref()))
     }
 
     fn derive_output_columns(&self, inputs: &[Plan], column_set: &mut ColumnRefSet) {
@@ -120,3 +262,6 @@ impl LogicalOperator<Demo> for LogicalIndexScan {
             .for_each(|expr| expr.derive_used_columns(column_set));
     }
 }
+
+#[typetag::serde]
+impl SerializableLogicalOperator for LogicalIndexScan {}
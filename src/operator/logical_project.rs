@@ -1,13 +1,16 @@
+use crate::expression::ColumnVar;
 use crate::metadata::MdAccessor;
-use crate::operator::OperatorId;
-use crate::{Demo, Plan};
+use crate::operator::{OperatorId, SerializableLogicalOperator};
+use crate::statistics::Statistics;
+use crate::{Demo, Options, Plan};
 use cso_core::expression::ScalarExpression;
 use cso_core::metadata::Stats;
 use cso_core::operator::LogicalOperator;
 use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LogicalProject {
     project: Vec<Rc<dyn ScalarExpression>>,
 }
@@ -31,8 +34,25 @@ impl LogicalOperator<Demo> for LogicalProject {
         &OperatorId::LogicalProject
     }
 
-    fn derive_statistics(&self, _md_accessor: &MdAccessor, input_stats: &[Rc<dyn Stats>]) -> Rc<dyn Stats> {
-        input_stats[0].clone()
+    fn derive_statistics(
+        &self,
+        _md_accessor: &MdAccessor,
+        _options: &Options,
+        input_stats: &[Rc<dyn Stats>],
+    ) -> Rc<dyn Stats> {
+        debug_assert_eq!(input_stats.len(), 1);
+        let input_stats = input_stats[0]
+            .as_any()
+            .downcast_ref::<Statistics>()
+            .expect("Statistics expected");
+
+        // only the projected columns keep their statistics
+        let columns: Vec<ColumnVar> = self
+            .project
+            .iter()
+            .filter_map(|expr| expr.downcast_ref::<ColumnVar>().cloned())
+            .collect();
+        Rc::new(input_stats.project(&columns))
     }
 
     fn derive_output_columns(&self, inputs: &[Plan], column_set: &mut ColumnRefSet) {
@@ -43,3 +63,6 @@ impl LogicalOperator<Demo> for LogicalProject {
             .for_each(|scalar| scalar.derive_used_columns(column_set))
     }
 }
+
+#[typetag::serde]
+impl SerializableLogicalOperator for LogicalProject {}
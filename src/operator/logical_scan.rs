@@ -1,33 +1,38 @@
-use crate::expression::ColumnVar;
-use crate::metadata::MdAccessor;
-use crate::operator::OperatorId;
-use crate::statistics::{RelationMetadata, RelationStats, Statistics};
-use crate::{Demo, Plan};
+use crate::expression::{referenced_columns, substitute_columns, ColumnVar};
+use crate::metadata::{MdAccessor, MdId};
+use crate::operator::logical_index_scan::IndexDesc;
+use crate::operator::{OperatorId, SerializableLogicalOperator};
+use crate::statistics::{
+    ColumnMetadata, ColumnStats, IndexMd, RelationMetadata, RelationStats, RelationStorage, Statistics,
+};
+use crate::{Demo, Options, Plan};
 use cso_core::expression::ScalarExpression;
 use cso_core::metadata::Stats;
 use cso_core::operator::LogicalOperator;
 use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::rc::Rc;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TableDesc {
-    md_id: u64,
+    md_id: MdId,
 }
 
 impl TableDesc {
-    pub const fn new(md_id: u64) -> Self {
+    pub const fn new(md_id: MdId) -> Self {
         Self { md_id }
     }
 
-    pub fn md_id(&self) -> u64 {
+    pub fn md_id(&self) -> MdId {
         self.md_id
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LogicalScan {
     table_desc: TableDesc,
-    output_columns: Vec<ColumnVar>,
+    output_columns: Vec<ColumnVar>, // Column vars of the relation columns, in the order of the relation
 }
 
 impl LogicalScan {
@@ -47,10 +52,35 @@ impl LogicalScan {
     }
 }
 
+/// Maps the positions of the columns of a relation to `output_columns`, the column vars of the columns of a scan of the
+/// relation, see `remap_columns`.
+pub fn relation_column_vars(output_columns: &[ColumnVar]) -> HashMap<u32, Box<dyn ScalarExpression>> {
+    output_columns
+        .iter()
+        .enumerate()
+        .map(|(col, column)| (col as u32, Box::new(column.clone()) as Box<dyn ScalarExpression>))
+        .collect()
+}
+
+/// Remaps an expression of the metadata of a relation, whose columns are positions of columns in the relation, onto
+/// the column vars of a scan of the relation. Returns `None` if a column has no column var.
+pub fn remap_columns(
+    expr: &dyn ScalarExpression,
+    column_vars: &HashMap<u32, Box<dyn ScalarExpression>>,
+) -> Option<Box<dyn ScalarExpression>> {
+    referenced_columns(expr)
+        .iter()
+        .all(|column| column_vars.contains_key(&column.id()))
+        .then(|| substitute_columns(expr, column_vars))
+}
+
+/// Derives the statistics of a scan of the table, `output_columns[i]` is the column var of the i-th column of the
+/// relation.
 pub fn derive_scan_stats(
     md_accessor: &MdAccessor,
     input_stats: &[Rc<dyn Stats>],
     table_desc: &TableDesc,
+    output_columns: &[ColumnVar],
 ) -> Rc<dyn Stats> {
     debug_assert!(input_stats.is_empty());
 
@@ -72,15 +102,63 @@ pub fn derive_scan_stats(
 
     let output_row_count = rel_stats.rows();
 
-    let mut column_stats = Vec::new();
+    let mut column_stats = HashMap::new();
     for col_stats_md_id in rel_stats.col_stat_mdids() {
         let col_stats = md_accessor
             .retrieve_metadata(col_stats_md_id)
             .expect("Missing metadata");
-        column_stats.push(col_stats);
+        let col_stats = col_stats.downcast_ref::<ColumnStats>().expect("ColumnStats expected");
+        if let Some(column) = output_columns.get(col_stats.col_id()) {
+            column_stats.insert(column.id(), col_stats.clone());
+        }
     }
 
-    let stats = Statistics::new(output_row_count, column_stats);
+    let extended_stats = rel_md
+        .extended_stats()
+        .iter()
+        .filter_map(|extended_stats| {
+            extended_stats.map_columns(|col| output_columns.get(col as usize).map(|column| column.id()))
+        })
+        .collect();
+
+    // constraints on columns without a column var are dropped
+    let column_id = |col: u32| output_columns.get(col as usize).map(|column| column.id());
+    let mut keys: Vec<Vec<u32>> = rel_md
+        .keys()
+        .iter()
+        .filter_map(|key| key.iter().map(|col| column_id(*col)).collect())
+        .collect();
+    // the key columns of a unique index are a key, unless the index is partial or has keys on expressions
+    for i in 0..rel_md.index_count() {
+        let index_md = md_accessor
+            .retrieve_metadata(&rel_md.index_mdid(i))
+            .expect("Missing metadata");
+        let index_md = index_md.downcast_ref::<IndexMd>().expect("IndexMd expected");
+        if index_md.unique() && index_md.key_columns().count() == index_md.key_count() && index_md.predicate().is_none()
+        {
+            let index_desc = IndexDesc::from_index_md(index_md, output_columns);
+            keys.extend(index_desc.map(|index_desc| index_desc.key_columns().map(ColumnVar::id).collect()));
+        }
+    }
+    let not_null_columns = rel_md.not_null_columns().into_iter().filter_map(column_id).collect();
+    let column_vars = relation_column_vars(output_columns);
+    let check_constraints = rel_md
+        .check_constraints()
+        .filter_map(|predicate| remap_columns(predicate, &column_vars).map(Rc::from))
+        .collect();
+
+    let relation_storage = rel_stats.storage().unwrap_or_else(|| {
+        let row_width = rel_md.column_metadata().iter().map(ColumnMetadata::width).sum();
+        RelationStorage::estimate(output_row_count, row_width, 1.0)
+    });
+
+    let stats = Statistics::new(output_row_count, column_stats)
+        .with_extended_stats(extended_stats)
+        .with_keys(keys)
+        .with_not_null_columns(not_null_columns)
+        .with_check_constraints(check_constraints)
+        .with_relation_row_count(Some(output_row_count))
+        .with_relation_storage(Some(relation_storage));
     Rc::new(stats)
 }
 
@@ -93,8 +171,13 @@ impl LogicalOperator<Demo> for LogicalScan {
         &OperatorId::LogicalScan
     }
 
-    fn derive_statistics(&self, md_accessor: &MdAccessor, input_stats: &[Rc<dyn Stats>]) -> Rc<dyn Stats> {
-        derive_scan_stats(md_accessor, input_stats, self.table_desc())
+    fn derive_statistics(
+        &self,
+        md_accessor: &MdAccessor,
+        _options: &Options,
+        input_stats: &[Rc<dyn Stats>],
+    ) -> Rc<dyn Stats> {
+        derive_scan_stats(md_accessor, input_stats, self.table_desc(), self.output_columns())
     }
 
     fn derive_output_columns(&self, inputs: &[Plan], column_set: &mut ColumnRefSet) {
@@ -104,3 +187,6 @@ impl LogicalOperator<Demo> for LogicalScan {
             .for_each(|expr| expr.derive_used_columns(column_set));
     }
 }
+
+#[typetag::serde]
+impl SerializableLogicalOperator for LogicalScan {}
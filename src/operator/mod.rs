@@ -1,10 +1,25 @@
+use crate::operator::logical_filter::LogicalFilter;
+use crate::operator::logical_index_scan::LogicalIndexScan;
+use crate::operator::logical_project::LogicalProject;
+use crate::operator::logical_scan::LogicalScan;
+use crate::operator::physical_filter::PhysicalFilter;
+use crate::operator::physical_index_lookup::PhysicalIndexLookup;
+use crate::operator::physical_index_scan::PhysicalIndexScan;
+use crate::operator::physical_project::PhysicalProject;
+use crate::operator::physical_scan::PhysicalScan;
+use crate::operator::physical_sort::PhysicalSort;
 use crate::Demo;
+use cso_core::operator::OperatorSerde;
+use serde::ser::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::rc::Rc;
 
 pub mod logical_filter;
 pub mod logical_index_scan;
 pub mod logical_project;
 pub mod logical_scan;
 pub mod physical_filter;
+pub mod physical_index_lookup;
 pub mod physical_index_scan;
 pub mod physical_project;
 pub mod physical_scan;
@@ -23,7 +38,80 @@ pub enum OperatorId {
 
     PhysicalScan,
     PhysicalIndexScan,
+    PhysicalIndexLookup,
     PhysicalFilter,
     PhysicalProject,
     PhysicalSort,
 }
+
+/// A logical operator serialized with typetag, which does not support the generic `LogicalOperator<T>` trait.
+#[typetag::serde(tag = "type")]
+pub trait SerializableLogicalOperator: cso_core::operator::LogicalOperator<Demo> {}
+
+/// A physical operator serialized with typetag, which does not support the generic `PhysicalOperator<T>` trait.
+#[typetag::serde(tag = "type")]
+pub trait SerializablePhysicalOperator: cso_core::operator::PhysicalOperator<Demo> {}
+
+/// Serializes the operators of the demo optimizer through `SerializableLogicalOperator` and
+/// `SerializablePhysicalOperator`.
+pub struct DemoOperatorSerde;
+
+impl DemoOperatorSerde {
+    fn as_serializable_logical(op: &LogicalOperator) -> Option<&dyn SerializableLogicalOperator> {
+        if let Some(op) = op.downcast_ref::<LogicalScan>() {
+            Some(op)
+        } else if let Some(op) = op.downcast_ref::<LogicalFilter>() {
+            Some(op)
+        } else if let Some(op) = op.downcast_ref::<LogicalProject>() {
+            Some(op)
+        } else if let Some(op) = op.downcast_ref::<LogicalIndexScan>() {
+            Some(op)
+        } else {
+            None
+        }
+    }
+
+    fn as_serializable_physical(op: &PhysicalOperator) -> Option<&dyn SerializablePhysicalOperator> {
+        if let Some(op) = op.downcast_ref::<PhysicalScan>() {
+            Some(op)
+        } else if let Some(op) = op.downcast_ref::<PhysicalIndexScan>() {
+            Some(op)
+        } else if let Some(op) = op.downcast_ref::<PhysicalIndexLookup>() {
+            Some(op)
+        } else if let Some(op) = op.downcast_ref::<PhysicalFilter>() {
+            Some(op)
+        } else if let Some(op) = op.downcast_ref::<PhysicalProject>() {
+            Some(op)
+        } else if let Some(op) = op.downcast_ref::<PhysicalSort>() {
+            Some(op)
+        } else {
+            None
+        }
+    }
+}
+
+impl OperatorSerde<Demo> for DemoOperatorSerde {
+    fn serialize_logical<S: Serializer>(op: &LogicalOperator, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::as_serializable_logical(op)
+            .ok_or_else(|| S::Error::custom(format!("unexpected logical operator: {}", op.name())))?
+            .serialize(serializer)
+    }
+
+    fn deserialize_logical<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rc<LogicalOperator>, D::Error> {
+        let op: Rc<dyn SerializableLogicalOperator> =
+            Rc::from(Box::<dyn SerializableLogicalOperator>::deserialize(deserializer)?);
+        Ok(op)
+    }
+
+    fn serialize_physical<S: Serializer>(op: &PhysicalOperator, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::as_serializable_physical(op)
+            .ok_or_else(|| S::Error::custom(format!("unexpected physical operator: {}", op.name())))?
+            .serialize(serializer)
+    }
+
+    fn deserialize_physical<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rc<PhysicalOperator>, D::Error> {
+        let op: Rc<dyn SerializablePhysicalOperator> =
+            Rc::from(Box::<dyn SerializablePhysicalOperator>::deserialize(deserializer)?);
+        Ok(op)
+    }
+}
@@ -1,14 +1,15 @@
 use crate::cost::COST_FILTER_COL_COST_UNIT;
-use crate::operator::{OperatorId, PhysicalOperator};
+use crate::operator::{OperatorId, PhysicalOperator, SerializablePhysicalOperator};
 use crate::property::PhysicalProperties;
 use crate::Demo;
 use cso_core::cost::Cost;
 use cso_core::expression::ScalarExpression;
 use cso_core::metadata::Stats;
 use cso_core::ColumnRefSet;
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PhysicalFilter {
     predicate: Rc<dyn ScalarExpression>,
 }
@@ -65,3 +66,6 @@ impl PartialEq for PhysicalFilter {
         self.predicate.equal(other.predicate())
     }
 }
+
+#[typetag::serde]
+impl SerializablePhysicalOperator for PhysicalFilter {}
@@ -0,0 +1,139 @@
+use crate::cost::{
+    decompression_cost, relation_storage, IoCost, COST_CPU_OPERATOR_COST_UNIT, COST_CPU_TUPLE_COST_UNIT,
+};
+use crate::expression::ColumnVar;
+use crate::operator::logical_index_scan::IndexDesc;
+use crate::operator::logical_scan::TableDesc;
+use crate::operator::physical_index_scan::PhysicalIndexScan;
+use crate::operator::{OperatorId, PhysicalOperator, SerializablePhysicalOperator};
+use crate::property::PhysicalProperties;
+use crate::Demo;
+use cso_core::cost::Cost;
+use cso_core::expression::ScalarExpression;
+use cso_core::metadata::Stats;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+/// A scan of an index followed by a fetch of the rows it finds from the relation, for the columns the index does not
+/// have. The rows found by a lossy index are rechecked against the conditions of the scan.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PhysicalIndexLookup {
+    index_scan: PhysicalIndexScan,
+}
+
+impl PhysicalIndexLookup {
+    pub fn new(
+        index_desc: IndexDesc,
+        table_desc: TableDesc,
+        output_columns: Vec<ColumnVar>,
+        predicate: Rc<dyn ScalarExpression>,
+    ) -> Self {
+        let index_scan = PhysicalIndexScan::new(index_desc, table_desc, output_columns, predicate);
+        PhysicalIndexLookup { index_scan }
+    }
+
+    /// Sets the estimated fraction of the entries of the index the conditions select, see
+    /// `PhysicalIndexScan::with_scanned_fraction`.
+    pub fn with_scanned_fraction(mut self, scanned_fraction: f64) -> Self {
+        self.index_scan = self.index_scan.with_scanned_fraction(scanned_fraction);
+        self
+    }
+
+    /// Sets the estimated fraction of the rows of the relation a partial index has entries for, see
+    /// `PhysicalIndexScan::with_indexed_fraction`.
+    pub fn with_indexed_fraction(mut self, indexed_fraction: f64) -> Self {
+        self.index_scan = self.index_scan.with_indexed_fraction(indexed_fraction);
+        self
+    }
+
+    /// Returns the scan of the index producing the rows to fetch.
+    pub fn index_scan(&self) -> &PhysicalIndexScan {
+        &self.index_scan
+    }
+
+    pub fn index_desc(&self) -> &IndexDesc {
+        self.index_scan.index_desc()
+    }
+
+    pub fn table_desc(&self) -> &TableDesc {
+        self.index_scan.table_desc()
+    }
+
+    pub fn output_columns(&self) -> &[ColumnVar] {
+        self.index_scan.output_columns()
+    }
+
+    pub fn predicate(&self) -> &Rc<dyn ScalarExpression> {
+        self.index_scan.predicate()
+    }
+}
+
+impl cso_core::operator::PhysicalOperator<Demo> for PhysicalIndexLookup {
+    fn name(&self) -> &str {
+        "physical index lookup"
+    }
+
+    fn operator_id(&self) -> &OperatorId {
+        &OperatorId::PhysicalIndexLookup
+    }
+
+    /// The rows are fetched in the order the index finds them.
+    fn derive_output_properties(&self, child_props: &[Rc<PhysicalProperties>]) -> Rc<PhysicalProperties> {
+        self.index_scan.derive_output_properties(child_props)
+    }
+
+    fn required_properties(&self, _input_prop: Rc<PhysicalProperties>) -> Vec<Vec<Rc<PhysicalProperties>>> {
+        vec![vec![]]
+    }
+
+    /// The cost of the index scan, plus a random read for every fetched row. The rows on a page read before are not
+    /// read again, and the rows of an index correlating with the order of the rows are on consecutive pages, like the
+    /// index scan costing of PostgreSQL.
+    fn compute_cost(&self, stats: Option<&dyn Stats>) -> Cost {
+        debug_assert!(stats.is_some());
+        let stats = stats.unwrap();
+        let index_cost = self.index_scan.index_cost(stats);
+
+        // a lossy index finds every row of the scanned entries
+        let (relation_row_count, storage) = relation_storage(stats);
+        let (fetched_rows, recheck_count) = match self.index_desc().index_type().is_lossy() {
+            true => (
+                self.index_scan.scanned_fraction(stats) * self.index_scan.index_entries(stats),
+                self.index_scan.index_match().conditions().len() as f64,
+            ),
+            false => (stats.output_row_count() as f64, 0.0),
+        };
+        let fetched_fraction = match relation_row_count {
+            0 => 1.0,
+            relation_row_count => (fetched_rows / relation_row_count as f64).min(1.0),
+        };
+
+        // Mackert and Lohman's estimate of the distinct pages fetched by random lookups
+        let pages = storage.pages() as f64;
+        let random_pages = match storage.pages() {
+            0 => 0.0,
+            _ => (2.0 * pages * fetched_rows / (2.0 * pages + fetched_rows)).min(pages),
+        };
+        let clustered_pages = (fetched_fraction * pages).ceil();
+        let correlation = self.index_desc().correlation() * self.index_desc().correlation();
+        let heap_pages = correlation * clustered_pages + (1.0 - correlation) * random_pages;
+        let heap_cpu = fetched_rows * (COST_CPU_TUPLE_COST_UNIT + recheck_count * COST_CPU_OPERATOR_COST_UNIT)
+            + decompression_cost(&storage, heap_pages);
+        let heap_cost = IoCost::new(
+            correlation * clustered_pages,
+            (1.0 - correlation) * random_pages,
+            heap_cpu,
+        );
+        (index_cost + heap_cost).cost()
+    }
+
+    fn equal(&self, other: &PhysicalOperator) -> bool {
+        match other.downcast_ref::<PhysicalIndexLookup>() {
+            Some(other) => self.eq(other),
+            None => false,
+        }
+    }
+}
+
+#[typetag::serde]
+impl SerializablePhysicalOperator for PhysicalIndexLookup {}
@@ -1,23 +1,31 @@
-use crate::cost::{COST_INDEX_FILTER_COST_UNIT, COST_INDEX_SCAN_TUP_COST_UNIT, COST_INDEX_SCAN_TUP_RANDOM_FACTOR};
+use crate::cost::{relation_storage, IoCost, COST_CPU_INDEX_TUPLE_COST_UNIT, COST_CPU_OPERATOR_COST_UNIT};
 use crate::expression::ColumnVar;
+use crate::index_match::{match_index, IndexMatch, IndexRange};
+use crate::operator::logical_filter::split_predicate;
 use crate::operator::logical_index_scan::IndexDesc;
 use crate::operator::logical_scan::TableDesc;
 use crate::operator::physical_sort::{OrderSpec, Ordering};
-use crate::operator::{OperatorId, PhysicalOperator};
+use crate::operator::{OperatorId, PhysicalOperator, SerializablePhysicalOperator};
 use crate::property::sort_property::SortProperty;
 use crate::property::PhysicalProperties;
 use crate::Demo;
 use cso_core::cost::Cost;
 use cso_core::expression::ScalarExpression;
 use cso_core::metadata::Stats;
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
-#[derive(Clone, Debug)]
+/// A scan of the entries of an index, which returns the columns of the rows from the index alone. The rows of an index
+/// that does not have all the columns are fetched from the relation by a `PhysicalIndexLookup`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PhysicalIndexScan {
     index_desc: IndexDesc,
     table_desc: TableDesc,
     output_columns: Vec<ColumnVar>,
     predicate: Rc<dyn ScalarExpression>,
+    index_match: IndexMatch,       // the conjuncts of the predicate matched against the index
+    scanned_fraction: Option<f64>, // estimated fraction of the entries of the index in the range of the conditions
+    indexed_fraction: Option<f64>, // estimated fraction of the rows of the relation a partial index has entries for
 }
 
 impl PhysicalIndexScan {
@@ -27,13 +35,89 @@ impl PhysicalIndexScan {
         output_columns: Vec<ColumnVar>,
         predicate: Rc<dyn ScalarExpression>,
     ) -> Self {
+        let mut conjuncts = vec![];
+        split_predicate(&predicate, &mut conjuncts);
+        let index_match = match_index(&index_desc, &conjuncts);
         PhysicalIndexScan {
             index_desc,
             table_desc,
             output_columns,
             predicate,
+            index_match,
+            scanned_fraction: None,
+            indexed_fraction: None,
         }
     }
+
+    /// Sets the estimated fraction of the entries of the index the conditions select. By default, the fraction of the
+    /// rows of the relation the scan returns.
+    pub fn with_scanned_fraction(mut self, scanned_fraction: f64) -> Self {
+        self.scanned_fraction = Some(scanned_fraction.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Sets the estimated fraction of the rows of the relation the predicate of a partial index selects. By default,
+    /// the index has an entry for every row.
+    pub fn with_indexed_fraction(mut self, indexed_fraction: f64) -> Self {
+        self.indexed_fraction = Some(indexed_fraction.clamp(0.0, 1.0));
+        self
+    }
+
+    pub fn index_desc(&self) -> &IndexDesc {
+        &self.index_desc
+    }
+
+    pub fn table_desc(&self) -> &TableDesc {
+        &self.table_desc
+    }
+
+    pub fn output_columns(&self) -> &[ColumnVar] {
+        &self.output_columns
+    }
+
+    pub fn predicate(&self) -> &Rc<dyn ScalarExpression> {
+        &self.predicate
+    }
+
+    /// Returns the conditions, filters and range of keys of the scan.
+    pub fn index_match(&self) -> &IndexMatch {
+        &self.index_match
+    }
+
+    /// Returns the range of keys scanned.
+    pub fn range(&self) -> &IndexRange {
+        self.index_match.range()
+    }
+
+    /// Returns the estimated number of entries of the index, one for every row of the relation a partial index has.
+    pub(crate) fn index_entries(&self, stats: &dyn Stats) -> f64 {
+        let (relation_row_count, _) = relation_storage(stats);
+        relation_row_count as f64 * self.indexed_fraction.unwrap_or(1.0)
+    }
+
+    /// Returns the fraction of the entries of the index scanned to return the rows described by `stats`.
+    pub(crate) fn scanned_fraction(&self, stats: &dyn Stats) -> f64 {
+        let row_count = stats.output_row_count() as f64;
+        let fraction = match self.index_entries(stats) {
+            index_entries if index_entries > 0.0 => (row_count / index_entries).min(1.0),
+            _ => 1.0,
+        };
+        self.scanned_fraction.unwrap_or(fraction).max(fraction)
+    }
+
+    /// The index is descended once, at random, then the leaf pages of the keys in the range of the conditions are read
+    /// sequentially and the filters are evaluated on their entries.
+    pub(crate) fn index_cost(&self, stats: &dyn Stats) -> IoCost {
+        let scanned_fraction = self.scanned_fraction(stats);
+        let scanned_entries = scanned_fraction * self.index_entries(stats).max(1.0);
+
+        let leaf_pages = (scanned_fraction * self.index_desc.leaf_pages() as f64).ceil().max(1.0);
+        let key_count = self.index_desc.key_count() as f64;
+        let operator_count = key_count + self.index_match.filters().len() as f64;
+        let index_cpu =
+            scanned_entries * (COST_CPU_INDEX_TUPLE_COST_UNIT + operator_count * COST_CPU_OPERATOR_COST_UNIT);
+        IoCost::new(leaf_pages, self.index_desc.height() as f64, index_cpu)
+    }
 }
 
 impl cso_core::operator::PhysicalOperator<Demo> for PhysicalIndexScan {
@@ -47,11 +131,23 @@ impl cso_core::operator::PhysicalOperator<Demo> for PhysicalIndexScan {
 
     fn derive_output_properties(&self, child_props: &[Rc<PhysicalProperties>]) -> Rc<PhysicalProperties> {
         debug_assert!(child_props.is_empty());
-        let key_columns = self.index_desc.key_columns();
-
+        if !self.index_desc.index_type().delivers_order() {
+            return Rc::new(PhysicalProperties::new());
+        }
         let mut order_desc = vec![];
-        for key in key_columns {
-            order_desc.push(Ordering::new(key.id()));
+        for (key, key_order) in self.index_desc.keys().iter().zip(self.index_desc.key_orders()) {
+            // an order on an expression is not a sort property, the keys after it are ordered within its values only
+            let Some(key) = key.downcast_ref::<ColumnVar>() else {
+                break;
+            };
+            order_desc.push(Ordering {
+                key: key.clone(),
+                ascending: key_order.ascending,
+                nulls_first: key_order.nulls_first,
+            });
+        }
+        if order_desc.is_empty() {
+            return Rc::new(PhysicalProperties::new());
         }
 
         let sort_prop = SortProperty::with_order(OrderSpec { order_desc });
@@ -64,11 +160,7 @@ impl cso_core::operator::PhysicalOperator<Demo> for PhysicalIndexScan {
 
     fn compute_cost(&self, stats: Option<&dyn Stats>) -> Cost {
         debug_assert!(stats.is_some());
-
-        let index_key_column_count = self.index_desc.key_columns_count() as f64;
-        let cost_per_index_row = index_key_column_count * COST_INDEX_FILTER_COST_UNIT + COST_INDEX_SCAN_TUP_COST_UNIT;
-        let row_count = stats.unwrap().output_row_count() as f64;
-        Cost::new(row_count * cost_per_index_row + COST_INDEX_SCAN_TUP_RANDOM_FACTOR)
+        self.index_cost(stats.unwrap()).cost()
     }
 
     fn equal(&self, other: &PhysicalOperator) -> bool {
@@ -79,6 +171,7 @@ impl cso_core::operator::PhysicalOperator<Demo> for PhysicalIndexScan {
     }
 }
 
+// the index match is derived from the predicate, the scanned and indexed fractions are estimates
 impl PartialEq for PhysicalIndexScan {
     fn eq(&self, other: &Self) -> bool {
         self.index_desc == other.index_desc
@@ -87,3 +180,6 @@ impl PartialEq for PhysicalIndexScan {
             && self.predicate.as_ref() == other.predicate.as_ref()
     }
 }
+
+#[typetag::serde]
+impl SerializablePhysicalOperator for PhysicalIndexScan {}
@@ -1,13 +1,14 @@
 use crate::cost::COST_TUP_DEFAULT_PROC_COST_UNIT;
-use crate::operator::{OperatorId, PhysicalOperator};
+use crate::operator::{OperatorId, PhysicalOperator, SerializablePhysicalOperator};
 use crate::property::PhysicalProperties;
 use crate::Demo;
 use cso_core::cost::Cost;
 use cso_core::expression::ScalarExpression;
 use cso_core::metadata::Stats;
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct PhysicalProject {
     _project: Vec<Rc<dyn ScalarExpression>>,
 }
@@ -49,3 +50,6 @@ impl cso_core::operator::PhysicalOperator<Demo> for PhysicalProject {
         }
     }
 }
+
+#[typetag::serde]
+impl SerializablePhysicalOperator for PhysicalProject {}
@@ -1,14 +1,15 @@
-use crate::cost::{COST_INIT_SCAN_FACTOR, COST_TABLE_SCAN_COST_UNIT};
+use crate::cost::{decompression_cost, relation_storage, IoCost, COST_CPU_TUPLE_COST_UNIT};
 use crate::expression::ColumnVar;
 use crate::operator::logical_scan::TableDesc;
-use crate::operator::{OperatorId, PhysicalOperator};
+use crate::operator::{OperatorId, PhysicalOperator, SerializablePhysicalOperator};
 use crate::property::PhysicalProperties;
 use crate::Demo;
 use cso_core::cost::Cost;
 use cso_core::metadata::Stats;
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PhysicalScan {
     table_desc: TableDesc,
     output_columns: Vec<ColumnVar>,
@@ -40,11 +41,14 @@ impl cso_core::operator::PhysicalOperator<Demo> for PhysicalScan {
         vec![vec![]]
     }
 
+    /// Every page of the relation is read sequentially and every row is processed.
     fn compute_cost(&self, stats: Option<&dyn Stats>) -> Cost {
         debug_assert!(stats.is_some());
 
-        let row_count = stats.unwrap().output_row_count() as f64;
-        Cost::new(COST_INIT_SCAN_FACTOR + row_count * COST_TABLE_SCAN_COST_UNIT)
+        let (row_count, storage) = relation_storage(stats.unwrap());
+        let pages = storage.pages() as f64;
+        let cpu = row_count as f64 * COST_CPU_TUPLE_COST_UNIT + decompression_cost(&storage, pages);
+        IoCost::new(pages, 0.0, cpu).cost()
     }
 
     fn equal(&self, other: &PhysicalOperator) -> bool {
@@ -54,3 +58,6 @@ impl cso_core::operator::PhysicalOperator<Demo> for PhysicalScan {
         }
     }
 }
+
+#[typetag::serde]
+impl SerializablePhysicalOperator for PhysicalScan {}
@@ -1,14 +1,15 @@
 use crate::cost::COST_SORT_TUP_WIDTH_COST_UNIT;
 use crate::expression::ColumnVar;
-use crate::operator::{OperatorId, PhysicalOperator};
+use crate::operator::{OperatorId, PhysicalOperator, SerializablePhysicalOperator};
 use crate::property::sort_property::SortProperty;
 use crate::property::PhysicalProperties;
 use crate::Demo;
 use cso_core::cost::Cost;
 use cso_core::metadata::Stats;
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
-#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Ordering {
     pub key: ColumnVar,
     pub ascending: bool,
@@ -25,12 +26,12 @@ impl Ordering {
     }
 }
 
-#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct OrderSpec {
     pub order_desc: Vec<Ordering>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct PhysicalSort {
     order_spec: OrderSpec,
 }
@@ -78,3 +79,6 @@ impl cso_core::operator::PhysicalOperator<Demo> for PhysicalSort {
         }
     }
 }
+
+#[typetag::serde]
+impl SerializablePhysicalOperator for PhysicalSort {}
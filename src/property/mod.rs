@@ -1,6 +1,41 @@
+use crate::property::sort_property::SortProperty;
 use crate::Demo;
+use cso_core::property::PropertySerde;
+use serde::ser::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 pub mod sort_property;
 
 pub type PhysicalProperties = cso_core::property::PhysicalProperties<Demo>;
 pub type PhysicalProperty = dyn cso_core::property::PhysicalProperty<Demo>;
+
+/// A physical property serialized with typetag, which does not support the generic `PhysicalProperty<T>` trait.
+#[typetag::serde(tag = "type")]
+pub trait SerializablePhysicalProperty: cso_core::property::PhysicalProperty<Demo> {}
+
+/// Serializes the physical properties of the demo optimizer through `SerializablePhysicalProperty`.
+pub struct DemoPropertySerde;
+
+impl DemoPropertySerde {
+    fn as_serializable(property: &PhysicalProperty) -> Option<&dyn SerializablePhysicalProperty> {
+        if let Some(property) = property.downcast_ref::<SortProperty>() {
+            Some(property)
+        } else {
+            None
+        }
+    }
+}
+
+impl PropertySerde<Demo> for DemoPropertySerde {
+    fn serialize<S: Serializer>(property: &PhysicalProperty, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::as_serializable(property)
+            .ok_or_else(|| S::Error::custom(format!("unexpected physical property: {:?}", property)))?
+            .serialize(serializer)
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Box<PhysicalProperty>, D::Error> {
+        let property: Box<dyn SerializablePhysicalProperty> =
+            Box::<dyn SerializablePhysicalProperty>::deserialize(deserializer)?;
+        Ok(property)
+    }
+}
@@ -1,19 +1,23 @@
 use crate::operator::physical_sort::{OrderSpec, PhysicalSort};
-use crate::property::PhysicalProperty;
+use crate::property::{PhysicalProperty, SerializablePhysicalProperty};
 use crate::Demo;
 use crate::{GroupPlan, GroupRef};
 use cso_core::operator::Operator;
 use cso_core::property::Property;
+use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
-#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct SortProperty {
     order_spec: OrderSpec,
 }
 
 impl Property for SortProperty {}
 
+#[typetag::serde]
+impl SerializablePhysicalProperty for SortProperty {}
+
 impl cso_core::property::PhysicalProperty<Demo> for SortProperty {
     fn hash(&self, mut hasher: &mut dyn Hasher) {
         Hash::hash(self, &mut hasher)
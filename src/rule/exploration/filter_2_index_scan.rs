@@ -1,6 +1,9 @@
 use crate::expression::And;
-use crate::operator::logical_filter::LogicalFilter;
-use crate::operator::logical_index_scan::LogicalIndexScan;
+use crate::implication::implies;
+use crate::index_match::match_index;
+use crate::normalize::{to_cnf, DEFAULT_CNF_MAX_CONJUNCTS};
+use crate::operator::logical_filter::{split_predicate, LogicalFilter};
+use crate::operator::logical_index_scan::{IndexDesc, LogicalIndexScan};
 use crate::operator::logical_scan::LogicalScan;
 use crate::operator::OperatorId;
 use crate::rule::RuleId;
@@ -67,7 +70,10 @@ impl Rule<Demo> for Filter2IndexScan {
         input.derive_output_columns(&mut filter_required_columns);
         filter_required_columns.union_with(&filter_predicate_columns);
 
-        let predicates = logical_filter.split_predicate();
+        // the conjuncts of the CNF of the predicate are matched separately
+        let cnf = Rc::from(to_cnf(predicate.as_ref(), DEFAULT_CNF_MAX_CONJUNCTS));
+        let mut predicates = vec![];
+        split_predicate(&cnf, &mut predicates);
         let mut new_plans = vec![];
         for i in 0..relation_md.index_count() {
             let index_mdid = relation_md.index_mdid(i);
@@ -76,18 +82,24 @@ impl Rule<Demo> for Filter2IndexScan {
                 .expect("Index metadata missed!");
             let index_md = index_md.downcast_ref::<IndexMd>().unwrap();
 
-            if let Some((applicable_predicates, residual_predicates)) = index_matched(
-                index_md,
-                &predicates,
-                &filter_required_columns,
-                &filter_predicate_columns,
-            ) {
+            let Some(index_desc) = IndexDesc::from_index_md(index_md, logical_scan.output_columns()) else {
+                continue;
+            };
+            if let Some((applicable_predicates, residual_predicates, implied_conjuncts_dropped)) =
+                index_matched(&index_desc, &predicates)
+            {
+                // the rows are fetched from the relation unless the index covers the columns used by the filter and
+                // its consumers
                 let logical_index_scan = LogicalIndexScan::new(
                     table_desc.clone(),
                     index_md,
                     logical_scan.output_columns().to_vec(),
                     applicable_predicates,
                 );
+                let index_only = index_covers(logical_index_scan.index_desc(), &filter_required_columns);
+                let logical_index_scan = logical_index_scan
+                    .with_index_only(index_only)
+                    .with_implied_conjuncts_dropped(implied_conjuncts_dropped);
                 let index_scan_plan = Plan::new(Operator::Logical(Rc::new(logical_index_scan)), vec![], None);
 
                 if let Some(residual_predicates) = residual_predicates {
@@ -108,50 +120,64 @@ impl Rule<Demo> for Filter2IndexScan {
     }
 }
 
-type ApplicableAndResidualPredicates = (Rc<dyn ScalarExpression>, Option<Rc<dyn ScalarExpression>>);
+/// The applicable and residual predicates, and whether conjuncts implied by the predicate of the index were dropped.
+type ApplicableAndResidualPredicates = (Rc<dyn ScalarExpression>, Option<Rc<dyn ScalarExpression>>, bool);
 
+/// Matches the conjuncts of the predicate against the index, see `match_index`. The index scan evaluates the
+/// conditions and the filters, the residual conjuncts are left to a filter above it. A partial index is matched only
+/// if the predicate implies the predicate of the index, and is scanned whole if no conjunct is a condition, e.g. when
+/// the predicate of the index implies the whole predicate.
 fn index_matched(
-    index_md: &IndexMd,
+    index_desc: &IndexDesc,
     predicates: &[Rc<dyn ScalarExpression>],
-    required_columns: &ColumnRefSet,
-    predicate_columns: &ColumnRefSet,
 ) -> Option<ApplicableAndResidualPredicates> {
-    let mut key_columns = ColumnRefSet::new();
-    index_md
-        .key_columns()
-        .iter()
-        .for_each(|key| key.derive_used_columns(&mut key_columns));
-
-    let mut include_columns = ColumnRefSet::new();
-    index_md
-        .included_columns()
-        .iter()
-        .for_each(|key| key.derive_used_columns(&mut include_columns));
-
-    if !include_columns.is_superset(required_columns) || key_columns.is_disjoint(predicate_columns) {
-        return None;
-    }
-
-    let mut residual_predicates = Vec::new();
-    let mut applicable_predicates = Vec::new();
-    for expr in predicates {
-        let mut used_columns = ColumnRefSet::new();
-        expr.derive_used_columns(&mut used_columns);
-        if key_columns.is_superset(&used_columns) {
-            applicable_predicates.push(expr.clone());
-        } else {
-            // For now, we are not considering complex indexing scenarios. We simply assume that
-            // when key_columns contain predicate used_columns, the current expr can use this index.
-            residual_predicates.push(expr.clone());
+    // the conjuncts implied by the predicate of a partial index hold for all its rows
+    let conjunct_count = predicates.len();
+    let predicates: Vec<_> = match index_desc.predicate() {
+        Some(index_predicate) => {
+            if !implies(&And::new(predicates.to_vec()), index_predicate.as_ref()) {
+                return None;
+            }
+            predicates
+                .iter()
+                .filter(|predicate| !implies(index_predicate.as_ref(), predicate.as_ref()))
+                .cloned()
+                .collect()
         }
+        None => predicates.to_vec(),
+    };
+    let implied_conjuncts_dropped = predicates.len() < conjunct_count;
+    let index_match = match_index(index_desc, &predicates);
+    if index_match.conditions().is_empty() && index_desc.predicate().is_none() {
+        return None;
     }
-    debug_assert!(!applicable_predicates.is_empty());
-    if residual_predicates.is_empty() {
-        Some((Rc::new(And::new(applicable_predicates)), None))
+    let applicable_predicates = index_match
+        .conditions()
+        .iter()
+        .chain(index_match.filters())
+        .cloned()
+        .collect();
+    if index_match.residual().is_empty() {
+        Some((
+            Rc::new(And::new(applicable_predicates)),
+            None,
+            implied_conjuncts_dropped,
+        ))
     } else {
         Some((
             Rc::new(And::new(applicable_predicates)),
-            Some(Rc::new(And::new(residual_predicates))),
+            Some(Rc::new(And::new(index_match.residual().to_vec()))),
+            implied_conjuncts_dropped,
         ))
     }
 }
+
+/// Returns whether the entries of the index have all the required columns, see `IndexDesc::covers`.
+fn index_covers(index_desc: &IndexDesc, required_columns: &ColumnRefSet) -> bool {
+    let mut index_columns = ColumnRefSet::new();
+    index_desc
+        .key_columns()
+        .chain(index_desc.included_columns())
+        .for_each(|column| column.derive_used_columns(&mut index_columns));
+    !index_desc.index_type().is_lossy() && index_columns.is_superset(required_columns)
+}
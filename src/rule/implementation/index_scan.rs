@@ -1,7 +1,12 @@
+use crate::expression::{bind_params, And};
 use crate::operator::logical_index_scan::LogicalIndexScan;
+use crate::operator::logical_scan::derive_scan_stats;
+use crate::operator::physical_index_lookup::PhysicalIndexLookup;
 use crate::operator::physical_index_scan::PhysicalIndexScan;
-use crate::operator::OperatorId;
+use crate::operator::{OperatorId, PhysicalOperator};
 use crate::rule::RuleId;
+use crate::selectivity::estimate_selectivity;
+use crate::statistics::Statistics;
 use crate::{Demo, Pattern, PatternType};
 use crate::{OptimizerContext, Plan};
 use cso_core::operator::Operator;
@@ -21,7 +26,7 @@ impl IndexScanImplementation {
 
 impl cso_core::rule::Rule<Demo> for IndexScanImplementation {
     fn name(&self) -> &str {
-        "scan implementation"
+        "index scan implementation"
     }
 
     fn rule_id(&self) -> RuleId {
@@ -32,20 +37,70 @@ impl cso_core::rule::Rule<Demo> for IndexScanImplementation {
         &self.pattern
     }
 
-    fn transform(&self, input: &Plan, _context: &mut OptimizerContext) -> Vec<Plan> {
+    fn transform(&self, input: &Plan, context: &mut OptimizerContext) -> Vec<Plan> {
         let logical_index_scan = input
             .operator()
             .logical_op()
             .downcast_ref::<LogicalIndexScan>()
             .unwrap();
-        let physical_index_scan = PhysicalIndexScan::new(
+        let index_scan = PhysicalIndexScan::new(
             logical_index_scan.index_desc().clone(),
             logical_index_scan.table_desc().clone(),
             logical_index_scan.output_columns().to_vec(),
             logical_index_scan.predicate().clone(),
         );
+
+        // the conditions are estimated against the rows of the relation, the statistics of the group describe the
+        // rows the filters return too
+        let relation_stats = derive_scan_stats(
+            context.md_accessor(),
+            &[],
+            logical_index_scan.table_desc(),
+            logical_index_scan.output_columns(),
+        );
+        let relation_stats = relation_stats
+            .as_any()
+            .downcast_ref::<Statistics>()
+            .expect("Statistics expected");
+        let conditions = index_scan.index_match().conditions().to_vec();
+        let conditions = bind_params(&And::new(conditions), context.options());
+        let selectivity = estimate_selectivity(conditions.as_ref(), relation_stats);
+
+        // a partial index has entries for the rows of its predicate only, the conditions select the same rows of the
+        // relation from fewer entries; as the conditions usually imply the predicate, they are not assumed independent
+        let indexed_fraction = match logical_index_scan.index_desc().predicate() {
+            Some(predicate) => estimate_selectivity(
+                bind_params(predicate.as_ref(), context.options()).as_ref(),
+                relation_stats,
+            ),
+            None => 1.0,
+        };
+        let scanned_fraction = match indexed_fraction {
+            indexed_fraction if indexed_fraction > 0.0 => selectivity / indexed_fraction,
+            _ => 1.0,
+        };
+
+        // the rows of an index without the columns used by the plan are fetched from the relation
+        let physical_op: Rc<PhysicalOperator> = match logical_index_scan.index_only() {
+            true => Rc::new(
+                index_scan
+                    .with_scanned_fraction(scanned_fraction)
+                    .with_indexed_fraction(indexed_fraction),
+            ),
+            false => Rc::new(
+                PhysicalIndexLookup::new(
+                    logical_index_scan.index_desc().clone(),
+                    logical_index_scan.table_desc().clone(),
+                    logical_index_scan.output_columns().to_vec(),
+                    logical_index_scan.predicate().clone(),
+                )
+                .with_scanned_fraction(scanned_fraction)
+                .with_indexed_fraction(indexed_fraction),
+            ),
+        };
+
         vec![Plan::new(
-            Operator::Physical(Rc::new(physical_index_scan)),
+            Operator::Physical(physical_op),
             vec![],
             input.group_plan().cloned(),
         )]
@@ -0,0 +1,516 @@
+use crate::datum::Datum;
+use crate::expression::{
+    as_comparison, like_prefix, new_comparison, referenced_columns, And, Between, Cast, CmpOp, ColumnVar, Const,
+    InList, IsNotNull, IsNull, Like, LikePrefix, Not, Or, Param,
+};
+use crate::fold::fold_predicate;
+use crate::statistics::{ColumnStats, Statistics};
+use cso_core::expression::ScalarExpression;
+use cso_core::metadata::Stats;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub const DEFAULT_EQ_SELECTIVITY: f64 = 0.005; // selectivity of `col = const` without histogram
+pub const DEFAULT_INEQ_SELECTIVITY: f64 = 1.0 / 3.0; // selectivity of `col < const` without histogram
+pub const DEFAULT_NULL_SELECTIVITY: f64 = 0.005; // selectivity of `col IS NULL` without column statistics
+pub const DEFAULT_LIKE_SELECTIVITY: f64 = 0.1; // selectivity of the wildcards of a LIKE pattern after its prefix
+pub const DEFAULT_SELECTIVITY: f64 = 0.25; // selectivity of predicates the estimator does not understand
+
+/// A predicate on a single column that the estimator knows how to evaluate against column statistics.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnPredicate {
+    Compare(CmpOp, Datum),
+    InList(Vec<Datum>), // distinct values
+    IsNull,
+    IsNotNull,
+}
+
+/// Matches `column op constant`, `constant op column`, `column IS NULL`, `column IS NOT NULL`, `column IN (constants)`
+/// and disjunctions of equalities of the same column, which are IN-lists. The column may be converted to a type it is
+/// implicitly converted to, which keeps its values ordered.
+pub fn column_predicate(expr: &dyn ScalarExpression) -> Option<(&ColumnVar, ColumnPredicate)> {
+    if let Some(or) = expr.downcast_ref::<Or>() {
+        return in_list(or.expressions().iter().map(|expr| expr.as_ref()));
+    }
+
+    if let Some(is_null) = expr.downcast_ref::<IsNull>() {
+        let column = column_operand(is_null.inner())?;
+        return Some((column, ColumnPredicate::IsNull));
+    }
+
+    if let Some(is_not_null) = expr.downcast_ref::<IsNotNull>() {
+        let column = column_operand(is_not_null.inner())?;
+        return Some((column, ColumnPredicate::IsNotNull));
+    }
+
+    if let Some(in_list) = expr.downcast_ref::<InList>() {
+        if in_list.negated() {
+            return None;
+        }
+        let column = column_operand(in_list.expr())?;
+        let mut values = Vec::new();
+        for item in in_list.list() {
+            let constant = item.downcast_ref::<Const>()?;
+            // NULL never equals the column
+            let datum = constant.to_datum();
+            if !datum.is_null() && !values.contains(&datum) {
+                values.push(datum);
+            }
+        }
+        return match values.is_empty() {
+            true => None,
+            false => Some((column, ColumnPredicate::InList(values))),
+        };
+    }
+
+    let (op, left, right) = as_comparison(expr)?;
+    match (column_operand(left), column_operand(right)) {
+        (Some(column), None) => Some((column, ColumnPredicate::Compare(op, const_datum(right)?))),
+        (None, Some(column)) => Some((column, ColumnPredicate::Compare(op.commute(), const_datum(left)?))),
+        _ => None,
+    }
+}
+
+/// Matches a column, or a column converted to a type it is implicitly converted to.
+fn column_operand(expr: &dyn ScalarExpression) -> Option<&ColumnVar> {
+    match expr.downcast_ref::<Cast>() {
+        Some(cast) => {
+            let column = cast.expr().downcast_ref::<ColumnVar>()?;
+            column
+                .data_type()
+                .can_implicitly_cast_to(&cast.data_type())
+                .then_some(column)
+        }
+        None => expr.downcast_ref::<ColumnVar>(),
+    }
+}
+
+fn in_list<'a>(
+    mut expressions: impl Iterator<Item = &'a dyn ScalarExpression>,
+) -> Option<(&'a ColumnVar, ColumnPredicate)> {
+    let mut values = Vec::new();
+    let (column, first) = match column_predicate(expressions.next()?)? {
+        (column, ColumnPredicate::Compare(CmpOp::Equal, datum)) => (column, datum),
+        _ => return None,
+    };
+    values.push(first);
+
+    for expr in expressions {
+        match column_predicate(expr)? {
+            (col, ColumnPredicate::Compare(CmpOp::Equal, datum)) if col == column => {
+                if !values.contains(&datum) {
+                    values.push(datum);
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some((column, ColumnPredicate::InList(values)))
+}
+
+fn const_datum(expr: &dyn ScalarExpression) -> Option<Datum> {
+    match expr.downcast_ref::<Const>()? {
+        // comparisons with NULL are never true, the estimator leaves them to the default selectivity
+        Const::Null => None,
+        constant => Some(constant.to_datum()),
+    }
+}
+
+fn collect_conjuncts<'a>(expr: &'a dyn ScalarExpression, conjuncts: &mut Vec<&'a dyn ScalarExpression>) {
+    match expr.downcast_ref::<And>() {
+        None => conjuncts.push(expr),
+        Some(and) => and
+            .expressions()
+            .iter()
+            .for_each(|expr| collect_conjuncts(expr.as_ref(), conjuncts)),
+    }
+}
+
+/// Estimates the fraction of the rows described by `stats` that satisfy `predicate`.
+///
+/// Conjunctions and disjunctions are estimated under the assumption that their children are independent, except
+/// equalities of columns correlated by extended statistics and disjunctions of equalities of the same column, which
+/// are estimated as IN-lists.
+pub fn estimate_selectivity(predicate: &dyn ScalarExpression, stats: &Statistics) -> f64 {
+    let selectivity = if let Some((column, col_predicate)) = column_predicate(predicate) {
+        match col_predicate {
+            ColumnPredicate::IsNull if stats.is_not_null(column) => 0.0,
+            ColumnPredicate::IsNotNull if stats.is_not_null(column) => 1.0,
+            _ => column_selectivity(stats.column_stats(column), stats.output_row_count(), &col_predicate),
+        }
+    } else if predicate.downcast_ref::<And>().is_some() {
+        let mut conjuncts = Vec::new();
+        collect_conjuncts(predicate, &mut conjuncts);
+        conjunction_selectivity(conjuncts, stats)
+    } else if let Some(or) = predicate.downcast_ref::<Or>() {
+        1.0 - or
+            .expressions()
+            .iter()
+            .map(|expr| 1.0 - estimate_selectivity(expr.as_ref(), stats))
+            .product::<f64>()
+    } else if let Some(not) = predicate.downcast_ref::<Not>() {
+        1.0 - estimate_selectivity(not.expression(), stats)
+    } else if let Some(in_list) = predicate.downcast_ref::<InList>().filter(|in_list| in_list.negated()) {
+        1.0 - estimate_selectivity(&in_list.negate(), stats)
+    } else if let Some(between) = predicate.downcast_ref::<Between>() {
+        let bounds = And::new(vec![
+            Rc::from(new_comparison(
+                CmpOp::GreaterThanEqual,
+                between.expr().clone_box(),
+                between.low().clone_box(),
+            )),
+            Rc::from(new_comparison(
+                CmpOp::LessThanEqual,
+                between.expr().clone_box(),
+                between.high().clone_box(),
+            )),
+        ]);
+        match between.negated() {
+            true => 1.0 - estimate_selectivity(&bounds, stats),
+            false => estimate_selectivity(&bounds, stats),
+        }
+    } else if let Some(like) = predicate.downcast_ref::<Like>() {
+        match (like.negated(), like_selectivity(like, stats)) {
+            (true, selectivity) => 1.0 - selectivity,
+            (false, selectivity) => selectivity,
+        }
+    } else if let Some((column, op)) = param_comparison(predicate) {
+        generic_compare_selectivity(stats.column_stats(column), stats.output_row_count(), op)
+    } else if let Some(Const::Bool(value)) = predicate.downcast_ref::<Const>() {
+        if *value {
+            1.0
+        } else {
+            0.0
+        }
+    } else {
+        DEFAULT_SELECTIVITY
+    };
+
+    // equalities on the columns of a key select at most one row
+    let selectivity = match stats.output_row_count() {
+        0 => selectivity,
+        row_count if stats.is_unique(&equality_columns(predicate)) => selectivity.min(1.0 / row_count as f64),
+        _ => selectivity,
+    };
+    selectivity.clamp(0.0, 1.0)
+}
+
+/// Returns the columns compared for equality with a constant or a parameter by a conjunct of the predicate.
+fn equality_columns(predicate: &dyn ScalarExpression) -> Vec<u32> {
+    let mut conjuncts = Vec::new();
+    collect_conjuncts(predicate, &mut conjuncts);
+    conjuncts
+        .into_iter()
+        .filter_map(|conjunct| match column_predicate(conjunct) {
+            Some((column, ColumnPredicate::Compare(CmpOp::Equal, _))) => Some(column.id()),
+            Some(_) => None,
+            None => match param_comparison(conjunct) {
+                Some((column, CmpOp::Equal)) => Some(column.id()),
+                _ => None,
+            },
+        })
+        .collect()
+}
+
+/// Returns the columns a conjunct of the predicate rejects the NULL values of.
+fn null_rejected_columns(conjuncts: &[&dyn ScalarExpression]) -> Vec<u32> {
+    conjuncts
+        .iter()
+        .filter_map(|conjunct| match column_predicate(*conjunct) {
+            Some((_, ColumnPredicate::IsNull)) | None => None,
+            Some((column, _)) => Some(column.id()),
+        })
+        .collect()
+}
+
+/// Returns whether no row satisfies both `predicate` and the check constraints of `stats`. A check constraint is also
+/// satisfied by the rows it evaluates to NULL for, so only the constraints on columns without NULL values among the
+/// rows satisfying the predicate, `not_null_columns`, are used.
+fn contradicts_check_constraints(
+    predicate: &dyn ScalarExpression,
+    stats: &Statistics,
+    not_null_columns: &[u32],
+) -> bool {
+    let mut operands: Vec<Rc<dyn ScalarExpression>> = stats
+        .check_constraints()
+        .iter()
+        .filter(|check| {
+            referenced_columns(check.as_ref())
+                .iter()
+                .all(|column| not_null_columns.contains(&column.id()))
+        })
+        .cloned()
+        .collect();
+    if operands.is_empty() {
+        return false;
+    }
+    operands.push(Rc::from(predicate.clone_box()));
+    let conjunction: Rc<dyn ScalarExpression> = Rc::new(And::new(operands));
+    matches!(
+        fold_predicate(&conjunction).downcast_ref::<Const>(),
+        Some(Const::Bool(false))
+    )
+}
+
+/// Matches `column op parameter` and `parameter op column` with an unbound parameter.
+pub fn param_comparison(expr: &dyn ScalarExpression) -> Option<(&ColumnVar, CmpOp)> {
+    let (op, left, right) = as_comparison(expr)?;
+    match (column_operand(left), column_operand(right)) {
+        (Some(column), None) if right.downcast_ref::<Param>().is_some() => Some((column, op)),
+        (None, Some(column)) if left.downcast_ref::<Param>().is_some() => Some((column, op.commute())),
+        _ => None,
+    }
+}
+
+/// Estimates the selectivity of `column op value` for any non-null value, like a generic plan of a prepared
+/// statement: an equality selects the rows of an average distinct value and a range the default fraction of the
+/// non-null values.
+fn generic_compare_selectivity(col_stats: Option<&ColumnStats>, row_count: u64, op: CmpOp) -> f64 {
+    let col_stats = match col_stats {
+        Some(col_stats) if row_count > 0 => col_stats,
+        _ => return default_cmp_selectivity(op),
+    };
+    let non_null_fraction = 1.0 - (col_stats.null_count() as f64 / row_count as f64).min(1.0);
+    let eq_selectivity = match col_stats.ndv() {
+        Some(ndv) if ndv > 0 => non_null_fraction / ndv as f64,
+        _ => DEFAULT_EQ_SELECTIVITY * non_null_fraction,
+    };
+    match op {
+        CmpOp::Equal => eq_selectivity,
+        CmpOp::NotEqual => non_null_fraction - eq_selectivity,
+        _ => DEFAULT_INEQ_SELECTIVITY * non_null_fraction,
+    }
+}
+
+/// Estimates the selectivity of `column LIKE pattern`: the fraction of the values equal to a pattern without wildcard,
+/// or in the range of the values starting with the literal prefix of the pattern.
+fn like_selectivity(like: &Like, stats: &Statistics) -> f64 {
+    let (column, pattern) = match (column_operand(like.expr()), like.pattern().downcast_ref::<Const>()) {
+        (Some(column), Some(Const::Str(pattern))) => (column, pattern),
+        _ => return DEFAULT_SELECTIVITY,
+    };
+    let col_stats = stats.column_stats(column);
+    let row_count = stats.output_row_count();
+    let range_selectivity = |prefix: String| {
+        if prefix.is_empty() {
+            return column_selectivity(col_stats, row_count, &ColumnPredicate::IsNotNull);
+        }
+        if col_stats.is_none() {
+            return DEFAULT_LIKE_SELECTIVITY;
+        }
+        // the values starting with the prefix are in [prefix, prefix + the greatest character)
+        let upper = format!("{prefix}{}", char::MAX);
+        let at_least_prefix = ColumnPredicate::Compare(CmpOp::GreaterThanEqual, Datum::String(prefix));
+        let at_least_upper = ColumnPredicate::Compare(CmpOp::GreaterThanEqual, Datum::String(upper));
+        column_selectivity(col_stats, row_count, &at_least_prefix)
+            - column_selectivity(col_stats, row_count, &at_least_upper)
+    };
+
+    match like_prefix(pattern) {
+        LikePrefix::Exact(value) => {
+            let predicate = ColumnPredicate::Compare(CmpOp::Equal, Datum::String(value));
+            column_selectivity(col_stats, row_count, &predicate)
+        }
+        LikePrefix::Prefix(prefix) => range_selectivity(prefix),
+        LikePrefix::Partial(prefix) => range_selectivity(prefix) * DEFAULT_LIKE_SELECTIVITY,
+    }
+}
+
+/// Estimates the selectivity of a conjunction.
+///
+/// Equalities covering all the columns of extended statistics with a number of distinct combinations are estimated
+/// together, then functional dependencies between the remaining equalities are applied, strongest first, like
+/// PostgreSQL: `P(a = x AND b = y) = P(a = x) * (degree + (1 - degree) * P(b = y))` for `a => b`. The other conjuncts
+/// are assumed to be independent.
+fn conjunction_selectivity(mut conjuncts: Vec<&dyn ScalarExpression>, stats: &Statistics) -> f64 {
+    // equality conjuncts, first one of every column
+    let mut equalities: HashMap<u32, f64> = HashMap::new();
+    conjuncts.retain(|conjunct| match column_predicate(*conjunct) {
+        Some((column, predicate @ ColumnPredicate::Compare(CmpOp::Equal, _)))
+            if !equalities.contains_key(&column.id()) =>
+        {
+            let selectivity = column_selectivity(stats.column_stats(column), stats.output_row_count(), &predicate);
+            equalities.insert(column.id(), selectivity);
+            false
+        }
+        _ => true,
+    });
+
+    let mut selectivity = 1.0;
+
+    // distinct combinations, widest groups first
+    let mut groups: Vec<_> = stats
+        .extended_stats()
+        .iter()
+        .filter_map(|extended_stats| Some((extended_stats.columns(), extended_stats.ndv()?)))
+        .filter(|(_, ndv)| *ndv > 0)
+        .collect();
+    groups.sort_by_key(|(columns, _)| std::cmp::Reverse(columns.len()));
+    for (columns, ndv) in groups {
+        if !columns.iter().all(|col| equalities.contains_key(col)) {
+            continue;
+        }
+        // the combination cannot be more selective than its most selective column, a repeated column counts once
+        let min_selectivity = columns
+            .iter()
+            .filter_map(|col| equalities.remove(col))
+            .fold(1.0, f64::min);
+        selectivity *= min_selectivity.max(1.0 / ndv as f64);
+    }
+
+    // functional dependencies, strongest first
+    let mut dependencies: Vec<_> = stats
+        .extended_stats()
+        .iter()
+        .flat_map(|extended_stats| extended_stats.dependencies())
+        .collect();
+    dependencies.sort_by(|a, b| b.degree().total_cmp(&a.degree()));
+    for dependency in dependencies {
+        if !dependency.determinant().iter().all(|col| equalities.contains_key(col)) {
+            continue;
+        }
+        if let Some(dependent_selectivity) = equalities.remove(&dependency.dependent()) {
+            selectivity *= dependency.degree() + (1.0 - dependency.degree()) * dependent_selectivity;
+        }
+    }
+
+    selectivity
+        * equalities.values().product::<f64>()
+        * conjuncts
+            .iter()
+            .map(|conjunct| estimate_selectivity(*conjunct, stats))
+            .product::<f64>()
+}
+
+/// Estimates the fraction of `row_count` rows whose column satisfies `predicate`.
+pub fn column_selectivity(col_stats: Option<&ColumnStats>, row_count: u64, predicate: &ColumnPredicate) -> f64 {
+    let col_stats = match col_stats {
+        Some(col_stats) if row_count > 0 => col_stats,
+        _ => {
+            return match predicate {
+                ColumnPredicate::IsNull => DEFAULT_NULL_SELECTIVITY,
+                ColumnPredicate::IsNotNull => 1.0 - DEFAULT_NULL_SELECTIVITY,
+                ColumnPredicate::Compare(op, _) => default_cmp_selectivity(*op),
+                ColumnPredicate::InList(values) => (values.len() as f64 * DEFAULT_EQ_SELECTIVITY).min(1.0),
+            }
+        }
+    };
+
+    let null_fraction = (col_stats.null_count() as f64 / row_count as f64).min(1.0);
+    match predicate {
+        ColumnPredicate::IsNull => null_fraction,
+        ColumnPredicate::IsNotNull => 1.0 - null_fraction,
+        ColumnPredicate::Compare(op, datum) => compare_selectivity(col_stats, row_count, null_fraction, *op, datum),
+        ColumnPredicate::InList(values) => values
+            .iter()
+            .map(|datum| compare_selectivity(col_stats, row_count, null_fraction, CmpOp::Equal, datum))
+            .sum::<f64>()
+            .min(1.0 - null_fraction),
+    }
+}
+
+/// Estimates the selectivity of `column op datum`, the most common values are looked up first and the histogram
+/// or the number of distinct values describe the remaining non-null values.
+fn compare_selectivity(col_stats: &ColumnStats, row_count: u64, null_fraction: f64, op: CmpOp, datum: &Datum) -> f64 {
+    let row_count = row_count as f64;
+    let (mcv_selectivity, mcv_fraction, mcv_len, in_mcv) = match col_stats.mcv() {
+        Some(mcv) => (
+            mcv.matched_count(op, datum) as f64 / row_count,
+            mcv.total_count() as f64 / row_count,
+            mcv.len() as u64,
+            mcv.count(datum).is_some(),
+        ),
+        None => (0.0, 0.0, 0, false),
+    };
+    let rest_fraction = (1.0 - null_fraction - mcv_fraction).max(0.0);
+
+    let rest_selectivity = match (op, col_stats.histogram(), col_stats.ndv()) {
+        // the histogram and the number of distinct values do not describe the most common values
+        (CmpOp::Equal, _, _) if in_mcv => 0.0,
+        (CmpOp::NotEqual, _, _) if in_mcv => rest_fraction,
+        (_, Some(histogram), _) => histogram.selectivity(op, datum) * rest_fraction,
+        (CmpOp::Equal | CmpOp::NotEqual, None, Some(ndv)) if ndv > mcv_len => {
+            let eq_selectivity = rest_fraction / (ndv - mcv_len) as f64;
+            match op {
+                CmpOp::Equal => eq_selectivity,
+                _ => rest_fraction - eq_selectivity,
+            }
+        }
+        _ if col_stats.mcv().is_some() => default_cmp_selectivity(op) * rest_fraction,
+        _ => default_cmp_selectivity(op),
+    };
+
+    mcv_selectivity + rest_selectivity
+}
+
+fn default_cmp_selectivity(op: CmpOp) -> f64 {
+    match op {
+        CmpOp::Equal => DEFAULT_EQ_SELECTIVITY,
+        CmpOp::NotEqual => 1.0 - DEFAULT_EQ_SELECTIVITY,
+        _ => DEFAULT_INEQ_SELECTIVITY,
+    }
+}
+
+/// Scales a row count by a selectivity, never estimating less than one row for a non-empty input.
+pub fn scale_row_count(row_count: u64, selectivity: f64) -> u64 {
+    if row_count == 0 {
+        return 0;
+    }
+    ((row_count as f64 * selectivity).round() as u64).max(1)
+}
+
+/// Derives the statistics of the rows of `input` that satisfy `predicate`.
+///
+/// A predicate contradicting the check constraints of the input selects no row. The keys and check constraints of the
+/// input still hold, and the columns whose NULL values the predicate rejects have none left.
+///
+/// The column statistics of the columns restricted by a conjunct of the predicate are filtered by that conjunct,
+/// all column statistics are then scaled by the selectivity of the remaining conjuncts.
+pub fn derive_filter_stats(input: &Statistics, predicate: &dyn ScalarExpression) -> Statistics {
+    let mut conjuncts = Vec::new();
+    collect_conjuncts(predicate, &mut conjuncts);
+
+    let mut not_null_columns = input.not_null_columns().to_vec();
+    not_null_columns.extend(null_rejected_columns(&conjuncts));
+
+    let selectivity = match contradicts_check_constraints(predicate, input, &not_null_columns) {
+        true => 0.0,
+        false => estimate_selectivity(predicate, input),
+    };
+    let output_row_count = scale_row_count(input.output_row_count(), selectivity);
+
+    let mut column_stats = HashMap::new();
+    for (column, col_stats) in input.iter_column_stats() {
+        let mut filtered = col_stats.clone();
+        let mut col_selectivity = 1.0;
+        for conjunct in &conjuncts {
+            match column_predicate(*conjunct) {
+                Some((col, col_predicate)) if *col == column => {
+                    col_selectivity *= column_selectivity(Some(col_stats), input.output_row_count(), &col_predicate);
+                    filtered = filtered.filter(&col_predicate);
+                }
+                _ => {}
+            }
+        }
+
+        let remaining_selectivity = if col_selectivity > 0.0 {
+            (selectivity / col_selectivity).min(1.0)
+        } else {
+            0.0
+        };
+        column_stats.insert(column.id(), filtered.scale(remaining_selectivity));
+    }
+
+    let extended_stats = input
+        .extended_stats()
+        .iter()
+        .map(|extended_stats| extended_stats.scale(output_row_count))
+        .collect();
+    Statistics::new(output_row_count, column_stats)
+        .with_extended_stats(extended_stats)
+        .with_keys(input.keys().to_vec())
+        .with_not_null_columns(not_null_columns)
+        .with_check_constraints(input.check_constraints().to_vec())
+        .with_relation_row_count(input.relation_row_count())
+        .with_relation_storage(input.relation_storage())
+}
@@ -0,0 +1,339 @@
+use crate::datum::Datum;
+use crate::metadata::{MdCache, MdId};
+use crate::statistics::{Bucket, ColumnStats, Histogram, HyperLogLog, MostCommonValues, RelationStats};
+
+pub const DEFAULT_BUCKET_COUNT: usize = 100; // number of histogram buckets
+pub const DEFAULT_MCV_COUNT: usize = 100; // max number of most common values
+pub const DEFAULT_SAMPLE_SIZE: usize = 30_000; // number of values kept to build the histogram
+
+/// Builds the statistics of a column from its values.
+///
+/// The builder keeps a uniform sample of at most `sample_size` values to find the most common values and to build an
+/// equi-depth histogram of the other values, while every value goes through a HyperLogLog sketch, so the number of
+/// distinct values of large columns is still estimated from all of them. If the values are themselves a sample of the
+/// table, `with_table_rows` scales the statistics up to the size of the table.
+#[derive(Clone, Debug)]
+pub struct ColumnStatsBuilder {
+    col_id: usize,
+    name: String,
+    bucket_count: usize,
+    mcv_count: usize,
+    sample_size: usize,
+    table_rows: Option<u64>,
+    value_count: u64, // number of values added, nulls included
+    null_count: u64,
+    min: Option<Datum>,
+    max: Option<Datum>,
+    sample: Vec<Datum>,
+    sketch: HyperLogLog,
+    rng_state: u64,
+}
+
+impl ColumnStatsBuilder {
+    pub fn new(col_id: usize, name: String) -> Self {
+        Self {
+            col_id,
+            name,
+            bucket_count: DEFAULT_BUCKET_COUNT,
+            mcv_count: DEFAULT_MCV_COUNT,
+            sample_size: DEFAULT_SAMPLE_SIZE,
+            table_rows: None,
+            value_count: 0,
+            null_count: 0,
+            min: None,
+            max: None,
+            sample: Vec::new(),
+            sketch: HyperLogLog::default(),
+            rng_state: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    pub fn with_bucket_count(mut self, bucket_count: usize) -> Self {
+        assert!(bucket_count > 0);
+        self.bucket_count = bucket_count;
+        self
+    }
+
+    /// Sets the max number of most common values, 0 disables the most common values.
+    pub fn with_mcv_count(mut self, mcv_count: usize) -> Self {
+        self.mcv_count = mcv_count;
+        self
+    }
+
+    pub fn with_sample_size(mut self, sample_size: usize) -> Self {
+        assert!(sample_size > 0);
+        self.sample_size = sample_size;
+        self
+    }
+
+    /// Declares that the added values are a sample of a table with `table_rows` rows.
+    pub fn with_table_rows(mut self, table_rows: u64) -> Self {
+        self.table_rows = Some(table_rows);
+        self
+    }
+
+    /// Adds a value of the column, `None` and `Datum::Null` are null values.
+    pub fn add(&mut self, value: Option<Datum>) {
+        self.value_count += 1;
+        let value = match value {
+            Some(value) if !value.is_null() => value,
+            _ => {
+                self.null_count += 1;
+                return;
+            }
+        };
+
+        self.sketch.insert(&value);
+        if self.min.as_ref().is_none_or(|min| value < *min) {
+            self.min = Some(value.clone());
+        }
+        if self.max.as_ref().is_none_or(|max| value > *max) {
+            self.max = Some(value.clone());
+        }
+
+        // reservoir sampling
+        if self.sample.len() < self.sample_size {
+            self.sample.push(value);
+        } else {
+            let non_null_count = self.value_count - self.null_count;
+            let index = self.next_random() % non_null_count;
+            if (index as usize) < self.sample_size {
+                self.sample[index as usize] = value;
+            }
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        // splitmix64, deterministic so that the same values always produce the same statistics
+        self.rng_state = self.rng_state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.rng_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn build(mut self) -> ColumnStats {
+        let scale = match self.table_rows {
+            Some(table_rows) if self.value_count > 0 => table_rows as f64 / self.value_count as f64,
+            _ => 1.0,
+        };
+        let null_count = (self.null_count as f64 * scale).round() as u64;
+        let non_null_count = ((self.value_count - self.null_count) as f64 * scale).round() as u64;
+
+        self.sample.sort_unstable();
+        let sample = self.sample.as_slice();
+        let (distinct, singletons) = count_distinct(sample);
+
+        // Values seen by the sketch are all the values of the column unless the input is a sample of the table.
+        let sample_is_complete = sample.len() as u64 == self.value_count - self.null_count;
+        let ndv = if self.table_rows.is_none() && !sample_is_complete {
+            self.sketch.estimate()
+        } else {
+            estimate_ndv(sample.len() as u64, distinct, singletons, non_null_count)
+        };
+        let ndv = ndv.clamp(distinct, non_null_count.max(distinct));
+
+        let count_scale = if sample.is_empty() {
+            1.0
+        } else {
+            non_null_count as f64 / sample.len() as f64
+        };
+        let mcv = select_mcv(sample, distinct, self.mcv_count);
+        let mcv = MostCommonValues::new(
+            mcv.iter()
+                .map(|(value, count)| (value.clone(), (*count as f64 * count_scale).round() as u64))
+                .collect(),
+        );
+
+        // the most common values are excluded from the histogram
+        let rest: Vec<Datum> = sample
+            .iter()
+            .filter(|value| mcv.count(value).is_none())
+            .cloned()
+            .collect();
+        let rest_count = non_null_count.saturating_sub(mcv.total_count());
+        let mcv_len = mcv.len() as u64;
+        let histogram = build_histogram(&rest, self.bucket_count, rest_count, ndv - mcv_len, distinct - mcv_len);
+
+        // the bounds of an all-null column are null
+        let min = self.min.unwrap_or(Datum::Null);
+        let max = self.max.unwrap_or(Datum::Null);
+        let col_stats = ColumnStats::new(self.col_id, self.name, min, max, null_count, Some(histogram)).with_ndv(ndv);
+        if mcv.is_empty() {
+            col_stats
+        } else {
+            col_stats.with_mcv(mcv)
+        }
+    }
+}
+
+/// Returns the number of distinct values of the sorted values and the number of values appearing exactly once.
+fn count_distinct(sorted: &[Datum]) -> (u64, u64) {
+    let mut distinct = 0;
+    let mut singletons = 0;
+    for group in sorted.chunk_by(|a, b| a == b) {
+        distinct += 1;
+        if group.len() == 1 {
+            singletons += 1;
+        }
+    }
+    (distinct, singletons)
+}
+
+/// Selects the values of the sorted sample that are noticeably more common than the average value, like ANALYZE of
+/// PostgreSQL: a most common value appears more than once and more than 1.25 times the average.
+fn select_mcv(sorted: &[Datum], distinct: u64, mcv_count: usize) -> Vec<(Datum, u64)> {
+    if sorted.is_empty() || mcv_count == 0 {
+        return vec![];
+    }
+
+    let threshold = 1.25 * sorted.len() as f64 / distinct as f64;
+    let mut candidates: Vec<(Datum, u64)> = sorted
+        .chunk_by(|a, b| a == b)
+        .filter(|group| group.len() > 1 && group.len() as f64 > threshold)
+        .map(|group| (group[0].clone(), group.len() as u64))
+        .collect();
+    candidates.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    candidates.truncate(mcv_count);
+    candidates
+}
+
+/// Estimates the number of distinct values of `total` values from a sample of `sampled` values, with the Duj1
+/// estimator of Haas and Stokes: `n * d / (n - f1 + f1 * n / N)`.
+fn estimate_ndv(sampled: u64, distinct: u64, singletons: u64, total: u64) -> u64 {
+    if sampled == 0 || sampled >= total {
+        return distinct;
+    }
+
+    let n = sampled as f64;
+    let denominator = n - singletons as f64 + singletons as f64 * n / total as f64;
+    (n * distinct as f64 / denominator).round() as u64
+}
+
+/// Builds an equi-depth histogram of the sorted sample, equal values are never split into two buckets.
+fn build_histogram(sorted: &[Datum], bucket_count: usize, total: u64, ndv: u64, distinct: u64) -> Histogram {
+    if sorted.is_empty() {
+        return Histogram::new(vec![]);
+    }
+
+    let count_scale = total as f64 / sorted.len() as f64;
+    let ndv_scale = ndv as f64 / distinct as f64;
+    let depth = sorted.len().div_ceil(bucket_count);
+
+    let mut buckets = Vec::with_capacity(bucket_count);
+    let mut start = 0;
+    while start < sorted.len() {
+        let mut end = (start + depth).min(sorted.len());
+        while end < sorted.len() && sorted[end] == sorted[end - 1] {
+            end += 1;
+        }
+
+        let values = &sorted[start..end];
+        let (bucket_distinct, _) = count_distinct(values);
+        let value_count = ((values.len() as f64 * count_scale).round() as u64).max(1);
+        let bucket_ndv = ((bucket_distinct as f64 * ndv_scale).round() as u64).clamp(1, value_count);
+        buckets.push(Bucket::new(
+            values[0].clone(),
+            values[values.len() - 1].clone(),
+            bucket_ndv,
+            value_count,
+        ));
+
+        start = end;
+    }
+    Histogram::new(buckets)
+}
+
+/// Builds the statistics of a relation and of its columns from its rows.
+#[derive(Clone, Debug)]
+pub struct RelationStatsBuilder {
+    name: String,
+    columns: Vec<ColumnStatsBuilder>,
+    row_count: u64,
+    table_rows: Option<u64>,
+}
+
+impl RelationStatsBuilder {
+    /// Creates a builder of a relation with the given columns, in the order of the relation.
+    pub fn new(name: String, column_names: Vec<String>) -> Self {
+        let columns = column_names
+            .into_iter()
+            .enumerate()
+            .map(|(col_id, name)| ColumnStatsBuilder::new(col_id, name))
+            .collect();
+
+        Self {
+            name,
+            columns,
+            row_count: 0,
+            table_rows: None,
+        }
+    }
+
+    pub fn with_bucket_count(mut self, bucket_count: usize) -> Self {
+        self.columns = self
+            .columns
+            .into_iter()
+            .map(|column| column.with_bucket_count(bucket_count))
+            .collect();
+        self
+    }
+
+    pub fn with_mcv_count(mut self, mcv_count: usize) -> Self {
+        self.columns = self
+            .columns
+            .into_iter()
+            .map(|column| column.with_mcv_count(mcv_count))
+            .collect();
+        self
+    }
+
+    pub fn with_sample_size(mut self, sample_size: usize) -> Self {
+        self.columns = self
+            .columns
+            .into_iter()
+            .map(|column| column.with_sample_size(sample_size))
+            .collect();
+        self
+    }
+
+    /// Declares that the added rows are a sample of a table with `table_rows` rows.
+    pub fn with_table_rows(mut self, table_rows: u64) -> Self {
+        self.table_rows = Some(table_rows);
+        self.columns = self
+            .columns
+            .into_iter()
+            .map(|column| column.with_table_rows(table_rows))
+            .collect();
+        self
+    }
+
+    /// Adds a row of the relation, `None` is a null value.
+    pub fn add_row(&mut self, row: &[Option<Datum>]) {
+        assert_eq!(row.len(), self.columns.len(), "row width mismatch");
+        self.row_count += 1;
+        self.columns
+            .iter_mut()
+            .zip(row)
+            .for_each(|(column, value)| column.add(value.clone()));
+    }
+
+    /// Builds the relation statistics and the statistics of every column, `col_stat_mdids` are the mdids the column
+    /// statistics will be stored with.
+    pub fn build(self, col_stat_mdids: Vec<MdId>) -> (RelationStats, Vec<ColumnStats>) {
+        assert_eq!(col_stat_mdids.len(), self.columns.len(), "column count mismatch");
+        let rows = self.table_rows.unwrap_or(self.row_count);
+        let relation_stats = RelationStats::new(self.name, rows, rows == 0, col_stat_mdids);
+        let column_stats = self.columns.into_iter().map(ColumnStatsBuilder::build).collect();
+        (relation_stats, column_stats)
+    }
+
+    /// Builds the statistics and inserts them into the metadata cache.
+    pub fn build_into(self, md_cache: &mut MdCache, rel_stats_mdid: MdId, col_stat_mdids: Vec<MdId>) {
+        let (relation_stats, column_stats) = self.build(col_stat_mdids.clone());
+        md_cache.insert(rel_stats_mdid, Box::new(relation_stats));
+        for (mdid, col_stats) in col_stat_mdids.into_iter().zip(column_stats) {
+            md_cache.insert(mdid, Box::new(col_stats));
+        }
+    }
+}
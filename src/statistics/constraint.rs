@@ -0,0 +1,36 @@
+use crate::expression::{referenced_columns, ColumnVar};
+use crate::metadata::MdId;
+use cso_core::expression::ScalarExpression;
+use serde::{Deserialize, Serialize};
+
+/// A constraint of a relation. The columns are the ordinal positions of the columns in the relation, as are the ids of
+/// the column vars of a check constraint.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Constraint {
+    PrimaryKey(Vec<u32>),
+    Unique(Vec<u32>),
+    /// The values of `columns` are NULL or the values of `referenced_columns` of a row of `referenced_relation`.
+    ForeignKey {
+        columns: Vec<u32>,
+        referenced_relation: MdId,
+        referenced_columns: Vec<u32>,
+    },
+    NotNull(u32),
+    /// A predicate every row satisfies or evaluates to NULL for.
+    Check(Box<dyn ScalarExpression>),
+}
+
+impl Constraint {
+    /// Returns the columns of the relation the constraint is on.
+    pub fn columns(&self) -> Vec<u32> {
+        match self {
+            Constraint::PrimaryKey(columns) | Constraint::Unique(columns) => columns.clone(),
+            Constraint::ForeignKey { columns, .. } => columns.clone(),
+            Constraint::NotNull(column) => vec![*column],
+            Constraint::Check(predicate) => referenced_columns(predicate.as_ref())
+                .iter()
+                .map(ColumnVar::id)
+                .collect(),
+        }
+    }
+}
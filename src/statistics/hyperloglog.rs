@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+const MIN_PRECISION: u8 = 4;
+const MAX_PRECISION: u8 = 16;
+
+/// HyperLogLog sketch to estimate the number of distinct values of a column with a fixed amount of memory.
+///
+/// The sketch uses `2^precision` registers, the standard error of the estimate is about `1.04 / sqrt(2^precision)`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    pub const DEFAULT_PRECISION: u8 = 12;
+
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (MIN_PRECISION..=MAX_PRECISION).contains(&precision),
+            "precision must be in [{MIN_PRECISION}, {MAX_PRECISION}]"
+        );
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    pub fn insert<V: Hash + ?Sized>(&mut self, value: &V) {
+        // `DefaultHasher::new` uses fixed keys, so the sketch of the same values is always the same.
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let index = (hash >> (64 - self.precision)) as usize;
+        let rest = hash << self.precision;
+        let rank = (rest.leading_zeros() as u8).min(64 - self.precision) + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Merges the sketch of another set of values into this one, both sketches must have the same precision.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        assert_eq!(self.precision, other.precision, "precision mismatch");
+        self.registers
+            .iter_mut()
+            .zip(other.registers.iter())
+            .for_each(|(register, other)| *register = (*register).max(*other));
+    }
+
+    /// Returns the estimated number of distinct values inserted into the sketch.
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let estimate = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            // small range correction: linear counting
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_PRECISION)
+    }
+}
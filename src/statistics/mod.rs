@@ -0,0 +1,1259 @@
+mod builder;
+mod constraint;
+mod hyperloglog;
+mod validator;
+
+pub use self::builder::{ColumnStatsBuilder, RelationStatsBuilder};
+pub use self::builder::{DEFAULT_BUCKET_COUNT, DEFAULT_MCV_COUNT, DEFAULT_SAMPLE_SIZE};
+pub use self::constraint::Constraint;
+pub use self::hyperloglog::HyperLogLog;
+pub use self::validator::{catalog_validator, validate_md_cache, validate_relations, CatalogError};
+
+use crate::cost::PAGE_SIZE;
+use crate::datum::Datum;
+use crate::expression::{referenced_columns, CmpOp, ColumnVar};
+use crate::metadata::MdId;
+use crate::selectivity::ColumnPredicate;
+use cso_core::datatype::DataType;
+use cso_core::expression::ScalarExpression;
+use cso_core::metadata::Metadata;
+use cso_core::metadata::Stats;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bucket {
+    lower: Datum,     // Lower bound value of the bucket.
+    upper: Datum,     // Upper bound value of the bucket.
+    ndv: u64,         // Estimated number of distinct values in the bucket.
+    value_count: u64, // Estimated number of values in the bucket.
+}
+
+impl Bucket {
+    #[inline]
+    pub const fn new(lower: Datum, upper: Datum, ndv: u64, value_count: u64) -> Self {
+        Self {
+            lower,
+            upper,
+            ndv,
+            value_count,
+        }
+    }
+
+    #[inline]
+    pub fn lower(&self) -> &Datum {
+        &self.lower
+    }
+
+    #[inline]
+    pub fn upper(&self) -> &Datum {
+        &self.upper
+    }
+
+    #[inline]
+    pub fn ndv(&self) -> u64 {
+        self.ndv
+    }
+
+    #[inline]
+    pub fn value_count(&self) -> u64 {
+        self.value_count
+    }
+
+    /// Returns the fraction of the values in the bucket that satisfy `value op datum`,
+    /// assuming the values are uniformly distributed between the bounds.
+    pub fn fraction(&self, op: CmpOp, datum: &Datum) -> f64 {
+        match op {
+            CmpOp::Equal => self.fraction_equal(datum),
+            CmpOp::NotEqual => 1.0 - self.fraction_equal(datum),
+            CmpOp::LessThan => self.fraction_less(datum),
+            CmpOp::LessThanEqual => self.fraction_less_equal(datum),
+            CmpOp::GreaterThan => 1.0 - self.fraction_less_equal(datum),
+            CmpOp::GreaterThanEqual => 1.0 - self.fraction_less(datum),
+        }
+    }
+
+    fn contains(&self, datum: &Datum) -> bool {
+        self.lower <= *datum && *datum <= self.upper
+    }
+
+    fn fraction_equal(&self, datum: &Datum) -> f64 {
+        if self.contains(datum) {
+            1.0 / self.ndv.max(1) as f64
+        } else {
+            0.0
+        }
+    }
+
+    fn fraction_less(&self, datum: &Datum) -> f64 {
+        if self.upper < *datum {
+            return 1.0;
+        }
+        if *datum <= self.lower {
+            return 0.0;
+        }
+
+        match (self.lower.to_f64(), self.upper.to_f64(), datum.to_f64()) {
+            (Some(lower), Some(upper), Some(val)) if upper > lower => (val - lower) / (upper - lower),
+            _ => 0.5,
+        }
+    }
+
+    fn fraction_less_equal(&self, datum: &Datum) -> f64 {
+        (self.fraction_less(datum) + self.fraction_equal(datum)).min(1.0)
+    }
+}
+
+/// A histogram is a representation of the distribution of a column.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Histogram {
+    buckets: Vec<Bucket>,
+}
+
+impl Histogram {
+    #[inline]
+    pub const fn new(buckets: Vec<Bucket>) -> Self {
+        Self { buckets }
+    }
+
+    #[inline]
+    pub fn buckets(&self) -> &[Bucket] {
+        self.buckets.as_slice()
+    }
+
+    /// Returns the number of values covered by the histogram.
+    pub fn total_count(&self) -> u64 {
+        self.buckets.iter().map(|bucket| bucket.value_count).sum()
+    }
+
+    /// Returns the fraction of the histogram values that satisfy `value op datum`.
+    pub fn selectivity(&self, op: CmpOp, datum: &Datum) -> f64 {
+        let total_count = self.total_count();
+        if total_count == 0 {
+            return 0.0;
+        }
+
+        let matched: f64 = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.value_count as f64 * bucket.fraction(op, datum))
+            .sum();
+        (matched / total_count as f64).clamp(0.0, 1.0)
+    }
+
+    /// Returns the histogram of the values that satisfy `value op datum`.
+    pub fn filter(&self, op: CmpOp, datum: &Datum) -> Histogram {
+        let buckets = self
+            .buckets
+            .iter()
+            .filter_map(|bucket| {
+                let fraction = bucket.fraction(op, datum);
+                if fraction <= 0.0 {
+                    return None;
+                }
+
+                let (lower, upper) = match op {
+                    CmpOp::Equal => (datum, datum),
+                    CmpOp::GreaterThan | CmpOp::GreaterThanEqual if bucket.lower < *datum => (datum, &bucket.upper),
+                    CmpOp::LessThan | CmpOp::LessThanEqual if *datum < bucket.upper => (&bucket.lower, datum),
+                    _ => (&bucket.lower, &bucket.upper),
+                };
+                let ndv = if op == CmpOp::Equal {
+                    1
+                } else {
+                    scale_count(bucket.ndv, fraction)
+                };
+                let value_count = scale_count(bucket.value_count, fraction);
+                Some(Bucket::new(
+                    lower.clone(),
+                    upper.clone(),
+                    ndv.min(value_count),
+                    value_count,
+                ))
+            })
+            .collect();
+        Histogram::new(buckets)
+    }
+
+    /// Returns the histogram with every bucket scaled by `factor`.
+    pub fn scale(&self, factor: f64) -> Histogram {
+        let buckets = self
+            .buckets
+            .iter()
+            .map(|bucket| {
+                let value_count = scale_count(bucket.value_count, factor);
+                Bucket::new(
+                    bucket.lower.clone(),
+                    bucket.upper.clone(),
+                    bucket.ndv.min(value_count),
+                    value_count,
+                )
+            })
+            .collect();
+        Histogram::new(buckets)
+    }
+}
+
+/// The most common values of a column with their number of occurrences, like `most_common_vals` of PostgreSQL.
+///
+/// The values are excluded from the histogram of the column, so that the frequency of skewed values is not smoothed
+/// away by the buckets.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MostCommonValues {
+    values: Vec<(Datum, u64)>, // (value, count) pairs, most common value first
+}
+
+impl MostCommonValues {
+    pub fn new(mut values: Vec<(Datum, u64)>) -> Self {
+        values.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        Self { values }
+    }
+
+    pub fn values(&self) -> &[(Datum, u64)] {
+        &self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the number of occurrences of `datum`, if it is one of the most common values.
+    pub fn count(&self, datum: &Datum) -> Option<u64> {
+        self.values
+            .iter()
+            .find(|(value, _)| value == datum)
+            .map(|(_, count)| *count)
+    }
+
+    /// Returns the number of occurrences of all the most common values.
+    pub fn total_count(&self) -> u64 {
+        self.values.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Returns the number of occurrences of the most common values that satisfy `value op datum`.
+    pub fn matched_count(&self, op: CmpOp, datum: &Datum) -> u64 {
+        self.values
+            .iter()
+            .filter(|(value, _)| op.holds(value.cmp(datum)))
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Returns the most common values that satisfy `value op datum`.
+    pub fn filter(&self, op: CmpOp, datum: &Datum) -> MostCommonValues {
+        self.retain(|value| op.holds(value.cmp(datum)))
+    }
+
+    fn retain(&self, f: impl Fn(&Datum) -> bool) -> MostCommonValues {
+        let values = self.values.iter().filter(|(value, _)| f(value)).cloned().collect();
+        MostCommonValues { values }
+    }
+
+    /// Returns the most common values with every count scaled by `factor`.
+    pub fn scale(&self, factor: f64) -> MostCommonValues {
+        let values = self
+            .values
+            .iter()
+            .map(|(value, count)| (value.clone(), scale_count(*count, factor)))
+            .collect();
+        MostCommonValues { values }
+    }
+}
+
+#[inline]
+fn scale_count(count: u64, factor: f64) -> u64 {
+    (count as f64 * factor).round() as u64
+}
+
+/// Statistics information of a column
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ColumnStats {
+    col_id: usize, // Ordinal position of the column in its relation
+    name: String,
+    min: Datum,                   // Min value of the column
+    max: Datum,                   // Max value of the column
+    null_count: u64,              // Count of null values
+    histogram: Option<Histogram>, // Histogram of column
+    #[serde(default)]
+    ndv: Option<u64>, // Estimated number of distinct non-null values
+    #[serde(default)]
+    mcv: Option<MostCommonValues>, // Most common values of column, excluded from the histogram
+}
+
+impl ColumnStats {
+    pub const fn new(
+        col_id: usize,
+        name: String,
+        min: Datum,
+        max: Datum,
+        null_count: u64,
+        histogram: Option<Histogram>,
+    ) -> Self {
+        Self {
+            col_id,
+            name,
+            min,
+            max,
+            null_count,
+            histogram,
+            ndv: None,
+            mcv: None,
+        }
+    }
+
+    pub fn with_ndv(mut self, ndv: u64) -> Self {
+        self.ndv = Some(ndv);
+        self
+    }
+
+    pub fn with_mcv(mut self, mcv: MostCommonValues) -> Self {
+        self.mcv = Some(mcv);
+        self
+    }
+
+    pub fn col_id(&self) -> usize {
+        self.col_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn min(&self) -> &Datum {
+        &self.min
+    }
+
+    pub fn max(&self) -> &Datum {
+        &self.max
+    }
+
+    pub fn null_count(&self) -> u64 {
+        self.null_count
+    }
+
+    pub fn histogram(&self) -> &Option<Histogram> {
+        &self.histogram
+    }
+
+    pub fn ndv(&self) -> Option<u64> {
+        self.ndv
+    }
+
+    pub fn mcv(&self) -> &Option<MostCommonValues> {
+        &self.mcv
+    }
+
+    /// Returns the number of non-null values described by the histogram and the most common values.
+    fn described_count(&self) -> Option<u64> {
+        match (&self.histogram, &self.mcv) {
+            (None, None) => None,
+            (histogram, mcv) => Some(
+                histogram.as_ref().map_or(0, Histogram::total_count)
+                    + mcv.as_ref().map_or(0, MostCommonValues::total_count),
+            ),
+        }
+    }
+
+    /// Recomputes min and max from the histogram and the most common values.
+    fn refresh_bounds(&mut self) {
+        let buckets = self.histogram.as_ref().map_or(&[][..], Histogram::buckets);
+        let mcv = self.mcv.as_ref().map_or(&[][..], MostCommonValues::values);
+        let lowers = buckets
+            .iter()
+            .map(Bucket::lower)
+            .chain(mcv.iter().map(|(value, _)| value));
+        let uppers = buckets
+            .iter()
+            .map(Bucket::upper)
+            .chain(mcv.iter().map(|(value, _)| value));
+        if let (Some(min), Some(max)) = (lowers.min().cloned(), uppers.max().cloned()) {
+            self.min = min;
+            self.max = max;
+        }
+    }
+
+    /// Returns the statistics of the column values that satisfy `predicate`.
+    pub fn filter(&self, predicate: &ColumnPredicate) -> ColumnStats {
+        let mut col_stats = self.clone();
+        match predicate {
+            ColumnPredicate::IsNull => {
+                col_stats.histogram = self.histogram.as_ref().map(|_| Histogram::new(vec![]));
+                col_stats.mcv = self.mcv.as_ref().map(|_| MostCommonValues::new(vec![]));
+                col_stats.ndv = self.ndv.map(|_| 0);
+                return col_stats;
+            }
+            ColumnPredicate::IsNotNull => {
+                col_stats.null_count = 0;
+                return col_stats;
+            }
+            ColumnPredicate::Compare(op, datum) => {
+                let in_mcv = self.mcv.as_ref().is_some_and(|mcv| mcv.count(datum).is_some());
+                col_stats.mcv = self.mcv.as_ref().map(|mcv| mcv.filter(*op, datum));
+                col_stats.histogram = self.histogram.as_ref().map(|histogram| match op {
+                    // the value is not in the histogram
+                    CmpOp::Equal if in_mcv => Histogram::new(vec![]),
+                    CmpOp::NotEqual if in_mcv => histogram.clone(),
+                    _ => histogram.filter(*op, datum),
+                });
+                col_stats.ndv = match op {
+                    CmpOp::Equal => self.ndv.map(|ndv| ndv.min(1)),
+                    _ => col_stats.histogram_ndv().or(self.ndv),
+                };
+            }
+            ColumnPredicate::InList(values) => {
+                let mcv = self.mcv.as_ref();
+                col_stats.mcv = mcv.map(|mcv| mcv.retain(|value| values.contains(value)));
+                col_stats.histogram = self.histogram.as_ref().map(|histogram| {
+                    let buckets = values
+                        .iter()
+                        .filter(|value| mcv.is_none_or(|mcv| mcv.count(value).is_none()))
+                        .flat_map(|value| histogram.filter(CmpOp::Equal, value).buckets)
+                        .collect();
+                    Histogram::new(buckets)
+                });
+                col_stats.ndv = self.ndv.map(|ndv| ndv.min(values.len() as u64));
+            }
+        }
+        col_stats.null_count = 0;
+        col_stats.refresh_bounds();
+        col_stats
+    }
+
+    fn histogram_ndv(&self) -> Option<u64> {
+        let histogram = self.histogram.as_ref()?;
+        let mcv_count = self.mcv.as_ref().map_or(0, MostCommonValues::len) as u64;
+        Some(histogram.buckets().iter().map(Bucket::ndv).sum::<u64>() + mcv_count)
+    }
+
+    /// Returns the statistics with the null count, the histogram and the most common values scaled by `factor`.
+    pub fn scale(&self, factor: f64) -> ColumnStats {
+        let mut col_stats = self.clone();
+        col_stats.null_count = scale_count(self.null_count, factor);
+        col_stats.histogram = self.histogram.as_ref().map(|histogram| histogram.scale(factor));
+        col_stats.mcv = self.mcv.as_ref().map(|mcv| mcv.scale(factor));
+        if let Some(described_count) = col_stats.described_count() {
+            col_stats.ndv = self.ndv.map(|ndv| ndv.min(described_count));
+        }
+        col_stats
+    }
+}
+
+#[typetag::serde]
+impl Metadata for ColumnStats {
+    fn size_hint(&self) -> usize {
+        let buckets = self.histogram.as_ref().map_or(0, |histogram| histogram.buckets.len());
+        let mcvs = self.mcv.as_ref().map_or(0, |mcv| mcv.values.len());
+        size_of::<Self>() + self.name.len() + buckets * size_of::<Bucket>() + mcvs * size_of::<(Datum, u64)>()
+    }
+}
+
+/// A functional dependency `determinant => dependent` between columns, holding for a `degree` fraction of the rows,
+/// like the `dependencies` extended statistics of PostgreSQL.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FunctionalDependency {
+    determinant: Vec<u32>,
+    dependent: u32,
+    degree: f64, // fraction of the rows for which the determinant values determine the dependent value
+}
+
+impl FunctionalDependency {
+    pub fn new(determinant: Vec<u32>, dependent: u32, degree: f64) -> Self {
+        assert!((0.0..=1.0).contains(&degree), "degree must be in [0, 1]");
+        assert!(!determinant.contains(&dependent), "a column cannot determine itself");
+        Self {
+            determinant,
+            dependent,
+            degree,
+        }
+    }
+
+    pub fn determinant(&self) -> &[u32] {
+        &self.determinant
+    }
+
+    pub fn dependent(&self) -> u32 {
+        self.dependent
+    }
+
+    pub fn degree(&self) -> f64 {
+        self.degree
+    }
+}
+
+/// Statistics of a group of correlated columns, like the extended statistics of PostgreSQL.
+///
+/// In `RelationMetadata` the columns are the ordinal positions of the columns in the relation, in `Statistics` they
+/// are the ids of the column vars.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExtendedStats {
+    columns: Vec<u32>,
+    ndv: Option<u64>, // Estimated number of distinct combinations of values of the columns.
+    dependencies: Vec<FunctionalDependency>,
+}
+
+impl ExtendedStats {
+    pub fn new(columns: Vec<u32>) -> Self {
+        assert!(columns.len() > 1, "extended statistics cover at least two columns");
+        Self {
+            columns,
+            ndv: None,
+            dependencies: vec![],
+        }
+    }
+
+    pub fn with_ndv(mut self, ndv: u64) -> Self {
+        self.ndv = Some(ndv);
+        self
+    }
+
+    pub fn with_dependencies(mut self, dependencies: Vec<FunctionalDependency>) -> Self {
+        assert!(
+            dependencies.iter().all(|dependency| {
+                self.columns.contains(&dependency.dependent)
+                    && dependency.determinant.iter().all(|col| self.columns.contains(col))
+            }),
+            "dependencies must be between the columns of the statistics"
+        );
+        self.dependencies = dependencies;
+        self
+    }
+
+    pub fn columns(&self) -> &[u32] {
+        &self.columns
+    }
+
+    pub fn ndv(&self) -> Option<u64> {
+        self.ndv
+    }
+
+    pub fn dependencies(&self) -> &[FunctionalDependency] {
+        &self.dependencies
+    }
+
+    /// Renames the columns with `f`, returns `None` if a column has no new name.
+    pub fn map_columns(&self, f: impl Fn(u32) -> Option<u32>) -> Option<ExtendedStats> {
+        let columns = self.columns.iter().map(|col| f(*col)).collect::<Option<Vec<_>>>()?;
+        let dependencies = self
+            .dependencies
+            .iter()
+            .map(|dependency| {
+                let determinant = dependency
+                    .determinant
+                    .iter()
+                    .map(|col| f(*col))
+                    .collect::<Option<_>>()?;
+                Some(FunctionalDependency::new(
+                    determinant,
+                    f(dependency.dependent)?,
+                    dependency.degree,
+                ))
+            })
+            .collect::<Option<_>>()?;
+        Some(ExtendedStats {
+            columns,
+            ndv: self.ndv,
+            dependencies,
+        })
+    }
+
+    /// Returns the statistics of `row_count` rows out of the described ones.
+    pub fn scale(&self, row_count: u64) -> ExtendedStats {
+        let mut extended_stats = self.clone();
+        extended_stats.ndv = self.ndv.map(|ndv| ndv.min(row_count));
+        extended_stats
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Statistics {
+    output_row_count: u64,
+
+    /// Statistics of columns, global column id -> column stat
+    column_stats: HashMap<u32, ColumnStats>,
+
+    /// Statistics of groups of correlated columns, on global column ids
+    extended_stats: Vec<ExtendedStats>,
+
+    /// Sets of global column ids whose values are unique among the rows, from unique constraints
+    keys: Vec<Vec<u32>>,
+
+    /// Global column ids of the columns without NULL values, sorted
+    not_null_columns: Vec<u32>,
+
+    /// Predicates every row satisfies or evaluates to NULL for, from check constraints
+    check_constraints: Vec<Rc<dyn ScalarExpression>>,
+
+    /// Row count of the relation the rows are read from by a scan, if they are
+    relation_row_count: Option<u64>,
+
+    /// Storage of the relation the rows are read from by a scan, if they are
+    relation_storage: Option<RelationStorage>,
+}
+
+impl Statistics {
+    pub const fn new(output_row_count: u64, column_stats: HashMap<u32, ColumnStats>) -> Self {
+        Self {
+            output_row_count,
+            column_stats,
+            extended_stats: vec![],
+            keys: vec![],
+            not_null_columns: vec![],
+            check_constraints: vec![],
+            relation_row_count: None,
+            relation_storage: None,
+        }
+    }
+
+    pub fn with_extended_stats(mut self, extended_stats: Vec<ExtendedStats>) -> Self {
+        self.extended_stats = extended_stats;
+        self
+    }
+
+    pub fn with_keys(mut self, keys: Vec<Vec<u32>>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    pub fn with_not_null_columns(mut self, mut not_null_columns: Vec<u32>) -> Self {
+        not_null_columns.sort_unstable();
+        not_null_columns.dedup();
+        self.not_null_columns = not_null_columns;
+        self
+    }
+
+    pub fn with_check_constraints(mut self, check_constraints: Vec<Rc<dyn ScalarExpression>>) -> Self {
+        self.check_constraints = check_constraints;
+        self
+    }
+
+    pub fn with_relation_row_count(mut self, relation_row_count: Option<u64>) -> Self {
+        self.relation_row_count = relation_row_count;
+        self
+    }
+
+    pub fn with_relation_storage(mut self, relation_storage: Option<RelationStorage>) -> Self {
+        self.relation_storage = relation_storage;
+        self
+    }
+
+    pub fn extended_stats(&self) -> &[ExtendedStats] {
+        &self.extended_stats
+    }
+
+    pub fn keys(&self) -> &[Vec<u32>] {
+        &self.keys
+    }
+
+    /// Returns whether the values of `columns` are unique among the rows, i.e. they contain a key.
+    pub fn is_unique(&self, columns: &[u32]) -> bool {
+        self.keys.iter().any(|key| key.iter().all(|col| columns.contains(col)))
+    }
+
+    pub fn not_null_columns(&self) -> &[u32] {
+        &self.not_null_columns
+    }
+
+    pub fn is_not_null(&self, column: &ColumnVar) -> bool {
+        self.not_null_columns.binary_search(&column.id()).is_ok()
+    }
+
+    pub fn check_constraints(&self) -> &[Rc<dyn ScalarExpression>] {
+        &self.check_constraints
+    }
+
+    pub fn relation_row_count(&self) -> Option<u64> {
+        self.relation_row_count
+    }
+
+    pub fn relation_storage(&self) -> Option<RelationStorage> {
+        self.relation_storage
+    }
+
+    /// Returns the statistics of `column`, if they are known.
+    pub fn column_stats(&self, column: &ColumnVar) -> Option<&ColumnStats> {
+        self.column_stats.get(&column.id())
+    }
+
+    /// Returns the columns with known statistics and their statistics.
+    pub fn iter_column_stats(&self) -> impl Iterator<Item = (ColumnVar, &ColumnStats)> {
+        self.column_stats
+            .iter()
+            .map(|(id, col_stats)| (ColumnVar::new(*id), col_stats))
+    }
+
+    /// Returns the statistics restricted to `columns`.
+    pub fn project(&self, columns: &[ColumnVar]) -> Statistics {
+        let column_stats = columns
+            .iter()
+            .filter_map(|column| Some((column.id(), self.column_stats(column)?.clone())))
+            .collect();
+        let extended_stats = self
+            .extended_stats
+            .iter()
+            .filter(|extended_stats| {
+                extended_stats
+                    .columns()
+                    .iter()
+                    .all(|col| columns.contains(&ColumnVar::new(*col)))
+            })
+            .cloned()
+            .collect();
+        let projected = |col: &u32| columns.contains(&ColumnVar::new(*col));
+        let keys = self
+            .keys
+            .iter()
+            .filter(|key| key.iter().all(projected))
+            .cloned()
+            .collect();
+        let not_null_columns = self.not_null_columns.iter().copied().filter(projected).collect();
+        let check_constraints = self
+            .check_constraints
+            .iter()
+            .filter(|predicate| {
+                referenced_columns(predicate.as_ref())
+                    .iter()
+                    .all(|column| columns.contains(column))
+            })
+            .cloned()
+            .collect();
+        Statistics::new(self.output_row_count, column_stats)
+            .with_extended_stats(extended_stats)
+            .with_keys(keys)
+            .with_not_null_columns(not_null_columns)
+            .with_check_constraints(check_constraints)
+            .with_relation_row_count(self.relation_row_count)
+            .with_relation_storage(self.relation_storage)
+    }
+}
+
+impl Stats for Statistics {
+    /// The alternatives of a group estimate the same rows, but not always the same row count: a predicate split
+    /// between an index scan and a filter above it loses the correlations of the extended statistics and is rounded
+    /// twice. The lowest estimate is kept so that the statistics of a group do not depend on the order its
+    /// alternatives are explored in.
+    fn should_update(&self, new_stats: &Rc<dyn Stats>) -> bool {
+        let new_stats = new_stats.as_ref().as_any().downcast_ref::<Statistics>().unwrap();
+        new_stats.output_row_count < self.output_row_count
+    }
+
+    fn output_row_count(&self) -> u64 {
+        self.output_row_count
+    }
+
+    fn column_stats(&self, col_id: u32) -> Option<&dyn Metadata> {
+        self.column_stats
+            .get(&col_id)
+            .map(|col_stats| col_stats as &dyn Metadata)
+    }
+}
+
+/// How the rows of a relation are stored.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RelationStorage {
+    pages: u64,
+    avg_row_width: u32,     // bytes of a row before compression
+    compression_ratio: f64, // uncompressed size / stored size
+}
+
+impl RelationStorage {
+    pub fn new(pages: u64, avg_row_width: u32) -> Self {
+        Self {
+            pages,
+            avg_row_width,
+            compression_ratio: 1.0,
+        }
+    }
+
+    /// Estimates the storage of `rows` rows of `avg_row_width` bytes, packed into pages of `PAGE_SIZE` bytes.
+    pub fn estimate(rows: u64, avg_row_width: u32, compression_ratio: f64) -> Self {
+        let bytes = rows as f64 * avg_row_width as f64 / compression_ratio;
+        let pages = (bytes / PAGE_SIZE as f64).ceil() as u64;
+        Self::new(pages.max(1), avg_row_width).with_compression_ratio(compression_ratio)
+    }
+
+    pub fn with_compression_ratio(mut self, compression_ratio: f64) -> Self {
+        assert!(compression_ratio >= 1.0, "compression ratio must be at least 1");
+        self.compression_ratio = compression_ratio;
+        self
+    }
+
+    pub fn pages(&self) -> u64 {
+        self.pages
+    }
+
+    pub fn avg_row_width(&self) -> u32 {
+        self.avg_row_width
+    }
+
+    pub fn compression_ratio(&self) -> f64 {
+        self.compression_ratio
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RelationStats {
+    name: String,
+    rows: u64,
+    empty: bool,
+    col_stat_mdids: Vec<MdId>,
+    #[serde(default)]
+    storage: Option<RelationStorage>, // estimated from the rows and the column widths if unknown
+}
+
+impl RelationStats {
+    pub const fn new(name: String, rows: u64, empty: bool, col_stat_mdids: Vec<MdId>) -> Self {
+        Self {
+            name,
+            rows,
+            empty,
+            col_stat_mdids,
+            storage: None,
+        }
+    }
+
+    pub fn with_storage(mut self, storage: RelationStorage) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    pub fn storage(&self) -> Option<RelationStorage> {
+        self.storage
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.empty
+    }
+
+    pub fn col_stat_mdids(&self) -> &[MdId] {
+        &self.col_stat_mdids
+    }
+}
+
+#[typetag::serde]
+impl Metadata for RelationStats {
+    fn size_hint(&self) -> usize {
+        size_of::<Self>() + self.name.len() + self.col_stat_mdids.len() * size_of::<MdId>()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ColumnMetadata {
+    name: String,
+    attno: u64,
+    nullable: bool,
+    width: u32,
+    default: Datum,
+    #[serde(default)]
+    data_type: DataType,
+}
+
+impl ColumnMetadata {
+    pub const fn new(name: String, attno: u64, nullable: bool, width: u32, default: Datum) -> Self {
+        Self {
+            name,
+            attno,
+            nullable,
+            width,
+            default,
+            data_type: DataType::Unknown,
+        }
+    }
+
+    /// Declares the type of the column, the width of fixed-width types is the width of the type.
+    pub fn with_data_type(mut self, data_type: DataType) -> Self {
+        if let Some(width) = data_type.width() {
+            self.width = width;
+        }
+        self.data_type = data_type;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn attno(&self) -> u64 {
+        self.attno
+    }
+
+    pub fn nullable(&self) -> bool {
+        self.nullable
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn default(&self) -> &Datum {
+        &self.default
+    }
+
+    pub fn data_type(&self) -> DataType {
+        self.data_type
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IndexInfo {
+    mdid: MdId,
+}
+
+impl IndexInfo {
+    pub fn new(mdid: MdId) -> Self {
+        Self { mdid }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RelationMetadata {
+    name: String,
+    column_metadata: Vec<ColumnMetadata>,
+    rel_stats_mdid: MdId,
+    index_info_list: Vec<IndexInfo>,
+    #[serde(default)]
+    extended_stats: Vec<ExtendedStats>,
+    #[serde(default)]
+    constraints: Vec<Constraint>,
+}
+
+impl RelationMetadata {
+    pub const fn new(
+        name: String,
+        column_metadata: Vec<ColumnMetadata>,
+        rel_stats_mdid: MdId,
+        index_info_list: Vec<IndexInfo>,
+    ) -> Self {
+        Self {
+            name,
+            column_metadata,
+            rel_stats_mdid,
+            index_info_list,
+            extended_stats: vec![],
+            constraints: vec![],
+        }
+    }
+
+    /// Attaches statistics of groups of correlated columns, the columns are ordinal positions in the relation.
+    pub fn with_extended_stats(mut self, extended_stats: Vec<ExtendedStats>) -> Self {
+        self.extended_stats = extended_stats;
+        self
+    }
+
+    /// Attaches the constraints of the relation, the columns are ordinal positions in the relation.
+    pub fn with_constraints(mut self, constraints: Vec<Constraint>) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn column_metadata(&self) -> &[ColumnMetadata] {
+        &self.column_metadata
+    }
+
+    pub fn rel_stats_mdid(&self) -> MdId {
+        self.rel_stats_mdid
+    }
+
+    pub fn index_count(&self) -> usize {
+        self.index_info_list.len()
+    }
+
+    pub fn index_mdid(&self, id: usize) -> MdId {
+        self.index_info_list[id].mdid
+    }
+
+    pub fn extended_stats(&self) -> &[ExtendedStats] {
+        &self.extended_stats
+    }
+
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// Returns the columns of the primary key and of the unique constraints.
+    pub fn keys(&self) -> Vec<&[u32]> {
+        self.constraints
+            .iter()
+            .filter_map(|constraint| match constraint {
+                Constraint::PrimaryKey(columns) | Constraint::Unique(columns) => Some(columns.as_slice()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the columns that cannot be NULL: the non-nullable columns, the columns of a NOT NULL constraint and the
+    /// columns of the primary key, in order.
+    pub fn not_null_columns(&self) -> Vec<u32> {
+        let mut columns: Vec<u32> = (0..self.column_metadata.len() as u32)
+            .filter(|col| !self.column_metadata[*col as usize].nullable())
+            .collect();
+        for constraint in &self.constraints {
+            match constraint {
+                Constraint::PrimaryKey(key) => columns.extend(key),
+                Constraint::NotNull(column) => columns.push(*column),
+                _ => {}
+            }
+        }
+        columns.sort_unstable();
+        columns.dedup();
+        columns
+    }
+
+    /// Returns the predicates of the check constraints.
+    pub fn check_constraints(&self) -> impl Iterator<Item = &dyn ScalarExpression> {
+        self.constraints.iter().filter_map(|constraint| match constraint {
+            Constraint::Check(predicate) => Some(predicate.as_ref()),
+            _ => None,
+        })
+    }
+}
+
+#[typetag::serde]
+impl Metadata for RelationMetadata {
+    fn size_hint(&self) -> usize {
+        let columns: usize = self
+            .column_metadata
+            .iter()
+            .map(|column| size_of::<ColumnMetadata>() + column.name.len())
+            .sum();
+        size_of::<Self>()
+            + self.name.len()
+            + columns
+            + self.index_info_list.len() * size_of::<IndexInfo>()
+            + self.extended_stats.len() * size_of::<ExtendedStats>()
+            + self.constraints.len() * size_of::<Constraint>()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum IndexType {
+    Btree,
+    /// Entries hashed on the values of all the key columns.
+    Hash,
+    /// A bitmap of the rows of every distinct value of the key columns.
+    Bitmap,
+    /// The range of the values of the key columns in every block range of the relation.
+    Brin,
+}
+
+impl IndexType {
+    /// Returns whether the index finds the rows by comparisons other than equality, e.g. `c1 < 5`.
+    pub fn supports_range(&self) -> bool {
+        matches!(self, IndexType::Btree | IndexType::Brin)
+    }
+
+    /// Returns whether the index finds the rows whose key columns are NULL, or are not.
+    pub fn supports_null_test(&self) -> bool {
+        !matches!(self, IndexType::Hash)
+    }
+
+    /// Returns whether the index finds rows only by equalities on all its key columns.
+    pub fn requires_all_keys(&self) -> bool {
+        matches!(self, IndexType::Hash)
+    }
+
+    /// Returns whether a scan of the index returns the rows in the order of the key columns.
+    pub fn delivers_order(&self) -> bool {
+        matches!(self, IndexType::Btree)
+    }
+
+    /// Returns whether the index finds a superset of the matching rows, which are rechecked against the rows of the
+    /// relation, so a scan of the index is never index-only.
+    pub fn is_lossy(&self) -> bool {
+        matches!(self, IndexType::Brin)
+    }
+}
+
+/// The order of the entries of an index on a key.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexKeyOrder {
+    pub ascending: bool,
+    pub nulls_first: bool,
+}
+
+impl Default for IndexKeyOrder {
+    fn default() -> Self {
+        Self {
+            ascending: true,
+            nulls_first: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IndexMd {
+    mdid: MdId,
+    index_name: String,
+    index_type: IndexType,
+    keys: Vec<Box<dyn ScalarExpression>>, // columns or expressions of the columns, in the order of the entries
+    included_columns: Vec<ColumnVar>,
+    #[serde(default)]
+    unique: bool,
+    #[serde(default = "one")]
+    leaf_pages: u64,
+    #[serde(default = "one")]
+    height: u32, // levels from the root to the leaf pages, the leaf pages included
+    #[serde(default)]
+    correlation: f64, // correlation between the order of the index and the physical order of the rows, in [-1, 1]
+    #[serde(default)]
+    key_orders: Vec<IndexKeyOrder>, // the default order for every key if empty
+    #[serde(default)]
+    predicate: Option<Box<dyn ScalarExpression>>, // the predicate of the rows of a partial index
+}
+
+fn one<T: From<u8>>() -> T {
+    T::from(1)
+}
+
+impl IndexMd {
+    /// Creates an index on the key columns, see `with_keys` for keys on expressions.
+    pub fn new(mdid: MdId, index_name: String, key_columns: Vec<ColumnVar>, included_columns: Vec<ColumnVar>) -> Self {
+        Self {
+            mdid,
+            index_name,
+            index_type: IndexType::Btree,
+            keys: key_columns
+                .into_iter()
+                .map(|column| Box::new(column) as Box<dyn ScalarExpression>)
+                .collect(),
+            included_columns,
+            unique: false,
+            leaf_pages: 1,
+            height: 1,
+            correlation: 0.0,
+            key_orders: vec![],
+            predicate: None,
+        }
+    }
+
+    pub fn with_index_type(mut self, index_type: IndexType) -> Self {
+        self.index_type = index_type;
+        self
+    }
+
+    pub fn with_unique(mut self, unique: bool) -> Self {
+        self.unique = unique;
+        self
+    }
+
+    pub fn with_storage(mut self, leaf_pages: u64, height: u32) -> Self {
+        assert!(leaf_pages > 0 && height > 0, "an index has at least one page");
+        self.leaf_pages = leaf_pages;
+        self.height = height;
+        self
+    }
+
+    pub fn with_correlation(mut self, correlation: f64) -> Self {
+        assert!((-1.0..=1.0).contains(&correlation), "correlation must be in [-1, 1]");
+        self.correlation = correlation;
+        self
+    }
+
+    pub fn with_key_orders(mut self, key_orders: Vec<IndexKeyOrder>) -> Self {
+        assert_eq!(key_orders.len(), self.key_count(), "one order per key expected");
+        self.key_orders = key_orders;
+        self
+    }
+
+    /// Sets the keys in order, columns or expressions of the columns, e.g. `(lower(name), id)`.
+    pub fn with_keys(mut self, keys: Vec<Box<dyn ScalarExpression>>) -> Self {
+        assert!(self.key_orders.is_empty(), "keys must be set before key orders");
+        self.keys = keys;
+        self
+    }
+
+    /// Makes the index partial, with the entries of the rows satisfying `predicate` only.
+    pub fn with_predicate(mut self, predicate: Box<dyn ScalarExpression>) -> Self {
+        assert!(predicate.is_boolean_expression());
+        self.predicate = Some(predicate);
+        self
+    }
+
+    pub fn mdid(&self) -> MdId {
+        self.mdid
+    }
+
+    pub fn index_name(&self) -> &str {
+        &self.index_name
+    }
+
+    pub fn index_type(&self) -> IndexType {
+        self.index_type
+    }
+
+    /// Returns the keys in order, columns or expressions of the columns.
+    pub fn keys(&self) -> &[Box<dyn ScalarExpression>] {
+        &self.keys
+    }
+
+    /// Returns the keys that are columns, in order.
+    pub fn key_columns(&self) -> impl Iterator<Item = &ColumnVar> {
+        self.keys.iter().filter_map(|key| key.downcast_ref::<ColumnVar>())
+    }
+
+    pub fn included_columns(&self) -> &[ColumnVar] {
+        &self.included_columns
+    }
+
+    /// Returns the number of keys, on columns and on expressions.
+    pub fn key_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn predicate(&self) -> Option<&dyn ScalarExpression> {
+        self.predicate.as_deref()
+    }
+
+    pub fn unique(&self) -> bool {
+        self.unique
+    }
+
+    pub fn leaf_pages(&self) -> u64 {
+        self.leaf_pages
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn correlation(&self) -> f64 {
+        self.correlation
+    }
+
+    /// Returns the order of the entries on every key.
+    pub fn key_orders(&self) -> Vec<IndexKeyOrder> {
+        match self.key_orders.is_empty() {
+            true => vec![IndexKeyOrder::default(); self.key_count()],
+            false => self.key_orders.clone(),
+        }
+    }
+}
+
+#[typetag::serde]
+impl Metadata for IndexMd {
+    fn size_hint(&self) -> usize {
+        let expressions = self.keys.len() + self.predicate.iter().count();
+        size_of::<Self>()
+            + self.index_name.len()
+            + self.included_columns.len() * size_of::<ColumnVar>()
+            + self.key_orders.len() * size_of::<IndexKeyOrder>()
+            + expressions * size_of::<Box<dyn ScalarExpression>>()
+    }
+}
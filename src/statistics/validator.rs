@@ -0,0 +1,269 @@
+use crate::expression::referenced_columns;
+use crate::metadata::{MdCache, MdId, MdKind, MdProvider, MdValidator, Metadata, VersionedMdId};
+use crate::statistics::{ColumnStats, Constraint, Histogram, IndexMd, RelationMetadata, RelationStats};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+/// An inconsistency of a metadata catalog.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CatalogError {
+    /// The metadata of `mdid`, referenced by the metadata of `referrer`, is missing.
+    DanglingMdId { referrer: MdId, mdid: MdId },
+    /// The metadata of `mdid` is a `found` where a `expected` is expected, by the kind of `mdid` or by the metadata
+    /// referencing it.
+    TypeMismatch {
+        mdid: MdId,
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The bucket at position `bucket` of the histogram of the column statistics `mdid` has a lower bound above its
+    /// upper bound, or overlaps the previous bucket.
+    InvalidBucket { mdid: MdId, bucket: usize },
+    /// The metadata of `mdid` refers to the column at position `column` of a relation of `columns` columns.
+    ColumnOutOfRange { mdid: MdId, column: usize, columns: usize },
+    /// The extended statistics of the relation `mdid` list the column at position `column` more than once.
+    DuplicateColumn { mdid: MdId, column: usize },
+    /// The metadata of `referrer` references a version of `mdid` rather than the object, see `VersionedMdId`.
+    VersionedReference { referrer: MdId, mdid: MdId },
+}
+
+impl Display for CatalogError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CatalogError::DanglingMdId { referrer, mdid } => {
+                write!(f, "{:?} references missing metadata {:?}", referrer, mdid)
+            }
+            CatalogError::TypeMismatch { mdid, expected, found } => {
+                write!(f, "{:?} is a {} where a {} is expected", mdid, found, expected)
+            }
+            CatalogError::InvalidBucket { mdid, bucket } => {
+                write!(f, "bucket {} of the histogram of {:?} is out of order", bucket, mdid)
+            }
+            CatalogError::ColumnOutOfRange { mdid, column, columns } => {
+                write!(
+                    f,
+                    "{:?} references column {} of a relation of {} columns",
+                    mdid, column, columns
+                )
+            }
+            CatalogError::DuplicateColumn { mdid, column } => {
+                write!(f, "extended statistics of {:?} repeat column {}", mdid, column)
+            }
+            CatalogError::VersionedReference { referrer, mdid } => {
+                write!(f, "{:?} references version {} of {:?}", referrer, mdid.version(), mdid)
+            }
+        }
+    }
+}
+
+/// Validates the relations of a provider and the metadata they reference. Metadata of a provider cannot be listed, so
+/// only the metadata reachable from the relations is validated.
+pub fn validate_relations(md_provider: &MdProvider, relation_mdids: &[MdId]) -> Vec<CatalogError> {
+    let mut validator = CatalogValidator::new(
+        |mdid| md_provider.retrieve_metadata(mdid),
+        |mdid| md_provider.current_version(mdid),
+    );
+    for mdid in relation_mdids {
+        if let Some(current) = md_provider.current_version(mdid) {
+            validator.validate(current);
+        }
+    }
+    validator.errors
+}
+
+/// Validates every metadata object of the cache.
+pub fn validate_md_cache(md_cache: &MdCache) -> Vec<CatalogError> {
+    let mut validator = CatalogValidator::new(
+        |mdid| md_cache.get(mdid).cloned(),
+        |mdid| md_cache.current_version(mdid).cloned(),
+    );
+    for (mdid, _) in md_cache.iter() {
+        validator.validate(*mdid);
+    }
+    validator.errors
+}
+
+/// Returns a validator of the metadata loaded by a `FileMdProvider`, see `validate_md_cache`.
+pub fn catalog_validator() -> MdValidator {
+    Box::new(|md_cache| {
+        let errors = validate_md_cache(md_cache);
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors
+                .iter()
+                .map(CatalogError::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")),
+        }
+    })
+}
+
+/// The name of the metadata type of the metadata of a kind.
+fn type_name(kind: MdKind) -> &'static str {
+    match kind {
+        MdKind::Relation => "RelationMetadata",
+        MdKind::RelationStats => "RelationStats",
+        MdKind::ColumnStats => "ColumnStats",
+        MdKind::Index => "IndexMd",
+    }
+}
+
+struct CatalogValidator<F: Fn(&MdId) -> Option<Box<dyn Metadata>>, V: Fn(&MdId) -> Option<MdId>> {
+    retrieve_metadata: F,
+    current_version: V,
+    validated: HashSet<MdId>,
+    errors: Vec<CatalogError>,
+}
+
+impl<F: Fn(&MdId) -> Option<Box<dyn Metadata>>, V: Fn(&MdId) -> Option<MdId>> CatalogValidator<F, V> {
+    fn new(retrieve_metadata: F, current_version: V) -> Self {
+        Self {
+            retrieve_metadata,
+            current_version,
+            validated: HashSet::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Validates the metadata of `mdid` and the references of a relation, once.
+    fn validate(&mut self, mdid: MdId) {
+        if !self.validated.insert(mdid) {
+            return;
+        }
+        let Some(md) = (self.retrieve_metadata)(&mdid) else {
+            return;
+        };
+        if md.typetag_name() != type_name(mdid.kind()) {
+            self.errors.push(CatalogError::TypeMismatch {
+                mdid,
+                expected: type_name(mdid.kind()),
+                found: md.typetag_name(),
+            });
+        }
+
+        if let Some(relation_md) = md.downcast_ref::<RelationMetadata>() {
+            self.validate_relation(mdid, relation_md);
+        } else if let Some(col_stats) = md.downcast_ref::<ColumnStats>() {
+            if let Some(histogram) = col_stats.histogram() {
+                self.validate_histogram(mdid, histogram);
+            }
+        }
+    }
+
+    fn validate_relation(&mut self, mdid: MdId, relation_md: &RelationMetadata) {
+        let columns = relation_md.column_metadata().len();
+        let check_column = |errors: &mut Vec<CatalogError>, referrer: MdId, column: usize| {
+            if column >= columns {
+                errors.push(CatalogError::ColumnOutOfRange {
+                    mdid: referrer,
+                    column,
+                    columns,
+                });
+            }
+        };
+
+        for extended_stats in relation_md.extended_stats() {
+            let mut seen = HashSet::new();
+            for column in extended_stats.columns() {
+                check_column(&mut self.errors, mdid, *column as usize);
+                if !seen.insert(column) {
+                    self.errors.push(CatalogError::DuplicateColumn {
+                        mdid,
+                        column: *column as usize,
+                    });
+                }
+            }
+        }
+
+        for constraint in relation_md.constraints() {
+            for column in constraint.columns() {
+                check_column(&mut self.errors, mdid, column as usize);
+            }
+            if let Constraint::ForeignKey {
+                referenced_relation,
+                referenced_columns,
+                ..
+            } = constraint
+            {
+                if let Some(referenced) =
+                    self.retrieve::<RelationMetadata>(mdid, *referenced_relation, MdKind::Relation)
+                {
+                    let referenced_count = referenced.column_metadata().len();
+                    for column in referenced_columns {
+                        if *column as usize >= referenced_count {
+                            self.errors.push(CatalogError::ColumnOutOfRange {
+                                mdid,
+                                column: *column as usize,
+                                columns: referenced_count,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        let rel_stats_mdid = relation_md.rel_stats_mdid();
+        if let Some(rel_stats) = self.retrieve::<RelationStats>(mdid, rel_stats_mdid, MdKind::RelationStats) {
+            for col_stats_mdid in rel_stats.col_stat_mdids() {
+                if let Some(col_stats) =
+                    self.retrieve::<ColumnStats>(rel_stats_mdid, *col_stats_mdid, MdKind::ColumnStats)
+                {
+                    check_column(&mut self.errors, *col_stats_mdid, col_stats.col_id());
+                }
+            }
+        }
+
+        for i in 0..relation_md.index_count() {
+            let index_mdid = relation_md.index_mdid(i);
+            if let Some(index_md) = self.retrieve::<IndexMd>(mdid, index_mdid, MdKind::Index) {
+                let keys = index_md.keys().iter().map(|key| key.as_ref());
+                let expression_columns = keys
+                    .chain(index_md.predicate())
+                    .flat_map(referenced_columns)
+                    .collect::<Vec<_>>();
+                for column in index_md.included_columns().iter().chain(&expression_columns) {
+                    check_column(&mut self.errors, index_mdid, column.id() as usize);
+                }
+            }
+        }
+    }
+
+    /// Buckets are ordered by their bounds and may only share a bound with their neighbours.
+    fn validate_histogram(&mut self, mdid: MdId, histogram: &Histogram) {
+        let buckets = histogram.buckets();
+        for (i, bucket) in buckets.iter().enumerate() {
+            let overlaps = i > 0 && bucket.lower() < buckets[i - 1].upper();
+            if bucket.lower() > bucket.upper() || overlaps {
+                self.errors.push(CatalogError::InvalidBucket { mdid, bucket: i });
+            }
+        }
+    }
+
+    /// Retrieves and validates the current version of the metadata of `mdid` referenced by `referrer`, which should be
+    /// a `M`, the metadata of `kind`.
+    fn retrieve<M: Metadata + Clone>(&mut self, referrer: MdId, mdid: MdId, kind: MdKind) -> Option<M> {
+        if mdid != mdid.unversioned() {
+            self.errors.push(CatalogError::VersionedReference { referrer, mdid });
+        }
+        let Some((current, md)) = (self.current_version)(&mdid)
+            .and_then(|current| (self.retrieve_metadata)(&current).map(|md| (current, md)))
+        else {
+            self.errors.push(CatalogError::DanglingMdId { referrer, mdid });
+            return None;
+        };
+        self.validate(current);
+        match md.downcast_ref::<M>() {
+            Some(md) => Some(md.clone()),
+            // the mismatch with the kind of the mdid is already reported
+            None if mdid.kind() == kind => None,
+            None => {
+                self.errors.push(CatalogError::TypeMismatch {
+                    mdid,
+                    expected: type_name(kind),
+                    found: md.typetag_name(),
+                });
+                None
+            }
+        }
+    }
+}
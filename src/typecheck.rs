@@ -0,0 +1,151 @@
+use crate::expression::{
+    as_comparison, And, Arithmetic, Between, Case, Cast, Coalesce, FunctionCall, InList, IsNotNull, IsNull, Like, Not,
+    Or,
+};
+use cso_core::datatype::DataType;
+use cso_core::expression::ScalarExpression;
+use std::fmt;
+
+/// An expression whose operands have types the operator does not accept.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeError {
+    message: String,
+}
+
+impl TypeError {
+    fn new(message: String) -> Self {
+        Self { message }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "type error: {}", self.message)
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+/// Returns the type both operands of a comparison are implicitly converted to before they are compared.
+pub fn comparison_type(left: &dyn ScalarExpression, right: &dyn ScalarExpression) -> Result<DataType, TypeError> {
+    let (left_type, right_type) = (left.return_type(), right.return_type());
+    left_type.common_type(&right_type).ok_or_else(|| {
+        TypeError::new(format!(
+            "cannot compare {left_type:?} with {right_type:?} in {left:?} and {right:?}"
+        ))
+    })
+}
+
+/// Checks the types of the operands of every operator of the expression and returns the type of the expression.
+pub fn type_check(expr: &dyn ScalarExpression) -> Result<DataType, TypeError> {
+    if let Some((_, left, right)) = as_comparison(expr) {
+        type_check(left)?;
+        type_check(right)?;
+        comparison_type(left, right)?;
+    } else if let Some(and) = expr.downcast_ref::<And>() {
+        and.expressions()
+            .iter()
+            .try_for_each(|expr| check_boolean(expr.as_ref()))?;
+    } else if let Some(or) = expr.downcast_ref::<Or>() {
+        or.expressions()
+            .iter()
+            .try_for_each(|expr| check_boolean(expr.as_ref()))?;
+    } else if let Some(not) = expr.downcast_ref::<Not>() {
+        check_boolean(not.expression())?;
+    } else if let Some(is_null) = expr.downcast_ref::<IsNull>() {
+        type_check(is_null.inner())?;
+    } else if let Some(is_not_null) = expr.downcast_ref::<IsNotNull>() {
+        type_check(is_not_null.inner())?;
+    } else if let Some(arithmetic) = expr.downcast_ref::<Arithmetic>() {
+        for operand in [arithmetic.left(), arithmetic.right()] {
+            match type_check(operand)? {
+                DataType::Unknown => {}
+                data_type if data_type.is_numeric() => {}
+                data_type => {
+                    return Err(TypeError::new(format!(
+                        "{data_type:?} operand {operand:?} of {:?} is not a number",
+                        arithmetic.op()
+                    )))
+                }
+            }
+        }
+        comparison_type(arithmetic.left(), arithmetic.right())?;
+    } else if let Some(cast) = expr.downcast_ref::<Cast>() {
+        type_check(cast.expr())?;
+    } else if let Some(in_list) = expr.downcast_ref::<InList>() {
+        type_check(in_list.expr())?;
+        in_list.list().iter().try_for_each(|item| {
+            type_check(item.as_ref())?;
+            comparison_type(in_list.expr(), item.as_ref()).map(|_| ())
+        })?;
+    } else if let Some(between) = expr.downcast_ref::<Between>() {
+        type_check(between.expr())?;
+        for bound in [between.low(), between.high()] {
+            type_check(bound)?;
+            comparison_type(between.expr(), bound)?;
+        }
+    } else if let Some(like) = expr.downcast_ref::<Like>() {
+        for operand in [like.expr(), like.pattern()] {
+            match type_check(operand)? {
+                DataType::String | DataType::Unknown => {}
+                data_type => {
+                    return Err(TypeError::new(format!(
+                        "{data_type:?} operand {operand:?} of LIKE is not a string"
+                    )))
+                }
+            }
+        }
+    } else if let Some(case) = expr.downcast_ref::<Case>() {
+        for (condition, result) in case.branches() {
+            match case.operand() {
+                Some(operand) => {
+                    type_check(condition.as_ref())?;
+                    comparison_type(operand, condition.as_ref())?;
+                }
+                None => check_boolean(condition.as_ref())?,
+            }
+            type_check(result.as_ref())?;
+        }
+        case.operand().map(type_check).transpose()?;
+        case.else_result().map(type_check).transpose()?;
+        let results = case.branches().iter().map(|(_, result)| result.as_ref());
+        check_common_type(results.chain(case.else_result()))?;
+    } else if let Some(coalesce) = expr.downcast_ref::<Coalesce>() {
+        coalesce
+            .expressions()
+            .iter()
+            .try_for_each(|expr| type_check(expr.as_ref()).map(|_| ()))?;
+        check_common_type(coalesce.expressions().iter().map(|expr| expr.as_ref()))?;
+    } else if let Some(call) = expr.downcast_ref::<FunctionCall>() {
+        call.args()
+            .iter()
+            .try_for_each(|expr| type_check(expr.as_ref()).map(|_| ()))?;
+    }
+    Ok(expr.return_type())
+}
+
+/// Checks that the values of the expressions, e.g. the results of a CASE, convert to a common type.
+fn check_common_type<'a>(mut exprs: impl Iterator<Item = &'a dyn ScalarExpression>) -> Result<(), TypeError> {
+    let mut common = DataType::Unknown;
+    exprs.try_for_each(|expr| {
+        let data_type = expr.return_type();
+        common = common
+            .common_type(&data_type)
+            .ok_or_else(|| TypeError::new(format!("{data_type:?} value {expr:?} does not convert to {common:?}")))?;
+        Ok(())
+    })
+}
+
+/// Checks an operand of a logical operator, NULL is a boolean of unknown value.
+fn check_boolean(expr: &dyn ScalarExpression) -> Result<(), TypeError> {
+    match type_check(expr)? {
+        DataType::Boolean | DataType::Unknown => Ok(()),
+        data_type => Err(TypeError::new(format!(
+            "{data_type:?} operand {expr:?} is not a boolean"
+        ))),
+    }
+}
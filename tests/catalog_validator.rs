@@ -0,0 +1,233 @@
+use cso_demo::datum::Datum;
+use cso_demo::expression::{ColumnVar, IsNotNull, IsNull};
+use cso_demo::metadata::{CachedMdProvider, MdCache, MdId, Metadata};
+use cso_demo::statistics::{
+    catalog_validator, validate_md_cache, validate_relations, Bucket, CatalogError, ColumnMetadata, ColumnStats,
+    ExtendedStats, Histogram, IndexInfo, IndexMd, RelationMetadata, RelationStats,
+};
+
+fn relation_md(rel_stats_mdid: MdId, index_mdid: MdId) -> Box<dyn Metadata> {
+    let column_md = vec![
+        ColumnMetadata::new("c1".to_string(), 1, true, 4, Datum::I32(0)),
+        ColumnMetadata::new("c2".to_string(), 2, true, 4, Datum::I32(0)),
+    ];
+    let relation_md = RelationMetadata::new(
+        "t1".to_string(),
+        column_md,
+        rel_stats_mdid,
+        vec![IndexInfo::new(index_mdid)],
+    );
+    Box::new(relation_md)
+}
+
+fn column_stats(col_id: usize, buckets: Vec<Bucket>) -> Box<dyn Metadata> {
+    let histogram = Histogram::new(buckets);
+    Box::new(ColumnStats::new(
+        col_id,
+        "c1".to_string(),
+        Datum::I32(0),
+        Datum::I32(9),
+        0,
+        Some(histogram),
+    ))
+}
+
+fn index_md(columns: Vec<u32>) -> Box<dyn Metadata> {
+    let columns: Vec<_> = columns.into_iter().map(ColumnVar::new).collect();
+    Box::new(IndexMd::new(
+        MdId::index(4),
+        "IDX_1".to_string(),
+        columns.clone(),
+        columns,
+    ))
+}
+
+fn bucket(lower: i32, upper: i32) -> Bucket {
+    Bucket::new(Datum::I32(lower), Datum::I32(upper), 1, 1)
+}
+
+fn catalog() -> MdCache {
+    let relation_stats = RelationStats::new("t1".to_string(), 100, false, vec![MdId::column_stats(3)]);
+    let mut md_cache = MdCache::new();
+    md_cache.insert(MdId::relation_stats(1), Box::new(relation_stats));
+    md_cache.insert(MdId::relation(2), relation_md(MdId::relation_stats(1), MdId::index(4)));
+    md_cache.insert(MdId::column_stats(3), column_stats(0, vec![bucket(0, 1), bucket(1, 3)]));
+    md_cache.insert(MdId::index(4), index_md(vec![0, 1]));
+    md_cache
+}
+
+#[test]
+fn test_validate_consistent_catalog() {
+    let md_cache = catalog();
+    assert_eq!(validate_md_cache(&md_cache), vec![]);
+    let md_provider = CachedMdProvider::new(md_cache);
+    assert_eq!(validate_relations(&md_provider, &[MdId::relation(2)]), vec![]);
+}
+
+#[test]
+fn test_validate_dangling_mdids() {
+    let mut md_cache = catalog();
+    md_cache.insert(MdId::relation(2), relation_md(MdId::relation_stats(5), MdId::index(6)));
+    let errors = validate_md_cache(&md_cache);
+    assert_eq!(errors.len(), 2);
+    assert!(errors.contains(&CatalogError::DanglingMdId {
+        referrer: MdId::relation(2),
+        mdid: MdId::relation_stats(5)
+    }));
+    assert!(errors.contains(&CatalogError::DanglingMdId {
+        referrer: MdId::relation(2),
+        mdid: MdId::index(6)
+    }));
+    let validator = catalog_validator();
+    let err = validator(&md_cache).unwrap_err();
+    assert!(err.contains("references missing metadata"), "{}", err);
+    assert!(validator(&catalog()).is_ok());
+}
+
+#[test]
+fn test_validate_versioned_references() {
+    // a new version of the statistics keeps the relation valid, the relation references the statistics object
+    let mut md_cache = catalog();
+    let relation_stats = RelationStats::new("t1".to_string(), 200, false, vec![MdId::column_stats(3)]);
+    md_cache.insert(MdId::relation_stats(1).with_version(2), Box::new(relation_stats));
+    assert_eq!(validate_md_cache(&md_cache), vec![]);
+
+    // a reference to a version is reported
+    let rel_stats_mdid = MdId::relation_stats(1).with_version(2);
+    md_cache.insert(MdId::relation(2), relation_md(rel_stats_mdid, MdId::index(4)));
+    assert_eq!(
+        validate_md_cache(&md_cache),
+        vec![CatalogError::VersionedReference {
+            referrer: MdId::relation(2),
+            mdid: rel_stats_mdid
+        }]
+    );
+}
+
+#[test]
+fn test_validate_type_mismatches() {
+    // the relation stats id refers to the index
+    let mut md_cache = catalog();
+    md_cache.insert(MdId::relation(2), relation_md(MdId::index(4), MdId::index(4)));
+    let errors = validate_md_cache(&md_cache);
+    assert_eq!(
+        errors,
+        vec![CatalogError::TypeMismatch {
+            mdid: MdId::index(4),
+            expected: "RelationStats",
+            found: "IndexMd"
+        }]
+    );
+
+    // index metadata stored with the id of column statistics is reported once
+    let mut md_cache = catalog();
+    md_cache.insert(MdId::column_stats(3), index_md(vec![0]));
+    let errors = validate_relations(&CachedMdProvider::new(md_cache), &[MdId::relation(2)]);
+    assert_eq!(
+        errors,
+        vec![CatalogError::TypeMismatch {
+            mdid: MdId::column_stats(3),
+            expected: "ColumnStats",
+            found: "IndexMd"
+        }]
+    );
+}
+
+#[test]
+fn test_validate_histograms_and_columns() {
+    let mut md_cache = catalog();
+    // overlapping and reversed buckets, a column statistics of a missing column
+    md_cache.insert(
+        MdId::column_stats(3),
+        column_stats(2, vec![bucket(0, 2), bucket(1, 3), bucket(5, 4)]),
+    );
+    // an index on a missing column
+    md_cache.insert(MdId::index(4), index_md(vec![0, 7]));
+    let errors = validate_md_cache(&md_cache);
+    assert_eq!(errors.len(), 5, "{:?}", errors);
+    for bucket in [1, 2] {
+        assert!(errors.contains(&CatalogError::InvalidBucket {
+            mdid: MdId::column_stats(3),
+            bucket
+        }));
+    }
+    assert!(errors.contains(&CatalogError::ColumnOutOfRange {
+        mdid: MdId::column_stats(3),
+        column: 2,
+        columns: 2
+    }));
+    // the column is both a key column and an included column
+    let index_errors = errors.iter().filter(|error| {
+        **error
+            == CatalogError::ColumnOutOfRange {
+                mdid: MdId::index(4),
+                column: 7,
+                columns: 2,
+            }
+    });
+    assert_eq!(index_errors.count(), 2);
+
+    // extended statistics on a missing column
+    let column_md = vec![ColumnMetadata::new("c1".to_string(), 1, true, 4, Datum::I32(0))];
+    let relation_md = RelationMetadata::new("t1".to_string(), column_md, MdId::relation_stats(1), vec![])
+        .with_extended_stats(vec![ExtendedStats::new(vec![0, 1])]);
+    let mut md_cache = MdCache::new();
+    md_cache.insert(MdId::relation(2), Box::new(relation_md));
+    md_cache.insert(
+        MdId::relation_stats(1),
+        Box::new(RelationStats::new("t1".to_string(), 0, true, vec![])),
+    );
+    let errors = validate_md_cache(&md_cache);
+    assert_eq!(
+        errors,
+        vec![CatalogError::ColumnOutOfRange {
+            mdid: MdId::relation(2),
+            column: 1,
+            columns: 1
+        }]
+    );
+
+    // extended statistics repeating a column
+    let mut md_cache = catalog();
+    let column_md = vec![
+        ColumnMetadata::new("c1".to_string(), 1, true, 4, Datum::I32(0)),
+        ColumnMetadata::new("c2".to_string(), 2, true, 4, Datum::I32(0)),
+    ];
+    let relation_md = RelationMetadata::new(
+        "t1".to_string(),
+        column_md,
+        MdId::relation_stats(1),
+        vec![IndexInfo::new(MdId::index(4))],
+    )
+    .with_extended_stats(vec![ExtendedStats::new(vec![0, 1, 0])]);
+    md_cache.insert(MdId::relation(2), Box::new(relation_md));
+    assert_eq!(
+        validate_md_cache(&md_cache),
+        vec![CatalogError::DuplicateColumn {
+            mdid: MdId::relation(2),
+            column: 0
+        }]
+    );
+}
+
+#[test]
+fn test_validate_index_expressions() {
+    let mut md_cache = catalog();
+    // a key expression and a predicate on missing columns
+    let index_md = IndexMd::new(MdId::index(4), "IDX_1".to_string(), vec![], vec![])
+        .with_keys(vec![
+            Box::new(ColumnVar::new(0)),
+            Box::new(IsNull::new(Box::new(ColumnVar::new(5)))),
+        ])
+        .with_predicate(Box::new(IsNotNull::new(Box::new(ColumnVar::new(6)))));
+    md_cache.insert(MdId::index(4), Box::new(index_md));
+    let errors = validate_md_cache(&md_cache);
+    let columns: Vec<_> = errors
+        .iter()
+        .map(|error| match error {
+            CatalogError::ColumnOutOfRange { mdid, column, .. } if *mdid == MdId::index(4) => *column,
+            error => panic!("unexpected error {:?}", error),
+        })
+        .collect();
+    assert_eq!(columns, vec![5, 6]);
+}
@@ -0,0 +1,224 @@
+use cso_core::operator::LogicalOperator;
+use cso_demo::datum::Datum;
+use cso_demo::expression::{
+    ColumnVar, Const, Equal, FunctionRegistry, GreaterThan, IsNotNull, IsNull, LessThan, ScalarExpression,
+};
+use cso_demo::index_match::match_index;
+use cso_demo::metadata::{CachedMdProvider, MdAccessor, MdCache, MdId, Metadata, Stats};
+use cso_demo::operator::logical_filter::LogicalFilter;
+use cso_demo::operator::logical_index_scan::IndexDesc;
+use cso_demo::operator::logical_scan::{LogicalScan, TableDesc};
+use cso_demo::selectivity::estimate_selectivity;
+use cso_demo::statistics::{
+    validate_md_cache, CatalogError, ColumnMetadata, Constraint, IndexInfo, IndexMd, RelationMetadata, RelationStats,
+    Statistics,
+};
+use cso_demo::Options;
+use std::rc::Rc;
+
+fn column(id: u32) -> Box<dyn ScalarExpression> {
+    Box::new(ColumnVar::new(id))
+}
+
+fn relation_md(constraints: Vec<Constraint>) -> RelationMetadata {
+    relation_md_with_indexes(constraints, vec![])
+}
+
+fn relation_md_with_indexes(constraints: Vec<Constraint>, index_info_list: Vec<IndexInfo>) -> RelationMetadata {
+    let column_md = vec![
+        ColumnMetadata::new("c1".to_string(), 1, true, 4, Datum::I32(0)),
+        ColumnMetadata::new("c2".to_string(), 2, true, 4, Datum::I32(0)),
+        ColumnMetadata::new("c3".to_string(), 3, true, 4, Datum::I32(0)),
+    ];
+    RelationMetadata::new("t1".to_string(), column_md, MdId::relation_stats(1), index_info_list)
+        .with_constraints(constraints)
+}
+
+/// t1(c1 PRIMARY KEY, c2 NOT NULL, c3 CHECK (c3 > 0) REFERENCES t2(c1)) of 1000 rows and t2(c1, c2, c3).
+fn md_cache() -> MdCache {
+    let constraints = vec![
+        Constraint::PrimaryKey(vec![0]),
+        Constraint::NotNull(1),
+        Constraint::Check(Box::new(GreaterThan::new(column(2), Box::new(Const::Int32(0))))),
+        Constraint::ForeignKey {
+            columns: vec![2],
+            referenced_relation: MdId::relation(3),
+            referenced_columns: vec![0],
+        },
+    ];
+    let relation_stats = RelationStats::new("t1".to_string(), 1000, false, vec![]);
+
+    let mut md_cache = MdCache::new();
+    md_cache.insert(MdId::relation_stats(1), Box::new(relation_stats));
+    md_cache.insert(MdId::relation(2), Box::new(relation_md(constraints)));
+    md_cache.insert(MdId::relation(3), Box::new(relation_md(vec![])));
+    md_cache
+}
+
+fn scan_stats(md_accessor: &MdAccessor) -> Rc<dyn Stats> {
+    let output_columns = vec![ColumnVar::new(10), ColumnVar::new(11), ColumnVar::new(12)];
+    let scan = LogicalScan::new(TableDesc::new(MdId::relation(2)), output_columns);
+    scan.derive_statistics(md_accessor, &Options::default(), &[])
+}
+
+fn filter_row_count(md_accessor: &MdAccessor, predicate: Rc<dyn ScalarExpression>) -> u64 {
+    let filter = LogicalFilter::new(predicate);
+    filter
+        .derive_statistics(md_accessor, &Options::default(), &[scan_stats(md_accessor)])
+        .output_row_count()
+}
+
+#[test]
+fn test_scan_derives_constraints() {
+    let md_accessor = MdAccessor::new(Rc::new(CachedMdProvider::new(md_cache())));
+    let stats = scan_stats(&md_accessor);
+    let stats = stats.as_any().downcast_ref::<Statistics>().unwrap();
+
+    // the constraints are on the column vars of the scan
+    assert_eq!(stats.keys(), &[vec![10]]);
+    assert_eq!(stats.not_null_columns(), &[10, 11]);
+    let check = GreaterThan::new(column(12), Box::new(Const::Int32(0)));
+    assert_eq!(stats.check_constraints().len(), 1);
+    assert!(stats.check_constraints()[0].equal(&check));
+
+    let projected = stats.project(&[ColumnVar::new(11), ColumnVar::new(12)]);
+    assert!(projected.keys().is_empty());
+    assert_eq!(projected.not_null_columns(), &[11]);
+    assert_eq!(projected.check_constraints().len(), 1);
+}
+
+#[test]
+fn test_unique_index_on_column_vars() {
+    // a unique index on c2 of t1, whose columns are positions in t1 rather than column vars
+    let mut md_cache = md_cache();
+    let relation_md = relation_md_with_indexes(
+        vec![Constraint::PrimaryKey(vec![0])],
+        vec![IndexInfo::new(MdId::index(4))],
+    );
+    let index_md = IndexMd::new(MdId::index(4), "IDX_1".to_string(), vec![ColumnVar::new(1)], vec![]).with_unique(true);
+    md_cache.insert(MdId::relation(2), Box::new(relation_md));
+    md_cache.insert(MdId::index(4), Box::new(index_md.clone()));
+    let md_accessor = MdAccessor::new(Rc::new(CachedMdProvider::new(md_cache)));
+
+    // the key of the index is on the column var of c2 in the statistics of the scan and in the index description
+    let stats = scan_stats(&md_accessor);
+    let stats = stats.as_any().downcast_ref::<Statistics>().unwrap();
+    assert_eq!(stats.keys(), &[vec![10], vec![11]]);
+    let output_columns = vec![ColumnVar::new(10), ColumnVar::new(11), ColumnVar::new(12)];
+    let index_desc = IndexDesc::from_index_md(&index_md, &output_columns).unwrap();
+    assert_eq!(index_desc.key_columns().collect::<Vec<_>>(), vec![&ColumnVar::new(11)]);
+    assert!(IndexDesc::from_index_md(&index_md, &output_columns[..1]).is_none());
+
+    // so a predicate on the column var of c2 matches the index
+    let equality: Rc<dyn ScalarExpression> = Rc::new(Equal::new(column(11), Box::new(Const::Int32(5))));
+    let index_match = match_index(&index_desc, std::slice::from_ref(&equality));
+    assert_eq!(index_match.conditions(), &[equality]);
+}
+
+#[test]
+fn test_partial_index_on_column_vars() {
+    // an index on lower(c1) of the rows of c3 > 0 of t1, on positions in t1 rather than column vars
+    let registry = FunctionRegistry::with_builtins();
+    let index_md = IndexMd::new(MdId::index(4), "IDX_1".to_string(), vec![], vec![])
+        .with_keys(vec![Box::new(registry.call("lower", vec![column(0)]).unwrap())])
+        .with_predicate(Box::new(GreaterThan::new(column(2), Box::new(Const::Int32(0)))));
+
+    // the keys and the predicate are on the column vars of the scan, like the check constraints
+    let output_columns = vec![ColumnVar::new(10), ColumnVar::new(11), ColumnVar::new(12)];
+    let index_desc = IndexDesc::from_index_md(&index_md, &output_columns).unwrap();
+    let key = registry.call("lower", vec![column(10)]).unwrap();
+    assert!(index_desc.keys()[0].equal(&key));
+    let predicate = GreaterThan::new(column(12), Box::new(Const::Int32(0)));
+    assert!(index_desc.predicate().unwrap().equal(&predicate));
+    assert!(IndexDesc::from_index_md(&index_md, &output_columns[..2]).is_none());
+}
+
+#[test]
+fn test_constraint_selectivity() {
+    let md_accessor = MdAccessor::new(Rc::new(CachedMdProvider::new(md_cache())));
+    let stats = scan_stats(&md_accessor);
+    let stats = stats.as_any().downcast_ref::<Statistics>().unwrap();
+
+    // NOT NULL columns
+    assert_eq!(estimate_selectivity(&IsNull::new(column(11)), stats), 0.0);
+    assert_eq!(estimate_selectivity(&IsNotNull::new(column(11)), stats), 1.0);
+    assert!(estimate_selectivity(&IsNull::new(column(12)), stats) > 0.0);
+
+    // an equality on the primary key selects at most one row, an equality on another column the default fraction
+    let key_equality = Equal::new(column(10), Box::new(Const::Int32(5)));
+    assert_eq!(estimate_selectivity(&key_equality, stats), 0.001);
+    assert_eq!(filter_row_count(&md_accessor, Rc::new(key_equality)), 1);
+    let equality = Rc::new(Equal::new(column(11), Box::new(Const::Int32(5))));
+    assert_eq!(filter_row_count(&md_accessor, equality), 5);
+
+    // c3 < 0 contradicts the check constraint c3 > 0, c3 IS NULL does not
+    let contradiction = Rc::new(LessThan::new(column(12), Box::new(Const::Int32(0))));
+    assert_eq!(filter_row_count(&md_accessor, contradiction), 1);
+    let satisfiable = Rc::new(LessThan::new(column(12), Box::new(Const::Int32(10))));
+    assert!(filter_row_count(&md_accessor, satisfiable) > 1);
+    assert_eq!(filter_row_count(&md_accessor, Rc::new(IsNull::new(column(12)))), 5);
+
+    // the filtered rows keep the keys and have no NULL values in the compared columns
+    let filter = LogicalFilter::new(Rc::new(LessThan::new(column(12), Box::new(Const::Int32(10)))));
+    let filter_stats = filter.derive_statistics(&md_accessor, &Options::default(), &[scan_stats(&md_accessor)]);
+    let filter_stats = filter_stats.as_any().downcast_ref::<Statistics>().unwrap();
+    assert_eq!(filter_stats.keys(), &[vec![10]]);
+    assert_eq!(filter_stats.not_null_columns(), &[10, 11, 12]);
+}
+
+#[test]
+fn test_constraints_serialize() {
+    let md_cache = md_cache();
+    let json = serde_json::to_string(&md_cache).unwrap();
+    let deserialized: MdCache = serde_json::from_str(&json).unwrap();
+    let md = deserialized.get(&MdId::relation(2)).unwrap();
+    let md = md.downcast_ref::<RelationMetadata>().unwrap();
+    assert_eq!(md.constraints().len(), 4);
+    assert_eq!(md.keys(), vec![&[0][..]]);
+    assert_eq!(md.not_null_columns(), vec![0, 1]);
+    assert_eq!(md.check_constraints().count(), 1);
+
+    // constraints are optional
+    let md: Box<dyn Metadata> = Box::new(relation_md(vec![]));
+    let mut json = serde_json::to_value(&md).unwrap();
+    json.as_object_mut().unwrap().remove("constraints").unwrap();
+    let md: Box<dyn Metadata> = serde_json::from_value(json).unwrap();
+    assert!(md.downcast_ref::<RelationMetadata>().unwrap().constraints().is_empty());
+}
+
+#[test]
+fn test_validate_constraints() {
+    let mut md_cache = md_cache();
+    assert_eq!(validate_md_cache(&md_cache), vec![]);
+
+    let constraints = vec![
+        Constraint::Unique(vec![3]),
+        Constraint::ForeignKey {
+            columns: vec![2],
+            referenced_relation: MdId::relation(3),
+            referenced_columns: vec![5],
+        },
+        Constraint::ForeignKey {
+            columns: vec![2],
+            referenced_relation: MdId::relation(4),
+            referenced_columns: vec![0],
+        },
+    ];
+    md_cache.insert(MdId::relation(2), Box::new(relation_md(constraints)));
+    let errors = validate_md_cache(&md_cache);
+    assert_eq!(errors.len(), 3);
+    assert!(errors.contains(&CatalogError::ColumnOutOfRange {
+        mdid: MdId::relation(2),
+        column: 3,
+        columns: 3
+    }));
+    assert!(errors.contains(&CatalogError::ColumnOutOfRange {
+        mdid: MdId::relation(2),
+        column: 5,
+        columns: 3
+    }));
+    assert!(errors.contains(&CatalogError::DanglingMdId {
+        referrer: MdId::relation(2),
+        mdid: MdId::relation(4)
+    }));
+}
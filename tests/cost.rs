@@ -0,0 +1,158 @@
+use cso_core::operator::{LogicalOperator, PhysicalOperator};
+use cso_demo::cost::{IoCost, COST_CPU_TUPLE_COST_UNIT};
+use cso_demo::datum::Datum;
+use cso_demo::expression::{And, ColumnVar, IsNull, ScalarExpression};
+use cso_demo::metadata::{CachedMdProvider, MdAccessor, MdCache, MdId};
+use cso_demo::operator::logical_index_scan::{IndexDesc, LogicalIndexScan};
+use cso_demo::operator::logical_scan::{LogicalScan, TableDesc};
+use cso_demo::operator::physical_index_lookup::PhysicalIndexLookup;
+use cso_demo::operator::physical_index_scan::PhysicalIndexScan;
+use cso_demo::operator::physical_scan::PhysicalScan;
+use cso_demo::statistics::{
+    ColumnMetadata, IndexInfo, IndexMd, IndexType, RelationMetadata, RelationStats, RelationStorage, Statistics,
+};
+use cso_demo::Options;
+use std::rc::Rc;
+
+fn index_md() -> IndexMd {
+    IndexMd::new(MdId::index(4), "IDX_1".to_string(), vec![ColumnVar::new(0)], vec![]).with_storage(30, 2)
+}
+
+fn md_accessor(rows: u64, storage: Option<RelationStorage>) -> MdAccessor {
+    let relation_stats = RelationStats::new("t1".to_string(), rows, rows == 0, vec![]);
+    let relation_stats = match storage {
+        Some(storage) => relation_stats.with_storage(storage),
+        None => relation_stats,
+    };
+    let column_md = vec![
+        ColumnMetadata::new("c1".to_string(), 1, true, 8, Datum::I64(0)),
+        ColumnMetadata::new("c2".to_string(), 2, true, 8, Datum::I64(0)),
+    ];
+    let relation_md = RelationMetadata::new(
+        "t1".to_string(),
+        column_md,
+        MdId::relation_stats(1),
+        vec![IndexInfo::new(MdId::index(4))],
+    );
+
+    let mut md_cache = MdCache::new();
+    md_cache.insert(MdId::relation_stats(1), Box::new(relation_stats));
+    md_cache.insert(MdId::relation(2), Box::new(relation_md));
+    md_cache.insert(MdId::index(4), Box::new(index_md()));
+    MdAccessor::new(Rc::new(CachedMdProvider::new(md_cache)))
+}
+
+fn output_columns() -> Vec<ColumnVar> {
+    vec![ColumnVar::new(0), ColumnVar::new(1)]
+}
+
+fn scan_cost(md_accessor: &MdAccessor) -> f64 {
+    let scan = LogicalScan::new(TableDesc::new(MdId::relation(2)), output_columns());
+    let stats = scan.derive_statistics(md_accessor, &Options::default(), &[]);
+    let scan = PhysicalScan::new(TableDesc::new(MdId::relation(2)), output_columns());
+    scan.compute_cost(Some(stats.as_ref())).value()
+}
+
+/// The cost of an index scan of `c1 IS NULL` on `index_md`, which looks up c2 in the table.
+fn index_scan_cost(md_accessor: &MdAccessor, index_md: &IndexMd) -> f64 {
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(IsNull::new(Box::new(ColumnVar::new(0))));
+    let index_scan = LogicalIndexScan::new(
+        TableDesc::new(MdId::relation(2)),
+        index_md,
+        output_columns(),
+        predicate.clone(),
+    );
+    let stats = index_scan.derive_statistics(md_accessor, &Options::default(), &[]);
+    assert!(!index_scan.index_only());
+    let index_lookup = PhysicalIndexLookup::new(
+        IndexDesc::from_index_md(index_md, &output_columns()).unwrap(),
+        TableDesc::new(MdId::relation(2)),
+        output_columns(),
+        predicate,
+    );
+    index_lookup.compute_cost(Some(stats.as_ref())).value()
+}
+
+#[test]
+fn test_io_cost() {
+    let cost = IoCost::new(10.0, 2.0, 0.5) + IoCost::new(1.0, 1.0, 0.5);
+    assert_eq!(cost, IoCost::new(11.0, 3.0, 1.0));
+    assert_eq!(cost.cost().value(), 11.0 + 3.0 * 4.0 + 1.0);
+}
+
+#[test]
+fn test_scan_cost_by_storage() {
+    // 10000 rows of 16 bytes fill 20 pages
+    let md_accessor = md_accessor(10000, None);
+    let scan = LogicalScan::new(TableDesc::new(MdId::relation(2)), output_columns());
+    let stats = scan.derive_statistics(&md_accessor, &Options::default(), &[]);
+    let stats = stats.as_any().downcast_ref::<Statistics>().unwrap();
+    let storage = stats.relation_storage().unwrap();
+    assert_eq!(storage.pages(), 20);
+    assert_eq!(storage.avg_row_width(), 16);
+    assert_eq!(scan_cost(&md_accessor), 20.0 + 10000.0 * COST_CPU_TUPLE_COST_UNIT);
+
+    // the stored pages are read, and decompressed
+    let compressed = RelationStorage::new(10, 16).with_compression_ratio(2.0);
+    let compressed_cost = scan_cost(&self::md_accessor(10000, Some(compressed)));
+    assert!(compressed_cost > 10.0 + 10000.0 * COST_CPU_TUPLE_COST_UNIT);
+    assert!(compressed_cost < scan_cost(&md_accessor));
+}
+
+#[test]
+fn test_scan_versus_index_scan() {
+    // a relation of a single page is cheaper to scan than to look up at random
+    let md_accessor = md_accessor(100, None);
+    assert!(scan_cost(&md_accessor) < index_scan_cost(&md_accessor, &index_md()));
+
+    // the few rows of a large relation are cheaper to look up
+    let md_accessor = self::md_accessor(10000, Some(RelationStorage::new(5000, 4000)));
+    assert!(index_scan_cost(&md_accessor, &index_md()) < scan_cost(&md_accessor));
+}
+
+#[test]
+fn test_index_lookup_cost_of_empty_relation() {
+    // a lossy index fetches no row of an empty relation of no pages
+    let md_accessor = md_accessor(0, Some(RelationStorage::new(0, 16)));
+    let brin_index_md = index_md().with_index_type(IndexType::Brin);
+    assert!(index_scan_cost(&md_accessor, &brin_index_md).is_finite());
+    assert!(index_scan_cost(&md_accessor, &index_md()).is_finite());
+}
+
+#[test]
+fn test_partial_index_full_scan_cost() {
+    // a partial index of 1% of the rows of c2 IS NULL, which has both columns
+    let md_accessor = md_accessor(100000, None);
+    let index_md = IndexMd::new(
+        MdId::index(4),
+        "IDX_1".to_string(),
+        vec![ColumnVar::new(0)],
+        vec![ColumnVar::new(1)],
+    )
+    .with_predicate(Box::new(IsNull::new(Box::new(ColumnVar::new(1)))))
+    .with_storage(3, 1);
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(And::new(vec![]));
+    let index_scan = LogicalIndexScan::new(
+        TableDesc::new(MdId::relation(2)),
+        &index_md,
+        output_columns(),
+        predicate.clone(),
+    );
+    assert!(index_scan.index_only());
+    let stats = index_scan.derive_statistics(&md_accessor, &Options::default(), &[]);
+    let index_scan = PhysicalIndexScan::new(
+        IndexDesc::from_index_md(&index_md, &output_columns()).unwrap(),
+        TableDesc::new(MdId::relation(2)),
+        output_columns(),
+        predicate,
+    );
+
+    // the whole index is scanned, its entries are those of the rows of its predicate only
+    let whole_index_cost = index_scan.clone().compute_cost(Some(stats.as_ref())).value();
+    let partial_index_cost = index_scan
+        .with_indexed_fraction(0.01)
+        .compute_cost(Some(stats.as_ref()))
+        .value();
+    assert!(partial_index_cost < whole_index_cost);
+    assert!(partial_index_cost < scan_cost(&md_accessor));
+}
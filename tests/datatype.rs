@@ -0,0 +1,130 @@
+use cso_demo::datum::{Datum, Decimal};
+use cso_demo::expression::{And, ColumnVar, Const, Equal, IsNull, LessThan, Not, ScalarExpression};
+use cso_demo::statistics::ColumnMetadata;
+use cso_demo::typecheck::{comparison_type, type_check};
+use cso_demo::DataType;
+use std::rc::Rc;
+
+fn column(id: u32, data_type: DataType) -> Box<dyn ScalarExpression> {
+    Box::new(ColumnVar::new(id).with_data_type(data_type))
+}
+
+#[test]
+fn test_implicit_casts() {
+    assert!(DataType::Int16.can_implicitly_cast_to(&DataType::Int64));
+    assert!(DataType::Int64.can_implicitly_cast_to(&DataType::Float64));
+    assert!(!DataType::Float64.can_implicitly_cast_to(&DataType::Int32));
+    assert!(DataType::Date.can_implicitly_cast_to(&DataType::Timestamp));
+    assert!(!DataType::String.can_implicitly_cast_to(&DataType::Int32));
+    assert!(DataType::Unknown.can_implicitly_cast_to(&DataType::Bytes));
+
+    assert_eq!(DataType::Int32.common_type(&DataType::Int64), Some(DataType::Int64));
+    assert_eq!(DataType::Float32.common_type(&DataType::Int16), Some(DataType::Float32));
+    assert_eq!(
+        DataType::Timestamp.common_type(&DataType::Date),
+        Some(DataType::Timestamp)
+    );
+    assert_eq!(DataType::Unknown.common_type(&DataType::String), Some(DataType::String));
+    assert_eq!(DataType::String.common_type(&DataType::Int32), None);
+    assert_eq!(
+        DataType::Int32.common_type(&DataType::Decimal { precision: 5, scale: 2 }),
+        Some(DataType::Decimal {
+            precision: 12,
+            scale: 2
+        })
+    );
+}
+
+#[test]
+fn test_return_types() {
+    assert_eq!(ColumnVar::new(0).return_type(), DataType::Unknown);
+    assert_eq!(column(0, DataType::Date).return_type(), DataType::Date);
+    assert_eq!(Const::Int64(1).return_type(), DataType::Int64);
+    assert_eq!(Const::Null.return_type(), DataType::Unknown);
+    assert_eq!(
+        Const::Decimal(Decimal::new(12345, 2)).return_type(),
+        DataType::Decimal { precision: 5, scale: 2 }
+    );
+
+    let equal = Equal::new(column(0, DataType::Int32), Box::new(Const::Int32(1)));
+    assert_eq!(equal.return_type(), DataType::Boolean);
+    assert!(equal.is_boolean_expression());
+    assert!(!Const::Int32(1).is_boolean_expression());
+
+    // column vars are the same column whatever their types
+    assert_eq!(ColumnVar::new(3), ColumnVar::new(3).with_data_type(DataType::Int32));
+}
+
+#[test]
+fn test_type_check() {
+    let int_col = || column(0, DataType::Int32);
+    let str_col = || column(1, DataType::String);
+
+    let less_than = LessThan::new(int_col(), Box::new(Const::Float64(2.5)));
+    assert_eq!(
+        comparison_type(less_than.left(), less_than.right()),
+        Ok(DataType::Float64)
+    );
+    assert_eq!(type_check(&less_than), Ok(DataType::Boolean));
+    assert!(type_check(&Equal::new(str_col(), Box::new(Const::Null))).is_ok());
+    assert!(type_check(&IsNull::new(str_col())).is_ok());
+
+    let mismatch = Equal::new(str_col(), Box::new(Const::Int32(1)));
+    let error = type_check(&mismatch).unwrap_err();
+    assert!(error.message().contains("cannot compare String with Int32"), "{error}");
+
+    // mismatches are found in nested expressions
+    let and = And::new(vec![Rc::new(less_than), Rc::new(Not::new(Box::new(mismatch)))]);
+    assert!(type_check(&and).is_err());
+}
+
+#[test]
+fn test_datum_casts() {
+    assert_eq!(Datum::I64(7).cast_to(&DataType::Int16), Some(Datum::I16(7)));
+    assert_eq!(Datum::I64(1 << 40).cast_to(&DataType::Int32), None);
+    assert_eq!(Datum::F64(-2.5).cast_to(&DataType::Int32), Some(Datum::I32(-3)));
+    assert_eq!(Datum::F64(f64::NAN).cast_to(&DataType::Int64), None);
+    assert_eq!(Datum::I32(3).cast_to(&DataType::Float64), Some(Datum::F64(3.0)));
+
+    let decimal = DataType::Decimal { precision: 5, scale: 1 };
+    assert_eq!(
+        Datum::Decimal(Decimal::new(12345, 3)).cast_to(&decimal),
+        Some(Datum::Decimal(Decimal::new(123, 1)))
+    );
+    assert_eq!(
+        Datum::F64(1.25).cast_to(&decimal),
+        Some(Datum::Decimal(Decimal::new(13, 1)))
+    );
+    assert_eq!(Datum::I32(100_000).cast_to(&decimal), None);
+    assert_eq!(
+        Datum::Decimal(Decimal::new(-15, 1)).cast_to(&DataType::Int64),
+        Some(Datum::I64(-2))
+    );
+
+    assert_eq!(
+        Datum::Date(1).cast_to(&DataType::Timestamp),
+        Some(Datum::Timestamp(86_400_000_000))
+    );
+    assert_eq!(Datum::Timestamp(-1).cast_to(&DataType::Date), Some(Datum::Date(-1)));
+    assert_eq!(
+        Datum::Timestamp(-1).cast_to(&DataType::Time),
+        Some(Datum::Time(86_399_999_999))
+    );
+    assert_eq!(Datum::String("1".to_string()).cast_to(&DataType::Int32), None);
+    assert_eq!(Datum::Null.cast_to(&DataType::Date), Some(Datum::Null));
+}
+
+#[test]
+fn test_column_metadata_type() {
+    let column_md = ColumnMetadata::new("c1".to_string(), 1, true, 0, Datum::Null).with_data_type(DataType::Int64);
+    assert_eq!(column_md.data_type(), DataType::Int64);
+    assert_eq!(column_md.width(), 8);
+
+    let column_md = ColumnMetadata::new("c2".to_string(), 2, true, 32, Datum::Null).with_data_type(DataType::String);
+    assert_eq!(column_md.width(), 32);
+
+    // metadata without types is still readable
+    let json = r#"{"name":"c3","attno":3,"nullable":false,"width":4,"default":{"I32":0}}"#;
+    let column_md: ColumnMetadata = serde_json::from_str(json).unwrap();
+    assert_eq!(column_md.data_type(), DataType::Unknown);
+}
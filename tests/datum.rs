@@ -0,0 +1,153 @@
+use cso_demo::datum::{Datum, Decimal};
+use cso_demo::expression::{CmpOp, Const};
+use cso_demo::statistics::ColumnStatsBuilder;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn hash(datum: &Datum) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    datum.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_datum_ordering() {
+    let mut datums = vec![
+        Datum::Timestamp(0),
+        Datum::String("b".to_string()),
+        Datum::F64(f64::NAN),
+        Datum::I64(-3),
+        Datum::Decimal(Decimal::new(15, 1)),
+        Datum::Bytes(vec![0]),
+        Datum::Bool(true),
+        Datum::Date(1),
+        Datum::String("a".to_string()),
+        Datum::F32(1.0),
+        Datum::I16(1),
+        Datum::Time(5),
+        Datum::Null,
+        Datum::F64(f64::NEG_INFINITY),
+    ];
+    datums.sort();
+    assert_eq!(
+        datums,
+        vec![
+            Datum::Null,
+            Datum::Bool(true),
+            Datum::F64(f64::NEG_INFINITY),
+            Datum::I64(-3),
+            Datum::I16(1),
+            Datum::F32(1.0),
+            Datum::Decimal(Decimal::new(15, 1)),
+            Datum::F64(f64::NAN),
+            Datum::String("a".to_string()),
+            Datum::String("b".to_string()),
+            Datum::Bytes(vec![0]),
+            Datum::Date(1),
+            Datum::Time(5),
+            Datum::Timestamp(0),
+        ]
+    );
+}
+
+#[test]
+fn test_numeric_datums() {
+    // exact numbers are equal by value
+    assert_eq!(Datum::I16(7), Datum::I64(7));
+    assert_eq!(Datum::I32(2), Datum::Decimal(Decimal::new(200, 2)));
+    assert_eq!(hash(&Datum::I32(2)), hash(&Datum::Decimal(Decimal::new(200, 2))));
+    assert_eq!(hash(&Datum::I16(7)), hash(&Datum::I64(7)));
+    assert!(Datum::Decimal(Decimal::new(-15, 1)) < Datum::I32(-1));
+    assert!(Datum::Decimal(Decimal::new(-12, 1)) > Datum::Decimal(Decimal::new(-125, 2)));
+
+    // large integers rounded to the same f64 are still ordered
+    assert!(Datum::I64((1 << 53) + 1) > Datum::I64(1 << 53));
+
+    // floats are reflexive and equal to the exact numbers of the same value
+    assert_eq!(Datum::F64(f64::NAN), Datum::F64(f64::NAN));
+    assert_eq!(Datum::F32(0.5), Datum::F64(0.5));
+    assert_eq!(hash(&Datum::F32(0.5)), hash(&Datum::F64(0.5)));
+    assert_eq!(Datum::I32(1), Datum::F64(1.0));
+    assert_eq!(hash(&Datum::I32(1)), hash(&Datum::F64(1.0)));
+    assert_eq!(Datum::F64(-0.0), Datum::F64(0.0));
+    assert_eq!(hash(&Datum::F64(-0.0)), hash(&Datum::I16(0)));
+    assert_eq!(Datum::F32(-2.5), Datum::Decimal(Decimal::new(-250, 2)));
+    assert_eq!(hash(&Datum::F32(-2.5)), hash(&Datum::Decimal(Decimal::new(-250, 2))));
+    assert_eq!(
+        Datum::F64(1e20),
+        Datum::Decimal(Decimal::new(100_000_000_000_000_000_000, 0))
+    );
+    assert!(Datum::F64(1.0) < Datum::I32(2));
+    assert!(Datum::F64(0.1) != Datum::Decimal(Decimal::new(1, 1)));
+    assert!(Datum::I64(i64::MAX) < Datum::F64(f64::INFINITY));
+
+    assert_eq!(Datum::Decimal(Decimal::new(25, 1)).to_f64(), Some(2.5));
+    assert_eq!(Datum::String("a".to_string()).to_f64(), None);
+}
+
+#[test]
+fn test_datum_serde() {
+    let datums = vec![
+        Datum::Null,
+        Datum::Bool(false),
+        Datum::I16(-1),
+        Datum::I32(1),
+        Datum::I64(i64::MAX),
+        Datum::F32(0.25),
+        Datum::F64(-2.5),
+        Datum::Decimal(Decimal::new(-123_456_789_012_345_678_901_234, 20)),
+        Datum::String("abc".to_string()),
+        Datum::Bytes(vec![1, 2, 3]),
+        Datum::Date(19000),
+        Datum::Time(3_600_000_000),
+        Datum::Timestamp(1_700_000_000_000_000),
+    ];
+    let json = serde_json::to_string(&datums).unwrap();
+    let deserialized: Vec<Datum> = serde_json::from_str(&json).unwrap();
+    assert_eq!(datums, deserialized);
+}
+
+#[test]
+fn test_const_conversion() {
+    let consts = vec![
+        Const::Null,
+        Const::Bool(true),
+        Const::Int16(1),
+        Const::Int32(1),
+        Const::Int64(1),
+        Const::Float64(1.5),
+        Const::Decimal(Decimal::new(15, 1)),
+        Const::Str("x".to_string()),
+        Const::Bytes(vec![7]),
+        Const::Date(1),
+        Const::Timestamp(2),
+    ];
+    for constant in consts {
+        let datum = Datum::from(&constant);
+        assert_eq!(Const::from(&datum), constant);
+    }
+
+    // constants of different types differ even if their datums are equal
+    assert_eq!(Datum::from(&Const::Int32(1)), Datum::from(&Const::Int64(1)));
+    assert_ne!(Const::Int32(1), Const::Int64(1));
+    assert_eq!(Const::Float64(f64::NAN), Const::Float64(f64::NAN));
+}
+
+#[test]
+fn test_string_histogram() {
+    let mut builder = ColumnStatsBuilder::new(0, "name".to_string()).with_bucket_count(2);
+    for name in ["ann", "bob", "carl", "dave", "eve", "fred"] {
+        builder.add(Some(Datum::String(name.to_string())));
+    }
+    builder.add(Some(Datum::Null));
+
+    let col_stats = builder.build();
+    assert_eq!(col_stats.null_count(), 1);
+    assert_eq!(col_stats.min(), &Datum::String("ann".to_string()));
+    assert_eq!(col_stats.max(), &Datum::String("fred".to_string()));
+
+    let histogram = col_stats.histogram().as_ref().unwrap();
+    assert_eq!(histogram.buckets().len(), 2);
+    let selectivity = histogram.selectivity(CmpOp::LessThan, &Datum::String("d".to_string()));
+    assert!((selectivity - 0.5).abs() < 1e-9);
+}
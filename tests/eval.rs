@@ -0,0 +1,175 @@
+use cso_core::datatype::DataType;
+use cso_demo::datum::{Datum, Decimal};
+use cso_demo::eval::{evaluate, evaluate_predicate, Row};
+use cso_demo::expression::{
+    And, ColumnVar, Const, Equal, GreaterThan, GreaterThanEqual, IsNotNull, IsNull, LessThan, Not, Or, ScalarExpression,
+};
+use cso_demo::fold::{fold_constants, fold_predicate};
+use cso_demo::operator::logical_filter::LogicalFilter;
+use std::rc::Rc;
+
+fn column(id: u32) -> Box<dyn ScalarExpression> {
+    Box::new(ColumnVar::new(id))
+}
+
+fn int(val: i32) -> Box<dyn ScalarExpression> {
+    Box::new(Const::Int32(val))
+}
+
+fn null() -> Box<dyn ScalarExpression> {
+    Box::new(Const::Null)
+}
+
+fn row(values: &[(u32, Datum)]) -> Row {
+    values.iter().cloned().collect()
+}
+
+#[test]
+fn test_three_valued_logic() {
+    let row = row(&[(0, Datum::I32(1)), (1, Datum::Null)]);
+    let c0_eq_1 = || -> Box<dyn ScalarExpression> { Box::new(Equal::new(column(0), int(1))) };
+    let c1_eq_1 = || -> Box<dyn ScalarExpression> { Box::new(Equal::new(column(1), int(1))) };
+    let c0_lt_0 = || -> Box<dyn ScalarExpression> { Box::new(LessThan::new(column(0), int(0))) };
+
+    assert_eq!(evaluate(c0_eq_1().as_ref(), &row), Some(Datum::Bool(true)));
+    assert_eq!(evaluate(c1_eq_1().as_ref(), &row), Some(Datum::Null));
+    assert_eq!(evaluate(&IsNull::new(column(1)), &row), Some(Datum::Bool(true)));
+    assert_eq!(evaluate(&IsNotNull::new(column(0)), &row), Some(Datum::Bool(true)));
+    assert_eq!(evaluate(&Not::new(c1_eq_1()), &row), Some(Datum::Null));
+
+    // false AND NULL is false, true AND NULL is NULL
+    let and = And::new(vec![Rc::from(c0_lt_0()), Rc::from(c1_eq_1())]);
+    assert_eq!(evaluate(&and, &row), Some(Datum::Bool(false)));
+    let and = And::new(vec![Rc::from(c0_eq_1()), Rc::from(c1_eq_1())]);
+    assert_eq!(evaluate(&and, &row), Some(Datum::Null));
+    assert_eq!(evaluate_predicate(&and, &row), Some(false));
+
+    // true OR NULL is true, false OR NULL is NULL
+    let or = Or::new(vec![c0_eq_1(), c1_eq_1()]);
+    assert_eq!(evaluate(&or, &row), Some(Datum::Bool(true)));
+    let or = Or::new(vec![c0_lt_0(), c1_eq_1()]);
+    assert_eq!(evaluate(&or, &row), Some(Datum::Null));
+
+    // a missing column or incomparable values cannot be evaluated, unless the result does not depend on them
+    assert_eq!(evaluate(&Equal::new(column(2), int(1)), &row), None);
+    let mismatch = Equal::new(column(0), Box::new(Const::Str("a".to_string())));
+    assert_eq!(evaluate(&mismatch, &row), None);
+    let and = And::new(vec![Rc::new(Equal::new(column(2), int(1))), Rc::from(c0_lt_0())]);
+    assert_eq!(evaluate(&and, &row), Some(Datum::Bool(false)));
+}
+
+#[test]
+fn test_mixed_type_comparisons() {
+    let decimal = |mantissa: i128, scale: u8| -> Box<dyn ScalarExpression> {
+        Box::new(Const::Decimal(Decimal::new(mantissa, scale)))
+    };
+    let float = |val: f64| -> Box<dyn ScalarExpression> { Box::new(Const::Float64(val)) };
+    let eval = |expr: &dyn ScalarExpression| evaluate(expr, &Row::new());
+
+    // the operands are compared in their common type
+    assert_eq!(eval(&Equal::new(int(1), decimal(10, 1))), Some(Datum::Bool(true)));
+    assert_eq!(eval(&Equal::new(int(1), float(1.0))), Some(Datum::Bool(true)));
+    assert_eq!(eval(&Equal::new(decimal(1, 1), float(0.1))), Some(Datum::Bool(true)));
+    assert_eq!(eval(&LessThan::new(int(2), decimal(25, 1))), Some(Datum::Bool(true)));
+    let date_eq_timestamp = Equal::new(Box::new(Const::Date(1)), Box::new(Const::Timestamp(86_400_000_000)));
+    assert_eq!(eval(&date_eq_timestamp), Some(Datum::Bool(true)));
+
+    // c1 = 1 AND c1 = 1.0 does not contradict, c1 = 1 AND c1 >= 1.5 does
+    let int_column = || -> Box<dyn ScalarExpression> { Box::new(ColumnVar::new(1).with_data_type(DataType::Int32)) };
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(And::new(vec![
+        Rc::new(Equal::new(int_column(), int(1))),
+        Rc::new(Equal::new(int_column(), decimal(10, 1))),
+    ]));
+    assert!(Rc::ptr_eq(&fold_predicate(&predicate), &predicate));
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(And::new(vec![
+        Rc::new(Equal::new(int_column(), int(1))),
+        Rc::new(GreaterThanEqual::new(int_column(), decimal(15, 1))),
+    ]));
+    assert!(fold_predicate(&predicate).equal(&Const::Bool(false)));
+
+    // a float column compares with 0.1 as a float
+    let float_column =
+        || -> Box<dyn ScalarExpression> { Box::new(ColumnVar::new(2).with_data_type(DataType::Float64)) };
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(And::new(vec![
+        Rc::new(Equal::new(float_column(), float(0.1))),
+        Rc::new(Equal::new(float_column(), decimal(1, 1))),
+    ]));
+    assert!(Rc::ptr_eq(&fold_predicate(&predicate), &predicate));
+}
+
+#[test]
+fn test_constant_folding() {
+    let c1_is_null = || -> Rc<dyn ScalarExpression> { Rc::new(IsNull::new(column(1))) };
+    let one_eq_one = || -> Rc<dyn ScalarExpression> { Rc::new(Equal::new(int(1), int(1))) };
+
+    // 1 = 1 AND c1 IS NULL
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(And::new(vec![one_eq_one(), c1_is_null()]));
+    let folded = fold_predicate(&predicate);
+    assert!(folded.equal(c1_is_null().as_ref()));
+
+    // c1 < 5 AND c1 > 10
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(And::new(vec![
+        Rc::new(LessThan::new(column(1), int(5))),
+        Rc::new(GreaterThan::new(column(1), int(10))),
+    ]));
+    assert!(fold_predicate(&predicate).equal(&Const::Bool(false)));
+
+    // c1 = 1 AND (c1 = 2 OR c1 = 3), c1 IS NULL AND c1 > 0
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(And::new(vec![
+        Rc::new(Equal::new(column(1), int(1))),
+        Rc::new(Or::new(vec![
+            Box::new(Equal::new(column(1), int(2))),
+            Box::new(Equal::new(column(1), int(3))),
+        ])),
+    ]));
+    assert!(fold_predicate(&predicate).equal(&Const::Bool(false)));
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(And::new(vec![
+        c1_is_null(),
+        Rc::new(GreaterThan::new(column(1), int(0))),
+    ]));
+    assert!(fold_predicate(&predicate).equal(&Const::Bool(false)));
+
+    // c1 < 5 AND c1 > 1 is kept, so is the same predicate
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(And::new(vec![
+        Rc::new(LessThan::new(column(1), int(5))),
+        Rc::new(GreaterThan::new(column(1), int(1))),
+    ]));
+    assert!(Rc::ptr_eq(&fold_predicate(&predicate), &predicate));
+
+    // a filter rejects NULL: c1 = NULL OR c1 IS NULL is c1 IS NULL
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(Or::new(vec![
+        Box::new(Equal::new(column(1), null())),
+        Box::new(IsNull::new(column(1))),
+    ]));
+    assert!(fold_predicate(&predicate).equal(c1_is_null().as_ref()));
+
+    // but NOT (c1 = NULL) is not NOT false
+    let not = Not::new(Box::new(Equal::new(column(1), null())));
+    assert!(fold_predicate(&(Rc::new(not.clone()) as Rc<dyn ScalarExpression>)).equal(&Const::Bool(false)));
+    let not_or = Or::new(vec![Box::new(not.clone()), Box::new(IsNull::new(column(1)))]);
+    let folded = fold_constants(&Not::new(Box::new(not_or)));
+    assert!(folded.downcast_ref::<Not>().is_some());
+
+    // NOT NOT c1 IS NULL, NOT (1 < 0)
+    let folded = fold_constants(&Not::new(Box::new(Not::new(Box::new(IsNull::new(column(1)))))));
+    assert!(folded.equal(c1_is_null().as_ref()));
+    assert!(fold_constants(&Not::new(Box::new(LessThan::new(int(1), int(0))))).equal(&Const::Bool(true)));
+}
+
+#[test]
+fn test_filter_folds_predicate() {
+    let predicate = And::new(vec![
+        Rc::new(Equal::new(int(1), int(1))),
+        Rc::new(IsNull::new(column(1))),
+        Rc::new(IsNull::new(column(1))),
+    ]);
+    let filter = LogicalFilter::new(Rc::new(predicate));
+    assert!(filter.predicate().equal(&IsNull::new(column(1))));
+
+    let predicate = Or::new(vec![
+        Box::new(Equal::new(int(1), int(2))),
+        Box::new(LessThan::new(int(1), int(0))),
+    ]);
+    let filter = LogicalFilter::new(Rc::new(predicate));
+    assert!(filter.predicate().equal(&Const::Bool(false)));
+}
@@ -0,0 +1,122 @@
+use cso_demo::expression::{
+    referenced_columns, substitute_columns, And, ColumnVar, Const, Equal, ExpressionVisitor, IsNotNull, IsNull,
+    LessThan, Not, Or, ScalarExpression,
+};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+fn column(id: u32) -> Box<dyn ScalarExpression> {
+    Box::new(ColumnVar::new(id))
+}
+
+fn int(val: i32) -> Box<dyn ScalarExpression> {
+    Box::new(Const::Int32(val))
+}
+
+// (c0 = 1 OR NOT c1 IS NULL) AND c2 < c0
+fn predicate() -> Box<dyn ScalarExpression> {
+    let or = Or::new(vec![
+        Box::new(Equal::new(column(0), int(1))),
+        Box::new(Not::new(Box::new(IsNull::new(column(1))))),
+    ]);
+    Box::new(And::new(vec![
+        Rc::new(or),
+        Rc::new(LessThan::new(column(2), column(0))),
+    ]))
+}
+
+#[test]
+fn test_children() {
+    let predicate = predicate();
+    let children = predicate.children();
+    assert_eq!(children.len(), 2);
+    assert!(children[1].equal(&LessThan::new(column(2), column(0))));
+    assert!(children[1].children()[0].equal(&ColumnVar::new(2)));
+    assert!(column(0).children().is_empty());
+
+    // rebuilding every node with its own children gives the same tree
+    let rebuilt = predicate.transform_up(&mut |node| node);
+    assert!(rebuilt.equal(predicate.as_ref()));
+
+    let less_than = children[1].with_children(vec![int(3), column(4)]);
+    assert!(less_than.equal(&LessThan::new(int(3), column(4))));
+}
+
+#[test]
+fn test_visitor() {
+    #[derive(Default)]
+    struct Depth {
+        depth: usize,
+        max_depth: usize,
+        nodes: usize,
+    }
+
+    impl ExpressionVisitor for Depth {
+        fn pre_visit(&mut self, _expr: &dyn ScalarExpression) -> bool {
+            self.nodes += 1;
+            self.depth += 1;
+            self.max_depth = self.max_depth.max(self.depth);
+            true
+        }
+
+        fn post_visit(&mut self, _expr: &dyn ScalarExpression) {
+            self.depth -= 1;
+        }
+    }
+
+    let mut visitor = Depth::default();
+    predicate().accept(&mut visitor);
+    assert_eq!(visitor.nodes, 11);
+    assert_eq!(visitor.max_depth, 5);
+    assert_eq!(visitor.depth, 0);
+
+    let predicate = predicate();
+    let constants = predicate.collect(&|node| node.downcast_ref::<Const>().is_some());
+    assert_eq!(constants.len(), 1);
+    let columns = referenced_columns(predicate.as_ref());
+    assert_eq!(columns, vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)]);
+}
+
+#[test]
+fn test_transform() {
+    // bottom-up: NOT x IS NULL becomes x IS NOT NULL
+    let rewritten = predicate().transform_up(&mut |node| {
+        let inner = node
+            .downcast_ref::<Not>()
+            .and_then(|not| not.expression().downcast_ref::<IsNull>());
+        match inner {
+            Some(is_null) => Box::new(IsNotNull::new(is_null.inner().clone_box())),
+            None => node,
+        }
+    });
+    let is_not_null = rewritten.collect(&|node| node.downcast_ref::<IsNotNull>().is_some());
+    assert_eq!(is_not_null.len(), 1);
+    assert!(rewritten
+        .collect(&|node| node.downcast_ref::<Not>().is_some())
+        .is_empty());
+
+    // top-down: the replaced disjunction is not visited again
+    let mut visited_or = 0;
+    let rewritten = predicate().transform_down(&mut |node| {
+        if node.downcast_ref::<Or>().is_some() {
+            visited_or += 1;
+            return Some(Box::new(IsNull::new(column(5))));
+        }
+        None
+    });
+    assert_eq!(visited_or, 1);
+    assert!(rewritten.children()[0].equal(&IsNull::new(column(5))));
+
+    // substituted columns are not substituted again
+    let mapping: HashMap<u32, Box<dyn ScalarExpression>> = HashMap::from([(
+        0,
+        Box::new(Not::new(Box::new(IsNull::new(column(0))))) as Box<dyn ScalarExpression>,
+    )]);
+    let less_than = LessThan::new(column(0), column(2));
+    let rewritten = substitute_columns(&IsNull::new(Box::new(less_than)), &mapping);
+    let expected = IsNull::new(Box::new(LessThan::new(
+        Box::new(Not::new(Box::new(IsNull::new(column(0))))),
+        column(2),
+    )));
+    assert!(rewritten.equal(&expected));
+}
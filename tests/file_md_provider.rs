@@ -0,0 +1,144 @@
+use cso_core::metadata::MdProvider;
+use cso_demo::datum::Datum;
+use cso_demo::metadata::{FileMdProvider, MdId, MdValidator, Metadata};
+use cso_demo::statistics::{ColumnMetadata, RelationMetadata, RelationStats};
+use serde_json::json;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// Creates an empty directory for the metadata files of a test.
+fn md_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cso-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn md_object(mdid: MdId, metadata: Box<dyn Metadata>) -> serde_json::Value {
+    json!({ "mdid": mdid, "metadata": metadata })
+}
+
+fn relation_stats(rows: u64) -> Box<dyn Metadata> {
+    Box::new(RelationStats::new("t1".to_string(), rows, false, vec![]))
+}
+
+fn relation_md() -> Box<dyn Metadata> {
+    let column_md = vec![ColumnMetadata::new("c1".to_string(), 1, true, 4, Datum::I32(0))];
+    Box::new(RelationMetadata::new(
+        "t1".to_string(),
+        column_md,
+        MdId::relation_stats(1),
+        vec![],
+    ))
+}
+
+fn rows(provider: &FileMdProvider, mdid: MdId) -> u64 {
+    let md = provider.retrieve_metadata(&mdid).unwrap();
+    md.downcast_ref::<RelationStats>().unwrap().rows()
+}
+
+#[test]
+fn test_load_md_files() {
+    let dir = md_dir("load");
+    // a metadata object per file and a catalog file, other files are ignored
+    fs::write(
+        dir.join("1.json"),
+        md_object(MdId::relation_stats(1), relation_stats(100)).to_string(),
+    )
+    .unwrap();
+    let catalog = json!([
+        md_object(MdId::relation(2), relation_md()),
+        md_object(MdId::relation_stats(3), relation_stats(7))
+    ]);
+    fs::write(dir.join("catalog.json"), catalog.to_string()).unwrap();
+    fs::write(dir.join("README.md"), "not metadata").unwrap();
+
+    let provider = FileMdProvider::new(&dir).unwrap();
+    assert_eq!(rows(&provider, MdId::relation_stats(1)), 100);
+    assert_eq!(rows(&provider, MdId::relation_stats(3)), 7);
+    let relation_md = provider.retrieve_metadata(&MdId::relation(2)).unwrap();
+    assert_eq!(
+        relation_md.downcast_ref::<RelationMetadata>().unwrap().rel_stats_mdid(),
+        MdId::relation_stats(1)
+    );
+    assert!(provider.retrieve_metadata(&MdId::relation(4)).is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_load_invalid_md_files() {
+    // a metadata id defined twice
+    let dir = md_dir("duplicate");
+    fs::write(
+        dir.join("1.json"),
+        md_object(MdId::relation_stats(1), relation_stats(100)).to_string(),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("catalog.json"),
+        json!([md_object(MdId::relation_stats(1), relation_stats(7))]).to_string(),
+    )
+    .unwrap();
+    let err = FileMdProvider::new(&dir).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("duplicate metadata id"), "{}", err);
+
+    // unknown metadata type
+    fs::write(
+        dir.join("catalog.json"),
+        json!({ "mdid": MdId::relation(2), "metadata": { "type": "Unknown" } }).to_string(),
+    )
+    .unwrap();
+    let err = FileMdProvider::new(&dir).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("catalog.json"), "{}", err);
+
+    // metadata rejected by the validator
+    fs::remove_file(dir.join("catalog.json")).unwrap();
+    let validator: MdValidator = Box::new(|md_cache| match md_cache.get(&MdId::relation(2)) {
+        Some(_) => Ok(()),
+        None => Err("missing relation metadata 2".to_string()),
+    });
+    let err = FileMdProvider::with_validator(&dir, validator).err().unwrap();
+    assert!(err.to_string().contains("missing relation metadata 2"), "{}", err);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_reload_md_files() {
+    let dir = md_dir("reload");
+    fs::write(
+        dir.join("1.json"),
+        md_object(MdId::relation_stats(1), relation_stats(100)).to_string(),
+    )
+    .unwrap();
+    let provider = FileMdProvider::new(&dir).unwrap();
+    assert!(!provider.reload_if_changed().unwrap());
+
+    // modified and added files
+    fs::write(
+        dir.join("1.json"),
+        md_object(MdId::relation_stats(1), relation_stats(100_000)).to_string(),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("2.json"),
+        md_object(MdId::relation(2), relation_md()).to_string(),
+    )
+    .unwrap();
+    assert!(provider.reload_if_changed().unwrap());
+    assert_eq!(rows(&provider, MdId::relation_stats(1)), 100_000);
+    assert!(provider.retrieve_metadata(&MdId::relation(2)).is_some());
+
+    // the loaded metadata is kept if the files fail to load
+    fs::write(dir.join("2.json"), "{").unwrap();
+    assert!(provider.reload_if_changed().is_err());
+    assert!(provider.retrieve_metadata(&MdId::relation(2)).is_some());
+
+    // removed files
+    fs::remove_file(dir.join("2.json")).unwrap();
+    assert!(provider.reload_if_changed().unwrap());
+    assert!(provider.retrieve_metadata(&MdId::relation(2)).is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}
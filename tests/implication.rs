@@ -0,0 +1,89 @@
+use cso_core::datatype::DataType;
+use cso_demo::datum::Decimal;
+use cso_demo::expression::{
+    And, ColumnVar, Const, Equal, FunctionRegistry, GreaterThan, GreaterThanEqual, IsNotNull, IsNull, LessThan,
+    LessThanEqual, Not, Or, ScalarExpression,
+};
+use cso_demo::implication::implies;
+use std::rc::Rc;
+
+fn column(id: u32) -> Box<dyn ScalarExpression> {
+    Box::new(ColumnVar::new(id))
+}
+
+fn int(value: i32) -> Box<dyn ScalarExpression> {
+    Box::new(Const::Int32(value))
+}
+
+#[test]
+fn test_implies_comparisons() {
+    // c1 = 5 AND c2 IS NULL
+    let premise = And::new(vec![
+        Rc::new(Equal::new(column(1), int(5))),
+        Rc::new(IsNull::new(column(2))),
+    ]);
+    assert!(implies(&premise, &GreaterThan::new(column(1), int(0))));
+    assert!(implies(&premise, &LessThanEqual::new(column(1), int(5))));
+    assert!(implies(&premise, &IsNotNull::new(column(1))));
+    assert!(implies(&premise, &IsNull::new(column(2))));
+    assert!(!implies(&premise, &GreaterThan::new(column(1), int(5))));
+    assert!(!implies(&premise, &GreaterThan::new(column(3), int(0))));
+
+    // every conjunct of the conclusion, a disjunction by any of its operands
+    let conclusion = And::new(vec![
+        Rc::new(GreaterThanEqual::new(column(1), int(0))),
+        Rc::new(Or::new(vec![
+            Box::new(LessThan::new(column(3), int(0))),
+            Box::new(GreaterThanEqual::new(column(1), int(5))),
+        ])),
+    ]);
+    assert!(implies(&premise, &conclusion));
+
+    // the predicates are normalized, NOT (c1 <= 10) is c1 > 10
+    let premise = Not::new(Box::new(LessThanEqual::new(column(1), int(10))));
+    assert!(implies(&premise, &GreaterThan::new(column(1), int(5))));
+    assert!(!implies(&GreaterThan::new(column(1), int(5)), &premise));
+}
+
+#[test]
+fn test_implies_null() {
+    // c1 > 0 is NULL, not true, for the NULL values of c1
+    let premise = IsNull::new(column(1));
+    assert!(!implies(&premise, &GreaterThan::new(column(1), int(0))));
+    assert!(!implies(&premise, &LessThanEqual::new(column(1), int(0))));
+    assert!(!implies(&IsNotNull::new(column(1)), &premise));
+
+    // an expression implies itself
+    let registry = FunctionRegistry::with_builtins();
+    let lower = || Box::new(registry.call("lower", vec![column(2)]).unwrap());
+    let equal = Equal::new(lower(), Box::new(Const::Str("abc".to_string())));
+    assert!(implies(&equal, &equal));
+    assert!(!implies(
+        &equal,
+        &Equal::new(lower(), Box::new(Const::Str("def".to_string())))
+    ));
+}
+
+#[test]
+fn test_implies_mixed_numeric_literals() {
+    let decimal = |mantissa: i128, scale: u8| -> Box<dyn ScalarExpression> {
+        Box::new(Const::Decimal(Decimal::new(mantissa, scale)))
+    };
+
+    // c1 = 5 implies c1 = 5.0 and c1 >= 5.00, not c1 > 5.0
+    let premise = Equal::new(column(1), int(5));
+    assert!(implies(&premise, &Equal::new(column(1), decimal(50, 1))));
+    assert!(implies(&premise, &GreaterThanEqual::new(column(1), decimal(500, 2))));
+    assert!(!implies(&premise, &GreaterThan::new(column(1), decimal(50, 1))));
+
+    // c1 <= 2.5 implies c1 < 3, an integer c1 > 4.5 implies c1 > 4
+    assert!(implies(
+        &LessThanEqual::new(column(1), decimal(25, 1)),
+        &LessThan::new(column(1), int(3))
+    ));
+    let int_column = || -> Box<dyn ScalarExpression> { Box::new(ColumnVar::new(1).with_data_type(DataType::Int32)) };
+    assert!(implies(
+        &GreaterThan::new(int_column(), decimal(45, 1)),
+        &GreaterThan::new(int_column(), int(4))
+    ));
+}
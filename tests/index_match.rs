@@ -0,0 +1,212 @@
+use cso_core::operator::PhysicalOperator;
+use cso_demo::expression::{
+    ColumnVar, Const, Equal, FunctionRegistry, GreaterThan, GreaterThanEqual, IsNull, LessThan, LessThanEqual, Param,
+    ScalarExpression,
+};
+use cso_demo::index_match::{match_index, KeyBound};
+use cso_demo::metadata::MdId;
+use cso_demo::operator::logical_index_scan::IndexDesc;
+use cso_demo::operator::logical_scan::TableDesc;
+use cso_demo::operator::physical_index_scan::PhysicalIndexScan;
+use cso_demo::property::PhysicalProperties;
+use cso_demo::statistics::{IndexKeyOrder, IndexMd, IndexType};
+use std::rc::Rc;
+
+fn column(id: u32) -> Box<dyn ScalarExpression> {
+    Box::new(ColumnVar::new(id))
+}
+
+fn int(value: i32) -> Rc<dyn ScalarExpression> {
+    Rc::new(Const::Int32(value))
+}
+
+/// The column vars of t1(c0, c1, c2, c3).
+fn columns() -> Vec<ColumnVar> {
+    (0..4).map(ColumnVar::new).collect()
+}
+
+/// An index of t1(c0, c1, c2, c3) with the key columns `keys` that includes c0, c1 and c2.
+fn index_desc(index_type: IndexType, keys: Vec<u32>) -> IndexDesc {
+    let keys = keys.into_iter().map(ColumnVar::new).collect();
+    let included = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
+    IndexDesc::new(MdId::index(4), "IDX_1".to_string(), index_type, keys, included)
+}
+
+#[test]
+fn test_match_key_prefix() {
+    // c0 = 1 AND c1 > 5 AND c1 < 9 AND c1 <= 10 AND c3 = 2
+    let conjuncts: Vec<Rc<dyn ScalarExpression>> = vec![
+        Rc::new(Equal::new(column(0), Box::new(Const::Int32(1)))),
+        Rc::new(GreaterThan::new(column(1), Box::new(Const::Int32(5)))),
+        Rc::new(LessThan::new(column(1), Box::new(Const::Int32(9)))),
+        Rc::new(LessThanEqual::new(column(1), Box::new(Const::Int32(10)))),
+        Rc::new(Equal::new(column(3), Box::new(Const::Int32(2)))),
+    ];
+    let index_match = match_index(&index_desc(IndexType::Btree, vec![0, 1]), &conjuncts);
+    assert_eq!(index_match.conditions(), &conjuncts[..3]);
+    assert_eq!(index_match.filters(), &conjuncts[3..4]);
+    assert_eq!(index_match.residual(), &conjuncts[4..]);
+
+    let range = index_match.range();
+    assert_eq!(range.start(), Some(&KeyBound::new(vec![int(1), int(5)], false)));
+    assert_eq!(range.stop(), Some(&KeyBound::new(vec![int(1), int(9)], false)));
+}
+
+#[test]
+fn test_match_key_prefix_gap() {
+    // without an equality on c0, the comparisons of c1 do not bound the scanned keys
+    let conjuncts: Vec<Rc<dyn ScalarExpression>> = vec![
+        Rc::new(Equal::new(column(1), Box::new(Const::Int32(1)))),
+        Rc::new(IsNull::new(column(2))),
+    ];
+    let index_match = match_index(&index_desc(IndexType::Btree, vec![0, 1, 2]), &conjuncts);
+    assert!(index_match.conditions().is_empty());
+    assert_eq!(index_match.filters(), &conjuncts[..]);
+    assert!(index_match.range().is_full());
+
+    // a NULL key is an equality, c0 IS NULL AND c1 >= $1 is a prefix of c0 and c1
+    let conjuncts: Vec<Rc<dyn ScalarExpression>> = vec![
+        Rc::new(GreaterThanEqual::new(column(1), Box::new(Param::new(1)))),
+        Rc::new(IsNull::new(column(0))),
+    ];
+    let index_match = match_index(&index_desc(IndexType::Btree, vec![0, 1]), &conjuncts);
+    assert_eq!(index_match.conditions(), &[conjuncts[1].clone(), conjuncts[0].clone()]);
+    let start = KeyBound::new(vec![Rc::new(Const::Null), Rc::new(Param::new(1))], true);
+    assert_eq!(index_match.range().start(), Some(&start));
+    assert_eq!(
+        index_match.range().stop(),
+        Some(&KeyBound::new(vec![Rc::new(Const::Null)], true))
+    );
+}
+
+#[test]
+fn test_match_descending_key() {
+    let index_md =
+        IndexMd::new(MdId::index(4), "IDX_1".to_string(), vec![ColumnVar::new(0)], vec![]).with_key_orders(vec![
+            IndexKeyOrder {
+                ascending: false,
+                nulls_first: false,
+            },
+        ]);
+    let conjuncts: Vec<Rc<dyn ScalarExpression>> = vec![
+        Rc::new(GreaterThan::new(column(0), Box::new(Const::Int32(5)))),
+        Rc::new(LessThanEqual::new(column(0), Box::new(Const::Int32(9)))),
+    ];
+    let index_match = match_index(&IndexDesc::from_index_md(&index_md, &columns()).unwrap(), &conjuncts);
+    assert_eq!(index_match.conditions().len(), 2);
+
+    // the scan starts from the greatest key
+    let range = index_match.range();
+    assert_eq!(range.start(), Some(&KeyBound::new(vec![int(9)], true)));
+    assert_eq!(range.stop(), Some(&KeyBound::new(vec![int(5)], false)));
+}
+
+#[test]
+fn test_match_unordered_index() {
+    let conjuncts: Vec<Rc<dyn ScalarExpression>> = vec![
+        Rc::new(Equal::new(column(0), Box::new(Const::Int32(1)))),
+        Rc::new(GreaterThan::new(column(1), Box::new(Const::Int32(5)))),
+    ];
+
+    // a hash index is searched by equalities on all its key columns only
+    let index_match = match_index(&index_desc(IndexType::Hash, vec![0]), &conjuncts);
+    assert_eq!(index_match.conditions(), &conjuncts[..1]);
+    assert_eq!(index_match.filters(), &conjuncts[1..]);
+    assert!(index_match.range().is_full());
+    let index_match = match_index(&index_desc(IndexType::Hash, vec![0, 1]), &conjuncts);
+    assert!(index_match.conditions().is_empty());
+    assert_eq!(index_match.filters(), &conjuncts[..]);
+
+    // a bitmap index is searched by the equality, the entries of a lossy index are not filtered
+    let index_match = match_index(&index_desc(IndexType::Bitmap, vec![0, 1]), &conjuncts);
+    assert_eq!(index_match.conditions(), &conjuncts[..1]);
+    assert_eq!(index_match.filters(), &conjuncts[1..]);
+    let index_match = match_index(&index_desc(IndexType::Brin, vec![0]), &conjuncts);
+    assert_eq!(index_match.conditions(), &conjuncts[..1]);
+    assert!(index_match.filters().is_empty());
+    assert_eq!(index_match.residual(), &conjuncts[1..]);
+}
+
+#[test]
+fn test_match_expression_key() {
+    let registry = FunctionRegistry::with_builtins();
+    let lower = || Box::new(registry.call("lower", vec![column(3)]).unwrap()) as Box<dyn ScalarExpression>;
+    let string = |value: &str| Box::new(Const::Str(value.to_string())) as Box<dyn ScalarExpression>;
+
+    // key columns(c0, lower(c3))
+    let index_md =
+        IndexMd::new(MdId::index(4), "IDX_1".to_string(), vec![], vec![]).with_keys(vec![column(0), lower()]);
+    let index_desc = IndexDesc::from_index_md(&index_md, &columns()).unwrap();
+    assert_eq!(index_desc.key_count(), 2);
+    assert_eq!(index_desc.key_orders().len(), 2);
+
+    // c0 = 1 AND 'abc' <= lower(c3) AND lower(c3) < 'abd' AND upper(c3) = 'ABC'
+    let conjuncts: Vec<Rc<dyn ScalarExpression>> = vec![
+        Rc::new(Equal::new(column(0), Box::new(Const::Int32(1)))),
+        Rc::new(LessThanEqual::new(string("abc"), lower())),
+        Rc::new(LessThan::new(lower(), string("abd"))),
+        Rc::new(Equal::new(
+            Box::new(registry.call("upper", vec![column(3)]).unwrap()),
+            string("ABC"),
+        )),
+    ];
+    let index_match = match_index(&index_desc, &conjuncts);
+    assert_eq!(index_match.conditions(), &conjuncts[..3]);
+    assert_eq!(index_match.residual(), &conjuncts[3..]);
+    let start = KeyBound::new(vec![int(1), Rc::from(string("abc"))], true);
+    let stop = KeyBound::new(vec![int(1), Rc::from(string("abd"))], false);
+    assert_eq!(index_match.range().start(), Some(&start));
+    assert_eq!(index_match.range().stop(), Some(&stop));
+
+    // a hash index on lower(c3) is searched by an equality of lower(c3)
+    let index_md = IndexMd::new(MdId::index(4), "IDX_1".to_string(), vec![], vec![])
+        .with_index_type(IndexType::Hash)
+        .with_keys(vec![lower()]);
+    let index_desc = IndexDesc::from_index_md(&index_md, &columns()).unwrap();
+    let equality: Rc<dyn ScalarExpression> = Rc::new(Equal::new(lower(), string("abc")));
+    let index_match = match_index(&index_desc, std::slice::from_ref(&equality));
+    assert_eq!(index_match.conditions(), &[equality]);
+    let index_match = match_index(&index_desc, &conjuncts[2..3]);
+    assert!(index_match.conditions().is_empty());
+    assert_eq!(index_match.residual(), &conjuncts[2..3]);
+}
+
+#[test]
+fn test_match_expression_key_first() {
+    let registry = FunctionRegistry::with_builtins();
+    let lower = || Box::new(registry.call("lower", vec![column(3)]).unwrap()) as Box<dyn ScalarExpression>;
+    let string = |value: &str| Box::new(Const::Str(value.to_string())) as Box<dyn ScalarExpression>;
+
+    // key columns(lower(c3), c0), included columns(c0)
+    let index_md = IndexMd::new(MdId::index(4), "IDX_1".to_string(), vec![], vec![ColumnVar::new(0)])
+        .with_keys(vec![lower(), column(0)]);
+    let index_desc = IndexDesc::from_index_md(&index_md, &columns()).unwrap();
+    assert_eq!(index_desc.key_columns().collect::<Vec<_>>(), vec![&ColumnVar::new(0)]);
+
+    // lower(c3) = 'abc' AND c0 > 1
+    let conjuncts: Vec<Rc<dyn ScalarExpression>> = vec![
+        Rc::new(Equal::new(lower(), string("abc"))),
+        Rc::new(GreaterThan::new(column(0), Box::new(Const::Int32(1)))),
+    ];
+    let index_match = match_index(&index_desc, &conjuncts);
+    assert_eq!(index_match.conditions(), &conjuncts[..]);
+    let start = KeyBound::new(vec![Rc::from(string("abc")), int(1)], false);
+    assert_eq!(index_match.range().start(), Some(&start));
+
+    // c0 is not a prefix of the keys, so it only filters the entries
+    let index_match = match_index(&index_desc, &conjuncts[1..]);
+    assert!(index_match.conditions().is_empty());
+    assert_eq!(index_match.filters(), &conjuncts[1..]);
+
+    // nor is the index ordered on c0
+    let index_scan = PhysicalIndexScan::new(
+        index_desc,
+        TableDesc::new(MdId::relation(2)),
+        columns(),
+        conjuncts[0].clone(),
+    );
+    assert_eq!(
+        index_scan.derive_output_properties(&[]),
+        Rc::new(PhysicalProperties::new())
+    );
+}
@@ -1,14 +1,15 @@
 use cso_demo::datum::Datum;
 use cso_demo::expression::{And, ScalarExpression};
-use cso_demo::expression::{ColumnVar, IsNull};
+use cso_demo::expression::{ColumnVar, IsNotNull, IsNull, Not, Or};
 use cso_demo::metadata::CachedMdProvider;
 use cso_demo::metadata::MdAccessor;
-use cso_demo::metadata::{MdCache, Metadata};
+use cso_demo::metadata::{MdCache, MdId, Metadata};
 use cso_demo::operator::logical_filter::LogicalFilter;
 use cso_demo::operator::logical_index_scan::IndexDesc;
 use cso_demo::operator::logical_project::LogicalProject;
 use cso_demo::operator::logical_scan::{LogicalScan, TableDesc};
 use cso_demo::operator::physical_filter::PhysicalFilter;
+use cso_demo::operator::physical_index_lookup::PhysicalIndexLookup;
 use cso_demo::operator::physical_index_scan::PhysicalIndexScan;
 use cso_demo::operator::physical_project::PhysicalProject;
 use cso_demo::operator::physical_scan::PhysicalScan;
@@ -23,7 +24,7 @@ use cso_demo::{LogicalPlan, Optimizer, Options, PhysicalPlan};
 use std::rc::Rc;
 
 fn logical_scan() -> LogicalPlan {
-    let mdid = 2;
+    let mdid = MdId::relation(2);
     let table_desc = TableDesc::new(mdid);
     let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
 
@@ -69,10 +70,10 @@ fn required_properties(id: u32) -> Rc<PhysicalProperties> {
 
 fn md_cache() -> MdCache {
     // mdids
-    let relation_stats_id = 1;
-    let relation_md_id = 2;
-    let column_stats_id = 3;
-    let index_md_id = 4;
+    let relation_stats_id = MdId::relation_stats(1);
+    let relation_md_id = MdId::relation(2);
+    let column_stats_id = MdId::column_stats(3);
+    let index_md_id = MdId::index(4);
 
     // relation stats
     let relation_stats = RelationStats::new("t1".to_string(), 9011, false, vec![column_stats_id]);
@@ -80,7 +81,7 @@ fn md_cache() -> MdCache {
 
     // index metadata
     let index_md = IndexMd::new(
-        4,
+        MdId::index(4),
         "IDX_1".to_string(),
         vec![ColumnVar::new(0)],
         vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)],
@@ -130,10 +131,10 @@ fn metadata_accessor() -> MdAccessor {
 }
 
 fn expected_physical_plan_with_index() -> PhysicalPlan {
-    let mdid = 2;
+    let mdid = MdId::relation(2);
     let table_desc = TableDesc::new(mdid);
     let index_desc = IndexDesc::new(
-        4,
+        MdId::index(4),
         "IDX_1".to_string(),
         IndexType::Btree,
         vec![ColumnVar::new(0)],
@@ -177,7 +178,7 @@ fn test_sort_project_index_scan_matched() {
 }
 
 fn expected_physical_plan_without_index() -> PhysicalPlan {
-    let mdid = 2;
+    let mdid = MdId::relation(2);
     let table_desc = TableDesc::new(mdid);
     let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
 
@@ -226,42 +227,36 @@ fn test_sort_project_index_scan_not_matched() {
 }
 
 fn expected_physical_plan_with_index_and_filter() -> PhysicalPlan {
-    let mdid = 2;
+    let mdid = MdId::relation(2);
     let table_desc = TableDesc::new(mdid);
     let index_desc = IndexDesc::new(
-        4,
+        MdId::index(4),
         "IDX_1".to_string(),
         IndexType::Btree,
         vec![ColumnVar::new(0)],
         vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)],
     );
     let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
-    let predicate = IsNull::new(Box::new(ColumnVar::new(0)));
-    let scan = PhysicalIndexScan::new(
-        index_desc,
-        table_desc,
-        output_columns,
-        Rc::new(And::new(vec![Rc::new(predicate)])),
-    );
+    // c2 IS NULL is a filter on the entries of the index
+    let predicate = And::new(vec![
+        Rc::new(IsNull::new(Box::new(ColumnVar::new(0)))),
+        Rc::new(IsNull::new(Box::new(ColumnVar::new(1)))),
+    ]);
+    let scan = PhysicalIndexScan::new(index_desc, table_desc, output_columns, Rc::new(predicate));
     let scan = PhysicalPlan::new(Rc::new(scan), vec![]);
 
-    let filter = PhysicalFilter::new(Rc::new(And::new(vec![Rc::new(IsNull::new(Box::new(ColumnVar::new(
-        1,
-    ))))])));
-    let filter = PhysicalPlan::new(Rc::new(filter), vec![scan]);
-
     let project = vec![
         Rc::new(ColumnVar::new(1)) as Rc<dyn ScalarExpression>,
         Rc::new(ColumnVar::new(2)) as Rc<dyn ScalarExpression>,
     ];
     let project = PhysicalProject::new(project);
-    PhysicalPlan::new(Rc::new(project), vec![filter])
+    PhysicalPlan::new(Rc::new(project), vec![scan])
 }
 
 // can partly cover filter
 // sql: select c2, c3 from t1 where c1 is null and c2 is null order by c1;
 // idx: key columns(c1) included columns(c1, c2, c3)
-// project(c2, c3) -> filter(c2) -> IndexScan(c1)
+// project(c2, c3) -> IndexScan(c1, filter c2)
 #[test]
 fn test_sort_project_index_scan_partly_matched() {
     let mut optimizer = Optimizer::new(Options::default());
@@ -279,10 +274,10 @@ fn test_sort_project_index_scan_partly_matched() {
 
 #[allow(dead_code)]
 fn expected_physical_plan_with_index_2() -> PhysicalPlan {
-    let mdid = 2;
+    let mdid = MdId::relation(2);
     let table_desc = TableDesc::new(mdid);
     let index_desc = IndexDesc::new(
-        4,
+        MdId::index(4),
         "IDX_1".to_string(),
         IndexType::Btree,
         vec![ColumnVar::new(0)],
@@ -339,37 +334,24 @@ fn test_sort_project_index_scan_matched_2() {
 }
 
 fn expected_physical_plan_with_index_and_filter_2() -> PhysicalPlan {
-    let mdid = 2;
+    let mdid = MdId::relation(2);
     let table_desc = TableDesc::new(mdid);
     let index_desc = IndexDesc::new(
-        4,
+        MdId::index(4),
         "IDX_1".to_string(),
         IndexType::Btree,
         vec![ColumnVar::new(0)],
         vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)],
     );
     let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
-    let predicate = IsNull::new(Box::new(ColumnVar::new(0)));
-    let scan = PhysicalIndexScan::new(
-        index_desc,
-        table_desc,
-        output_columns,
-        Rc::new(And::new(vec![Rc::new(predicate)])),
-    );
+    // c2 IS NULL is a filter on the entries of the index
+    let predicate = And::new(vec![
+        Rc::new(IsNull::new(Box::new(ColumnVar::new(0)))),
+        Rc::new(IsNull::new(Box::new(ColumnVar::new(1)))),
+    ]);
+    let scan = PhysicalIndexScan::new(index_desc, table_desc, output_columns, Rc::new(predicate));
     let scan = PhysicalPlan::new(Rc::new(scan), vec![]);
 
-    let filter = PhysicalFilter::new(Rc::new(And::new(vec![Rc::new(IsNull::new(Box::new(ColumnVar::new(
-        1,
-    ))))])));
-    let filter = PhysicalPlan::new(Rc::new(filter), vec![scan]);
-
-    let project = vec![
-        Rc::new(ColumnVar::new(1)) as Rc<dyn ScalarExpression>,
-        Rc::new(ColumnVar::new(2)) as Rc<dyn ScalarExpression>,
-    ];
-    let project = PhysicalProject::new(project);
-    let project = PhysicalPlan::new(Rc::new(project), vec![filter]);
-
     let order = OrderSpec {
         order_desc: vec![Ordering {
             key: ColumnVar::new(1),
@@ -378,13 +360,20 @@ fn expected_physical_plan_with_index_and_filter_2() -> PhysicalPlan {
         }],
     };
     let sort = PhysicalSort::new(order);
-    PhysicalPlan::new(Rc::new(sort), vec![project])
+    let sort = PhysicalPlan::new(Rc::new(sort), vec![scan]);
+
+    let project = vec![
+        Rc::new(ColumnVar::new(1)) as Rc<dyn ScalarExpression>,
+        Rc::new(ColumnVar::new(2)) as Rc<dyn ScalarExpression>,
+    ];
+    let project = PhysicalProject::new(project);
+    PhysicalPlan::new(Rc::new(project), vec![sort])
 }
 
 // can partly cover filter
 // sql: select c2, c3 from t1 where c1 is null and c2 is null order by c2;
 // idx: key columns(c1) included columns(c1, c2, c3)
-// Sort(c2) -> project(c2, c3) -> filter(c2) -> IndexScan(c1)
+// project(c2, c3) -> Sort(c2) -> IndexScan(c1, filter c2)
 #[test]
 fn test_sort_project_index_scan_partly_matched_2() {
     let mut optimizer = Optimizer::new(Options::default());
@@ -399,3 +388,343 @@ fn test_sort_project_index_scan_partly_matched_2() {
     let physical_plan = optimizer.optimize(project, required_properties, md_accessor, rule_set);
     assert_eq!(physical_plan, expected_physical_plan_with_index_and_filter_2());
 }
+
+// the index scan of test_sort_project_index_scan_partly_matched_2 returns a single row, which costs nothing to sort,
+// so sorting above or below the project costs the same and the first plan found is kept, the project passing the
+// required order down to the index scan
+#[test]
+fn test_sort_single_row_index_scan() {
+    use cso_core::operator::{LogicalOperator, PhysicalOperator};
+    use cso_demo::operator::logical_index_scan::LogicalIndexScan;
+
+    let md_accessor = metadata_accessor();
+    let index_md = md_accessor.retrieve_metadata(&MdId::index(4)).unwrap();
+    let index_md = index_md.downcast_ref::<IndexMd>().unwrap();
+    let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
+    let predicate = And::new(vec![
+        Rc::new(IsNull::new(Box::new(ColumnVar::new(0)))),
+        Rc::new(IsNull::new(Box::new(ColumnVar::new(1)))),
+    ]);
+    let index_scan = LogicalIndexScan::new(
+        TableDesc::new(MdId::relation(2)),
+        index_md,
+        output_columns,
+        Rc::new(predicate),
+    );
+    let stats = index_scan.derive_statistics(&md_accessor, &Options::default(), &[]);
+    assert_eq!(stats.output_row_count(), 1);
+
+    let sort = PhysicalSort::new(OrderSpec {
+        order_desc: vec![Ordering {
+            key: ColumnVar::new(1),
+            ascending: true,
+            nulls_first: true,
+        }],
+    });
+    assert_eq!(sort.compute_cost(Some(stats.as_ref())).value(), 0.0);
+}
+
+// the negated disjunction is normalized to a conjunction the index can partly cover
+// sql: select c2, c3 from t1 where not (c1 is not null or c2 is not null) order by c1;
+// idx: key columns(c1) included columns(c1, c2, c3)
+// Project(c2, c3) -> IndexScan(c1, filter c2)
+#[test]
+fn test_sort_project_index_scan_normalized_predicate() {
+    let mut optimizer = Optimizer::new(Options::default());
+    let rule_set = create_rule_set();
+
+    let scan = logical_scan();
+    let predicate = Not::new(Box::new(Or::new(vec![
+        Box::new(IsNotNull::new(Box::new(ColumnVar::new(0)))),
+        Box::new(IsNotNull::new(Box::new(ColumnVar::new(1)))),
+    ])));
+    let filter = LogicalPlan::new(Rc::new(LogicalFilter::new(Rc::new(predicate))), vec![scan], vec![]);
+    let project = logical_project(vec![filter]);
+    let required_properties = required_properties(0);
+    let md_accessor = metadata_accessor();
+
+    let physical_plan = optimizer.optimize(project, required_properties, md_accessor, rule_set);
+    assert_eq!(physical_plan, expected_physical_plan_with_index_and_filter());
+}
+
+#[test]
+fn test_index_scan_cost() {
+    use cso_core::operator::{LogicalOperator, PhysicalOperator};
+    use cso_demo::operator::logical_index_scan::LogicalIndexScan;
+    use cso_demo::statistics::{IndexKeyOrder, Statistics};
+
+    let md_accessor = metadata_accessor();
+    let index_md = IndexMd::new(
+        MdId::index(4),
+        "IDX_1".to_string(),
+        vec![ColumnVar::new(0)],
+        vec![ColumnVar::new(1)],
+    )
+    .with_storage(100, 3);
+    let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
+    let predicate: Rc<dyn ScalarExpression> = Rc::new(IsNull::new(Box::new(ColumnVar::new(0))));
+    let index_scan = LogicalIndexScan::new(TableDesc::new(MdId::relation(2)), &index_md, output_columns, predicate);
+    let stats = index_scan.derive_statistics(&md_accessor, &Options::default(), &[]);
+    let relation_row_count = stats
+        .as_any()
+        .downcast_ref::<Statistics>()
+        .unwrap()
+        .relation_row_count();
+    assert_eq!(relation_row_count, Some(9011));
+
+    let physical_index_scan = |index_md: &IndexMd| {
+        PhysicalIndexScan::new(
+            IndexDesc::from_index_md(index_md, index_scan.output_columns()).unwrap(),
+            index_scan.table_desc().clone(),
+            index_scan.output_columns().to_vec(),
+            index_scan.predicate().clone(),
+        )
+    };
+    let physical_index_lookup = |index_md: &IndexMd| {
+        PhysicalIndexLookup::new(
+            IndexDesc::from_index_md(index_md, index_scan.output_columns()).unwrap(),
+            index_scan.table_desc().clone(),
+            index_scan.output_columns().to_vec(),
+            index_scan.predicate().clone(),
+        )
+    };
+
+    // c3 is not in the index, so the rows are looked up in the table unless only c1 and c2 are used
+    assert!(!index_scan.index_only());
+    let index_only = physical_index_scan(&index_md);
+    let uncorrelated = physical_index_lookup(&index_md);
+    let correlated = physical_index_lookup(&index_md.clone().with_correlation(-0.9));
+    let index_only_cost = index_only.compute_cost(Some(stats.as_ref()));
+    let correlated_cost = correlated.compute_cost(Some(stats.as_ref()));
+    let uncorrelated_cost = uncorrelated.compute_cost(Some(stats.as_ref()));
+    assert!(index_only_cost < correlated_cost);
+    assert!(correlated_cost < uncorrelated_cost);
+
+    // a deeper index costs more to descend
+    let deeper = physical_index_scan(&index_md.clone().with_storage(100, 4));
+    assert!(index_only_cost < deeper.compute_cost(Some(stats.as_ref())));
+
+    // the scan delivers the order of the index
+    let descending = index_md.with_key_orders(vec![IndexKeyOrder {
+        ascending: false,
+        nulls_first: false,
+    }]);
+    let output_properties = physical_index_scan(&descending).derive_output_properties(&[]);
+    let order = OrderSpec {
+        order_desc: vec![Ordering {
+            key: ColumnVar::new(0),
+            ascending: false,
+            nulls_first: false,
+        }],
+    };
+    let expected = PhysicalProperties::with_property(Box::new(SortProperty::with_order(order)));
+    assert_eq!(output_properties, expected);
+}
+
+#[test]
+fn test_unique_index_key() {
+    use cso_core::operator::LogicalOperator;
+    use cso_demo::statistics::Statistics;
+
+    let mut md_cache = md_cache();
+    let index_md = IndexMd::new(
+        MdId::index(4),
+        "IDX_1".to_string(),
+        vec![ColumnVar::new(0)],
+        vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)],
+    );
+    md_cache.insert(MdId::index(4), Box::new(index_md.with_unique(true)));
+    let md_accessor = MdAccessor::new(Rc::new(CachedMdProvider::new(md_cache)));
+
+    let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
+    let scan = LogicalScan::new(TableDesc::new(MdId::relation(2)), output_columns);
+    let stats = scan.derive_statistics(&md_accessor, &Options::default(), &[]);
+    let stats = stats.as_any().downcast_ref::<Statistics>().unwrap();
+    assert_eq!(stats.keys(), &[vec![0]]);
+}
+
+#[test]
+fn test_index_type_capabilities() {
+    use cso_core::operator::PhysicalOperator;
+    use cso_demo::expression::{Const, Equal, LessThan};
+
+    fn uses_index_scan(plan: &PhysicalPlan) -> bool {
+        plan.operator().downcast_ref::<PhysicalIndexScan>().is_some() || plan.inputs().iter().any(uses_index_scan)
+    }
+
+    // sql: select c2, c3 from t1 where <predicate> order by c2;
+    let optimize = |index_type: IndexType, predicate: Rc<dyn ScalarExpression>| {
+        let mut md_cache = md_cache();
+        let index_md = IndexMd::new(
+            MdId::index(4),
+            "IDX_1".to_string(),
+            vec![ColumnVar::new(0)],
+            vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)],
+        );
+        md_cache.insert(MdId::index(4), Box::new(index_md.with_index_type(index_type)));
+        let md_accessor = MdAccessor::new(Rc::new(CachedMdProvider::new(md_cache)));
+
+        let filter = LogicalPlan::new(Rc::new(LogicalFilter::new(predicate)), vec![logical_scan()], vec![]);
+        let project = logical_project(vec![filter]);
+        let mut optimizer = Optimizer::new(Options::default());
+        optimizer.optimize(project, required_properties(1), md_accessor, create_rule_set())
+    };
+    let is_null = || Rc::new(IsNull::new(Box::new(ColumnVar::new(0)))) as Rc<dyn ScalarExpression>;
+    let equal =
+        || Rc::new(Equal::new(Box::new(ColumnVar::new(0)), Box::new(Const::Int32(5)))) as Rc<dyn ScalarExpression>;
+    let less_than =
+        || Rc::new(LessThan::new(Box::new(ColumnVar::new(0)), Box::new(Const::Int32(5)))) as Rc<dyn ScalarExpression>;
+
+    assert!(uses_index_scan(&optimize(IndexType::Btree, is_null())));
+    assert!(uses_index_scan(&optimize(IndexType::Btree, less_than())));
+
+    // hash indexes find equal values only
+    assert!(uses_index_scan(&optimize(IndexType::Hash, equal())));
+    assert!(!uses_index_scan(&optimize(IndexType::Hash, is_null())));
+    assert!(!uses_index_scan(&optimize(IndexType::Hash, less_than())));
+
+    // bitmap indexes find equal values and NULL values
+    assert!(uses_index_scan(&optimize(IndexType::Bitmap, is_null())));
+    assert!(!uses_index_scan(&optimize(IndexType::Bitmap, less_than())));
+
+    // brin indexes are lossy, the rows are looked up in the table
+    assert!(!uses_index_scan(&optimize(IndexType::Brin, less_than())));
+
+    // only b-tree indexes deliver the order of their keys
+    let index_scan = |index_type: IndexType| {
+        let index_desc = IndexDesc::new(
+            MdId::index(4),
+            "IDX_1".to_string(),
+            index_type,
+            vec![ColumnVar::new(0)],
+            vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)],
+        );
+        PhysicalIndexScan::new(
+            index_desc,
+            TableDesc::new(MdId::relation(2)),
+            vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)],
+            equal(),
+        )
+    };
+    assert_eq!(
+        index_scan(IndexType::Btree).derive_output_properties(&[]),
+        required_properties(0)
+    );
+    assert_eq!(
+        index_scan(IndexType::Hash).derive_output_properties(&[]),
+        Rc::new(PhysicalProperties::new())
+    );
+}
+
+// the index does not have c2 and c3, which are fetched from the table
+// sql: select c2, c3 from t1 where c1 is null order by c1;
+// idx: key columns(c1)
+// Project(c2, c3) -> IndexLookup(c1)
+#[test]
+fn test_sort_project_index_lookup() {
+    let optimize = |index_md: IndexMd| {
+        let mut md_cache = md_cache();
+        md_cache.insert(MdId::index(4), Box::new(index_md));
+        let md_accessor = MdAccessor::new(Rc::new(CachedMdProvider::new(md_cache)));
+
+        let filter = logical_filter(vec![logical_scan()], 0, None);
+        let project = logical_project(vec![filter]);
+        let mut optimizer = Optimizer::new(Options::default());
+        optimizer.optimize(project, required_properties(0), md_accessor, create_rule_set())
+    };
+    let index_md = IndexMd::new(MdId::index(4), "IDX_1".to_string(), vec![ColumnVar::new(0)], vec![]);
+    let physical_plan = optimize(index_md.clone());
+
+    let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
+    let predicate = IsNull::new(Box::new(ColumnVar::new(0)));
+    let lookup = PhysicalIndexLookup::new(
+        IndexDesc::from_index_md(&index_md, &output_columns).unwrap(),
+        TableDesc::new(MdId::relation(2)),
+        output_columns,
+        Rc::new(And::new(vec![Rc::new(predicate)])),
+    );
+    let lookup = PhysicalPlan::new(Rc::new(lookup), vec![]);
+    let project = vec![
+        Rc::new(ColumnVar::new(1)) as Rc<dyn ScalarExpression>,
+        Rc::new(ColumnVar::new(2)) as Rc<dyn ScalarExpression>,
+    ];
+    let project = PhysicalPlan::new(Rc::new(PhysicalProject::new(project)), vec![lookup]);
+    assert_eq!(physical_plan, project);
+
+    // the rows of the page ranges a brin index finds are fetched, in no order
+    let physical_plan = optimize(index_md.with_index_type(IndexType::Brin));
+    assert!(physical_plan.operator().downcast_ref::<PhysicalSort>().is_some());
+    let lookup = physical_plan.inputs()[0].inputs()[0].operator();
+    assert!(lookup.downcast_ref::<PhysicalIndexLookup>().is_some());
+}
+
+// the partial index has the rows of c2 IS NULL only
+// sql: select c2, c3 from t1 where c1 is null [and c2 is null] order by c1;
+// idx: key columns(c1) included columns(c1, c2, c3) where c2 is null
+#[test]
+fn test_sort_project_partial_index_scan() {
+    let index_md = IndexMd::new(
+        MdId::index(4),
+        "IDX_1".to_string(),
+        vec![ColumnVar::new(0)],
+        vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)],
+    )
+    .with_predicate(Box::new(IsNull::new(Box::new(ColumnVar::new(1)))));
+    let optimize = |filter: LogicalPlan| {
+        let mut md_cache = md_cache();
+        md_cache.insert(MdId::index(4), Box::new(index_md.clone()));
+        let md_accessor = MdAccessor::new(Rc::new(CachedMdProvider::new(md_cache)));
+        let mut optimizer = Optimizer::new(Options::default());
+        optimizer.optimize(
+            logical_project(vec![filter]),
+            required_properties(0),
+            md_accessor,
+            create_rule_set(),
+        )
+    };
+
+    // Project(c2, c3) -> IndexScan(c1), c2 IS NULL holds for the rows of the index
+    let physical_plan = optimize(logical_filter(vec![logical_scan()], 0, Some(1)));
+    let scan = physical_plan.inputs()[0].operator();
+    let scan = scan.downcast_ref::<PhysicalIndexScan>().unwrap();
+    let predicate = And::new(vec![Rc::new(IsNull::new(Box::new(ColumnVar::new(0))))]);
+    assert!(scan.predicate().equal(&predicate));
+    assert!(scan.index_desc().predicate().is_some());
+
+    // the rows of c2 IS NOT NULL are not in the index
+    let physical_plan = optimize(logical_filter(vec![logical_scan()], 0, None));
+    assert!(physical_plan.operator().downcast_ref::<PhysicalSort>().is_some());
+    let filter = physical_plan.inputs()[0].inputs()[0].operator();
+    assert!(filter.downcast_ref::<PhysicalFilter>().is_some());
+}
+
+// the filter is the predicate of the partial index, which is scanned whole
+// sql: select c2, c3 from t1 where c2 is null order by c1;
+// idx: key columns(c1) included columns(c1, c2, c3) where c2 is null
+#[test]
+fn test_sort_project_partial_index_full_scan() {
+    let index_md = IndexMd::new(
+        MdId::index(4),
+        "IDX_1".to_string(),
+        vec![ColumnVar::new(0)],
+        vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)],
+    )
+    .with_predicate(Box::new(IsNull::new(Box::new(ColumnVar::new(1)))));
+    let mut md_cache = md_cache();
+    md_cache.insert(MdId::index(4), Box::new(index_md));
+    let md_accessor = MdAccessor::new(Rc::new(CachedMdProvider::new(md_cache)));
+    let mut optimizer = Optimizer::new(Options::default());
+    let physical_plan = optimizer.optimize(
+        logical_project(vec![logical_filter(vec![logical_scan()], 1, None)]),
+        required_properties(0),
+        md_accessor,
+        create_rule_set(),
+    );
+
+    // Project(c2, c3) -> IndexScan(), the index delivers the order of c1
+    let scan = physical_plan.inputs()[0].operator();
+    let scan = scan.downcast_ref::<PhysicalIndexScan>().unwrap();
+    assert!(scan.predicate().equal(&And::new(vec![])));
+    assert!(scan.range().is_full());
+    assert!(scan.index_desc().predicate().is_some());
+}
@@ -0,0 +1,165 @@
+use cso_core::metadata::MdProvider;
+use cso_demo::metadata::{
+    CachedMdProvider, CachingMdProvider, LayeredMdProvider, LruMdCache, MdAccessor, MdCache, MdId, Metadata,
+};
+use cso_demo::statistics::RelationStats;
+use cso_demo::Demo;
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// A provider of relation statistics counting the retrieved metadata.
+struct CountingMdProvider {
+    retrieved: Cell<usize>,
+}
+
+impl MdProvider<Demo> for CountingMdProvider {
+    fn retrieve_metadata(&self, md_id: &MdId) -> Option<Box<dyn Metadata>> {
+        self.retrieved.set(self.retrieved.get() + 1);
+        Some(relation_stats(md_id.oid()))
+    }
+
+    fn current_version(&self, md_id: &MdId) -> Option<MdId> {
+        Some(*md_id)
+    }
+}
+
+fn stats_id(oid: u64) -> MdId {
+    MdId::relation_stats(oid)
+}
+
+fn relation_stats(rows: u64) -> Box<dyn Metadata> {
+    Box::new(RelationStats::new("t1".to_string(), rows, false, vec![]))
+}
+
+fn rows(md: Box<dyn Metadata>) -> u64 {
+    md.downcast_ref::<RelationStats>().unwrap().rows()
+}
+
+#[test]
+fn test_lru_md_cache_eviction() {
+    // bounded by the number of entries
+    let md_cache = LruMdCache::new(2, usize::MAX);
+    md_cache.insert(stats_id(1), relation_stats(1));
+    md_cache.insert(stats_id(2), relation_stats(2));
+    assert!(md_cache.get(&stats_id(1)).is_some());
+    md_cache.insert(stats_id(3), relation_stats(3));
+    assert!(md_cache.contains(&stats_id(1)) && !md_cache.contains(&stats_id(2)) && md_cache.contains(&stats_id(3)));
+
+    // bounded by the size of the entries, the size hints of the metadata
+    let bytes = md_cache.bytes() / 2;
+    assert_eq!(bytes, relation_stats(1).size_hint());
+    let md_cache = LruMdCache::new(usize::MAX, bytes * 2);
+    md_cache.insert(stats_id(1), relation_stats(1));
+    md_cache.insert(stats_id(2), relation_stats(2));
+    assert_eq!((md_cache.len(), md_cache.bytes()), (2, bytes * 2));
+    md_cache.insert(stats_id(3), relation_stats(3));
+    assert!(!md_cache.contains(&stats_id(1)) && md_cache.contains(&stats_id(2)) && md_cache.contains(&stats_id(3)));
+
+    // replaced entries are not counted twice
+    md_cache.insert(stats_id(3), relation_stats(4));
+    assert_eq!((md_cache.len(), md_cache.bytes()), (2, bytes * 2));
+    assert_eq!(rows(md_cache.get(&stats_id(3)).unwrap()), 4);
+
+    // metadata larger than the cache is not cached
+    let md_cache = LruMdCache::new(usize::MAX, bytes - 1);
+    md_cache.insert(stats_id(1), relation_stats(1));
+    assert!(md_cache.is_empty());
+}
+
+#[test]
+fn test_lru_md_cache_invalidation() {
+    let md_cache = LruMdCache::new(10, usize::MAX);
+    for oid in 1..=4 {
+        md_cache.insert(stats_id(oid), relation_stats(oid));
+    }
+    assert!(md_cache.invalidate(&stats_id(1)));
+    assert!(!md_cache.invalidate(&stats_id(1)));
+    assert_eq!(md_cache.invalidate_if(|md_id| md_id.oid() % 2 == 0), 2);
+    assert_eq!(md_cache.len(), 1);
+    assert!(md_cache.contains(&stats_id(3)));
+    md_cache.clear();
+    assert!(md_cache.is_empty());
+    assert_eq!(md_cache.bytes(), 0);
+}
+
+#[test]
+fn test_layered_md_provider() {
+    let catalog = Rc::new(CountingMdProvider {
+        retrieved: Cell::new(0),
+    });
+    let shared_cache = Rc::new(LruMdCache::new(10, usize::MAX));
+
+    // the shared cache outlives the providers of a query
+    for _ in 0..2 {
+        let mut overrides = MdCache::new();
+        overrides.insert(stats_id(1), relation_stats(100));
+        let md_provider = LayeredMdProvider::new(vec![
+            Rc::new(CachedMdProvider::new(overrides)),
+            Rc::new(CachingMdProvider::new(shared_cache.clone(), catalog.clone())),
+        ]);
+        assert_eq!(rows(md_provider.retrieve_metadata(&stats_id(1)).unwrap()), 100);
+        assert_eq!(rows(md_provider.retrieve_metadata(&stats_id(2)).unwrap()), 2);
+    }
+    assert_eq!(catalog.retrieved.get(), 1);
+    assert!(!shared_cache.contains(&stats_id(1)));
+
+    // invalidated metadata is retrieved again
+    shared_cache.invalidate(&stats_id(2));
+    let md_provider = CachingMdProvider::new(shared_cache.clone(), catalog.clone());
+    assert!(md_provider.retrieve_metadata(&stats_id(2)).is_some());
+    assert_eq!(catalog.retrieved.get(), 2);
+
+    // whether metadata is current is answered without retrieving it
+    assert!(md_provider.is_current(&stats_id(5)));
+    assert_eq!(catalog.retrieved.get(), 2);
+}
+
+#[test]
+fn test_current_version() {
+    let mut md_cache = MdCache::new();
+    md_cache.insert(stats_id(1).with_version(2), relation_stats(2));
+    md_cache.insert(stats_id(1).with_version(1), relation_stats(1));
+    md_cache.insert(stats_id(2), relation_stats(20));
+    let md_provider = Rc::new(CachedMdProvider::new(md_cache));
+
+    // the object, or any of its versions, names the latest version
+    let current = Some(stats_id(1).with_version(2));
+    assert_eq!(md_provider.current_version(&stats_id(1)), current);
+    assert_eq!(md_provider.current_version(&stats_id(1).with_version(1)), current);
+    assert_eq!(md_provider.current_version(&stats_id(2)), Some(stats_id(2)));
+    assert_eq!(md_provider.current_version(&stats_id(3)), None);
+
+    // metadata referenced by the object is retrieved in its current version
+    let md_accessor = MdAccessor::new(md_provider);
+    assert_eq!(rows(md_accessor.retrieve_metadata(&stats_id(1)).unwrap()), 2);
+}
+
+#[test]
+fn test_invalidate_stale_metadata() {
+    let catalog = |version: u64| {
+        let mut md_cache = MdCache::new();
+        md_cache.insert(stats_id(1).with_version(version), relation_stats(version));
+        md_cache.insert(stats_id(2), relation_stats(2));
+        Rc::new(CachedMdProvider::new(md_cache))
+    };
+    let shared_cache = Rc::new(LruMdCache::new(10, usize::MAX));
+    let md_provider = CachingMdProvider::new(shared_cache.clone(), catalog(1));
+    assert!(md_provider.retrieve_metadata(&stats_id(1).with_version(1)).is_some());
+    assert!(md_provider.retrieve_metadata(&stats_id(2)).is_some());
+    assert_eq!(shared_cache.invalidate_stale(&md_provider), 0);
+
+    // ANALYZE creates a new version of the statistics, the cached version is not current anymore
+    let md_provider = CachingMdProvider::new(shared_cache.clone(), catalog(2));
+    assert!(!md_provider.is_current(&stats_id(1).with_version(1)));
+    assert!(md_provider.is_current(&stats_id(1).with_version(2)));
+    assert_eq!(shared_cache.invalidate_stale(&md_provider), 1);
+    assert!(!shared_cache.contains(&stats_id(1).with_version(1)));
+    assert!(shared_cache.contains(&stats_id(2)));
+
+    // overridden metadata is current
+    let mut overrides = MdCache::new();
+    overrides.insert(stats_id(1).with_version(1), relation_stats(100));
+    let md_provider = LayeredMdProvider::new(vec![Rc::new(CachedMdProvider::new(overrides)), catalog(2)]);
+    assert!(md_provider.is_current(&stats_id(1).with_version(1)));
+    assert!(!md_provider.is_current(&stats_id(3)));
+}
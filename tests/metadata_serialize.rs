@@ -1,18 +1,18 @@
 use cso_demo::datum::Datum;
-use cso_demo::metadata::{MdCache, Metadata};
+use cso_demo::metadata::{MdCache, MdId, Metadata};
 use cso_demo::statistics::{Bucket, ColumnMetadata, ColumnStats, Histogram, RelationMetadata, RelationStats};
 
 #[test]
 fn test_serialize_md_cache() {
     // mdids
-    let relation_stats_id = 1;
-    let relation_md_id = 2;
-    let column_stats_id = 3;
+    let relation_stats_id = MdId::relation_stats(1);
+    let relation_md_id = MdId::relation(2);
+    let column_stats_id = MdId::column_stats(3);
 
     let json = serde_json::to_string(&relation_stats_id).unwrap();
-    let new_relation_stats_id: u64 = serde_json::from_str(json.as_str()).unwrap();
-    debug_assert_eq!(relation_stats_id.to_string(), new_relation_stats_id.to_string());
-    debug_assert_eq!("1", new_relation_stats_id.to_string());
+    let new_relation_stats_id: MdId = serde_json::from_str(json.as_str()).unwrap();
+    debug_assert_eq!(relation_stats_id, new_relation_stats_id);
+    debug_assert_eq!(new_relation_stats_id.oid(), 1);
 
     // relation stats
     let relation_stats = RelationStats::new("x".to_string(), 9011, false, vec![column_stats_id]);
@@ -0,0 +1,142 @@
+use cso_core::rule::RuleSet;
+use cso_demo::datum::Datum;
+use cso_demo::expression::{ColumnVar, IsNull, ScalarExpression};
+use cso_demo::metadata::{CachedMdProvider, MdCache, MdId, Metadata};
+use cso_demo::operator::logical_filter::LogicalFilter;
+use cso_demo::operator::logical_project::LogicalProject;
+use cso_demo::operator::logical_scan::{LogicalScan, TableDesc};
+use cso_demo::operator::physical_index_scan::PhysicalIndexScan;
+use cso_demo::operator::physical_project::PhysicalProject;
+use cso_demo::operator::physical_sort::{OrderSpec, Ordering};
+use cso_demo::property::sort_property::SortProperty;
+use cso_demo::property::PhysicalProperties;
+use cso_demo::rule::create_rule_set;
+use cso_demo::statistics::{ColumnMetadata, ColumnStats, IndexInfo, IndexMd, RelationMetadata, RelationStats};
+use cso_demo::{Demo, LogicalPlan, Minidump, Optimizer, Options};
+use std::rc::Rc;
+
+// Project(c2, c3) -> Filter(c1 IS NULL) -> Scan(c1, c2, c3)
+fn logical_plan() -> LogicalPlan {
+    let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
+    let scan = LogicalScan::new(TableDesc::new(MdId::relation(2)), output_columns);
+    let scan = LogicalPlan::new(Rc::new(scan), vec![], vec![]);
+
+    let predicate = IsNull::new(Box::new(ColumnVar::new(0)));
+    let filter = LogicalFilter::new(Rc::new(predicate));
+    let filter = LogicalPlan::new(Rc::new(filter), vec![scan], vec![]);
+
+    let project = vec![
+        Rc::new(ColumnVar::new(1)) as Rc<dyn ScalarExpression>,
+        Rc::new(ColumnVar::new(2)) as Rc<dyn ScalarExpression>,
+    ];
+    LogicalPlan::new(Rc::new(LogicalProject::new(project)), vec![filter], vec![])
+}
+
+fn required_properties() -> Rc<PhysicalProperties> {
+    let order = OrderSpec {
+        order_desc: vec![Ordering::new(0)],
+    };
+    PhysicalProperties::with_property(Box::new(SortProperty::with_order(order)))
+}
+
+fn md_cache() -> MdCache {
+    let relation_stats = RelationStats::new("t1".to_string(), 9011, false, vec![MdId::column_stats(3)]);
+    let index_md = IndexMd::new(
+        MdId::index(4),
+        "IDX_1".to_string(),
+        vec![ColumnVar::new(0)],
+        vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)],
+    );
+    let column_md = vec![
+        ColumnMetadata::new("c1".to_string(), 1, true, 4, Datum::I32(0)),
+        ColumnMetadata::new("c2".to_string(), 2, true, 4, Datum::I32(0)),
+        ColumnMetadata::new("c3".to_string(), 3, false, 4, Datum::I32(0)),
+    ];
+    let relation_md = RelationMetadata::new(
+        "t1".to_string(),
+        column_md,
+        MdId::relation_stats(1),
+        vec![IndexInfo::new(MdId::index(4))],
+    );
+    let column_stats = ColumnStats::new(1, "c1".to_string(), Datum::I32(0), Datum::I32(1), 0, None);
+    // the statistics of another table, which the optimization does not retrieve
+    let other_stats = RelationStats::new("t2".to_string(), 10, false, vec![]);
+
+    let mut md_cache = MdCache::new();
+    md_cache.insert(MdId::relation_stats(1), Box::new(relation_stats) as Box<dyn Metadata>);
+    md_cache.insert(MdId::relation(2), Box::new(relation_md) as Box<dyn Metadata>);
+    md_cache.insert(MdId::column_stats(3), Box::new(column_stats) as Box<dyn Metadata>);
+    md_cache.insert(MdId::index(4), Box::new(index_md) as Box<dyn Metadata>);
+    md_cache.insert(MdId::relation_stats(5), Box::new(other_stats) as Box<dyn Metadata>);
+    md_cache
+}
+
+fn capture(rule_set: RuleSet<Demo>) -> Minidump {
+    let mut optimizer = Optimizer::new(Options::default());
+    let md_provider = Rc::new(CachedMdProvider::new(md_cache()));
+    let (physical_plan, minidump) =
+        optimizer.optimize_with_minidump(logical_plan(), required_properties(), md_provider, rule_set);
+    assert_eq!(&physical_plan, minidump.physical_plan());
+
+    let mut json = Vec::new();
+    minidump.write_json(&mut json).unwrap();
+    Minidump::read_json(json.as_slice()).unwrap()
+}
+
+#[test]
+fn test_minidump_replay() {
+    let minidump = capture(create_rule_set());
+
+    // Project(c2, c3) -> IndexScan(c1)
+    let physical_plan = minidump.physical_plan();
+    assert!(physical_plan.operator().downcast_ref::<PhysicalProject>().is_some());
+    let scan = physical_plan.inputs()[0].operator();
+    assert!(scan.downcast_ref::<PhysicalIndexScan>().is_some());
+
+    // only the retrieved metadata is captured
+    let metadata = minidump.metadata();
+    assert!(metadata.get(&MdId::relation(2)).is_some() && metadata.get(&MdId::index(4)).is_some());
+    assert!(metadata.get(&MdId::relation_stats(5)).is_none());
+
+    assert_eq!(minidump.required_properties(), required_properties().as_ref());
+    assert!(minidump.verify(&create_rule_set()).unwrap());
+    assert_eq!(&minidump.replay(&create_rule_set()).unwrap(), minidump.physical_plan());
+}
+
+#[test]
+fn test_minidump_replay_rule_set() {
+    // without the index scan rules the filter is not turned into an index scan
+    let full_rule_set = create_rule_set();
+    let mut rule_set = RuleSet::new();
+    rule_set.set_implement_rules(
+        full_rule_set
+            .implement_rules()
+            .iter()
+            .filter(|rule| rule.name() != "index scan implementation")
+            .cloned()
+            .collect(),
+    );
+    let minidump = capture(rule_set);
+
+    assert!(minidump.rule_set().transform_rules().is_empty());
+    assert_eq!(minidump.rule_set().implement_rules().len(), 3);
+    let scan = minidump.physical_plan().inputs()[0].inputs()[0].operator();
+    assert!(scan.downcast_ref::<PhysicalIndexScan>().is_none());
+    assert!(minidump.verify(&full_rule_set).unwrap());
+}
+
+#[test]
+fn test_minidump_replay_unknown_rule() {
+    let minidump = capture(create_rule_set());
+    let mut json = Vec::new();
+    minidump.write_json(&mut json).unwrap();
+    let json = String::from_utf8(json)
+        .unwrap()
+        .replace("\"index scan implementation\"", "\"bogus rule\"");
+    let minidump = Minidump::read_json(json.as_bytes()).unwrap();
+
+    let err = minidump.replay(&create_rule_set()).unwrap_err();
+    assert_eq!(err.name(), "bogus rule");
+    assert_eq!(err.to_string(), "unknown rule: bogus rule");
+    assert!(minidump.verify(&create_rule_set()).is_err());
+}
@@ -0,0 +1,176 @@
+use cso_demo::expression::{
+    And, ColumnVar, Const, Equal, GreaterThan, GreaterThanEqual, IsNotNull, IsNull, LessThan, LessThanEqual, Not,
+    NotEqual, Or, ScalarExpression,
+};
+use cso_demo::normalize::{
+    normalize, normalize_predicate, to_cnf, to_dnf, DEFAULT_CNF_MAX_CONJUNCTS, DEFAULT_DNF_MAX_DISJUNCTS,
+};
+use cso_demo::operator::logical_filter::LogicalFilter;
+use std::rc::Rc;
+
+fn column(id: u32) -> Box<dyn ScalarExpression> {
+    Box::new(ColumnVar::new(id))
+}
+
+fn int(val: i32) -> Box<dyn ScalarExpression> {
+    Box::new(Const::Int32(val))
+}
+
+fn eq(id: u32, val: i32) -> Box<dyn ScalarExpression> {
+    Box::new(Equal::new(column(id), int(val)))
+}
+
+fn and(operands: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+    Box::new(And::new(operands.into_iter().map(Rc::from).collect()))
+}
+
+fn or(operands: Vec<Box<dyn ScalarExpression>>) -> Box<dyn ScalarExpression> {
+    Box::new(Or::new(operands))
+}
+
+fn not(operand: Box<dyn ScalarExpression>) -> Box<dyn ScalarExpression> {
+    Box::new(Not::new(operand))
+}
+
+fn assert_expr_eq(actual: &dyn ScalarExpression, expected: &dyn ScalarExpression) {
+    assert!(actual.equal(expected), "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn test_flatten_and_dedupe() {
+    // (c0 = 1 AND (c1 = 2 AND c0 = 1)) is c0 = 1 AND c1 = 2
+    let expr = and(vec![eq(0, 1), and(vec![eq(1, 2), eq(0, 1)])]);
+    assert_expr_eq(
+        normalize(expr.as_ref()).as_ref(),
+        and(vec![eq(0, 1), eq(1, 2)]).as_ref(),
+    );
+
+    // (c0 = 1 OR (c1 = 2 OR c0 = 1)) is c0 = 1 OR c1 = 2
+    let expr = or(vec![eq(0, 1), or(vec![eq(1, 2), eq(0, 1)])]);
+    assert_expr_eq(normalize(expr.as_ref()).as_ref(), or(vec![eq(0, 1), eq(1, 2)]).as_ref());
+
+    // an operand repeated is the operand
+    let expr = and(vec![eq(0, 1), eq(0, 1)]);
+    assert_expr_eq(normalize(expr.as_ref()).as_ref(), eq(0, 1).as_ref());
+}
+
+#[test]
+fn test_push_down_negation() {
+    // NOT (c0 < 1) is c0 >= 1
+    let expr = not(Box::new(LessThan::new(column(0), int(1))));
+    let expected = GreaterThanEqual::new(column(0), int(1));
+    assert_expr_eq(normalize(expr.as_ref()).as_ref(), &expected);
+
+    // NOT NOT c0 = 1 is c0 = 1
+    let expr = not(not(eq(0, 1)));
+    assert_expr_eq(normalize(expr.as_ref()).as_ref(), eq(0, 1).as_ref());
+
+    // NOT c0 IS NULL is c0 IS NOT NULL
+    let expr = not(Box::new(IsNull::new(column(0))));
+    assert_expr_eq(normalize(expr.as_ref()).as_ref(), &IsNotNull::new(column(0)));
+
+    // NOT (c0 = 1 AND (c1 > 2 OR c2 IS NOT NULL)) is c0 <> 1 OR (c1 <= 2 AND c2 IS NULL)
+    let c1_gt_2 = Box::new(GreaterThan::new(column(1), int(2)));
+    let expr = not(and(vec![
+        eq(0, 1),
+        or(vec![c1_gt_2, Box::new(IsNotNull::new(column(2)))]),
+    ]));
+    let expected = or(vec![
+        Box::new(NotEqual::new(column(0), int(1))),
+        and(vec![
+            Box::new(LessThanEqual::new(column(1), int(2))),
+            Box::new(IsNull::new(column(2))),
+        ]),
+    ]);
+    assert_expr_eq(normalize(expr.as_ref()).as_ref(), expected.as_ref());
+}
+
+#[test]
+fn test_factor_common_conjuncts() {
+    // (c0 = 1 AND c1 = 2) OR (c1 = 2 AND c2 = 3) is c1 = 2 AND (c0 = 1 OR c2 = 3)
+    let expr = or(vec![and(vec![eq(0, 1), eq(1, 2)]), and(vec![eq(1, 2), eq(2, 3)])]);
+    let expected = and(vec![eq(1, 2), or(vec![eq(0, 1), eq(2, 3)])]);
+    assert_expr_eq(normalize(expr.as_ref()).as_ref(), expected.as_ref());
+
+    // c0 = 1 OR (c0 = 1 AND c1 = 2) is c0 = 1
+    let expr = or(vec![eq(0, 1), and(vec![eq(0, 1), eq(1, 2)])]);
+    assert_expr_eq(normalize(expr.as_ref()).as_ref(), eq(0, 1).as_ref());
+
+    // nothing in common
+    let expr = or(vec![and(vec![eq(0, 1), eq(1, 2)]), eq(2, 3)]);
+    assert_expr_eq(normalize(expr.as_ref()).as_ref(), expr.as_ref());
+}
+
+#[test]
+fn test_empty_connectives() {
+    // an empty disjunction is false, NOT of an empty conjunction is an empty disjunction
+    assert_expr_eq(normalize(or(vec![]).as_ref()).as_ref(), &Const::Bool(false));
+    assert_expr_eq(normalize(not(and(vec![])).as_ref()).as_ref(), &Const::Bool(false));
+    assert_expr_eq(normalize(and(vec![]).as_ref()).as_ref(), and(vec![]).as_ref());
+    assert_expr_eq(
+        to_dnf(not(and(vec![])).as_ref(), DEFAULT_DNF_MAX_DISJUNCTS).as_ref(),
+        &Const::Bool(false),
+    );
+}
+
+#[test]
+fn test_to_cnf() {
+    // (c0 = 1 AND c1 = 2) OR c2 = 3 is (c0 = 1 OR c2 = 3) AND (c1 = 2 OR c2 = 3)
+    let expr = or(vec![and(vec![eq(0, 1), eq(1, 2)]), eq(2, 3)]);
+    let expected = and(vec![or(vec![eq(0, 1), eq(2, 3)]), or(vec![eq(1, 2), eq(2, 3)])]);
+    assert_expr_eq(
+        to_cnf(expr.as_ref(), DEFAULT_CNF_MAX_CONJUNCTS).as_ref(),
+        expected.as_ref(),
+    );
+
+    // (c0 = 1 AND c1 = 2) OR (c2 = 3 AND c3 = 4) has 4 conjuncts
+    let expr = or(vec![and(vec![eq(0, 1), eq(1, 2)]), and(vec![eq(2, 3), eq(3, 4)])]);
+    let cnf = to_cnf(expr.as_ref(), 4);
+    let and = cnf.downcast_ref::<And>().unwrap();
+    assert_eq!(and.expressions().len(), 4);
+    assert!(and.expressions()[0].equal(or(vec![eq(0, 1), eq(2, 3)]).as_ref()));
+
+    // too large, the predicate is only normalized
+    assert_expr_eq(to_cnf(expr.as_ref(), 3).as_ref(), expr.as_ref());
+}
+
+#[test]
+fn test_to_dnf() {
+    // (c0 = 1 OR c1 = 2) AND c2 = 3 is (c0 = 1 AND c2 = 3) OR (c1 = 2 AND c2 = 3)
+    let expr = and(vec![or(vec![eq(0, 1), eq(1, 2)]), eq(2, 3)]);
+    let expected = or(vec![and(vec![eq(0, 1), eq(2, 3)]), and(vec![eq(1, 2), eq(2, 3)])]);
+    assert_expr_eq(
+        to_dnf(expr.as_ref(), DEFAULT_DNF_MAX_DISJUNCTS).as_ref(),
+        expected.as_ref(),
+    );
+
+    // NOT (c0 = 1 AND c1 = 2) is c0 <> 1 OR c1 <> 2
+    let expr = not(and(vec![eq(0, 1), eq(1, 2)]));
+    let expected = or(vec![
+        Box::new(NotEqual::new(column(0), int(1))),
+        Box::new(NotEqual::new(column(1), int(2))),
+    ]);
+    assert_expr_eq(to_dnf(expr.as_ref(), 2).as_ref(), expected.as_ref());
+
+    // (c0 = 1 OR c1 = 2) AND (c2 = 3 OR c3 = 4) has 4 disjuncts, too large, the predicate is only normalized
+    let expr = and(vec![or(vec![eq(0, 1), eq(1, 2)]), or(vec![eq(2, 3), eq(3, 4)])]);
+    let dnf = to_dnf(expr.as_ref(), 4);
+    assert_eq!(dnf.downcast_ref::<Or>().unwrap().expressions().len(), 4);
+    assert_expr_eq(to_dnf(expr.as_ref(), 3).as_ref(), expr.as_ref());
+}
+
+#[test]
+fn test_normalized_filter() {
+    // an already normalized predicate is kept as is
+    let predicate: Rc<dyn ScalarExpression> = Rc::from(and(vec![eq(0, 1), eq(1, 2)]));
+    assert!(Rc::ptr_eq(&normalize_predicate(&predicate), &predicate));
+
+    // NOT (c0 <> 1 OR c0 IS NULL) is c0 = 1 AND c0 IS NOT NULL
+    let predicate = not(or(vec![
+        Box::new(NotEqual::new(column(0), int(1))),
+        Box::new(IsNull::new(column(0))),
+    ]));
+    let filter = LogicalFilter::new(Rc::from(predicate));
+    let expected = and(vec![eq(0, 1), Box::new(IsNotNull::new(column(0)))]);
+    assert_expr_eq(filter.predicate().as_ref(), expected.as_ref());
+}
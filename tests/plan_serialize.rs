@@ -0,0 +1,129 @@
+use cso_demo::datum::{Datum, Decimal};
+use cso_demo::eval::{evaluate, Row};
+use cso_demo::expression::{
+    And, ArithOp, Arithmetic, Between, Case, Cast, ColumnVar, Const, Equal, FunctionRegistry, InList, IsNull, LessThan,
+    Like, Not, Or, Param, ScalarExpression,
+};
+use cso_demo::metadata::MdId;
+use cso_demo::operator::logical_filter::LogicalFilter;
+use cso_demo::operator::logical_index_scan::IndexDesc;
+use cso_demo::operator::logical_project::LogicalProject;
+use cso_demo::operator::logical_scan::{LogicalScan, TableDesc};
+use cso_demo::operator::physical_filter::PhysicalFilter;
+use cso_demo::operator::physical_index_scan::PhysicalIndexScan;
+use cso_demo::operator::physical_project::PhysicalProject;
+use cso_demo::operator::physical_sort::{OrderSpec, Ordering, PhysicalSort};
+use cso_demo::statistics::IndexType;
+use cso_demo::{DataType, LogicalPlan, PhysicalPlan};
+use std::rc::Rc;
+
+fn column(id: u32) -> Box<dyn ScalarExpression> {
+    Box::new(ColumnVar::new(id).with_data_type(DataType::Int32))
+}
+
+fn int(val: i32) -> Box<dyn ScalarExpression> {
+    Box::new(Const::Int32(val))
+}
+
+fn round_trip(expr: &dyn ScalarExpression) -> Box<dyn ScalarExpression> {
+    let json = serde_json::to_string(expr).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn test_serialize_expressions() {
+    let registry = FunctionRegistry::with_builtins();
+    let expressions: Vec<Box<dyn ScalarExpression>> = vec![
+        Box::new(Equal::new(column(0), int(1))),
+        Box::new(Not::new(Box::new(IsNull::new(column(1))))),
+        Box::new(And::new(vec![
+            Rc::new(Equal::new(column(0), Box::new(Param::new(1)))),
+            Rc::new(Or::new(vec![
+                Box::new(InList::new(column(1), vec![int(1), int(2)], true)),
+                Box::new(Between::new(column(2), int(1), int(3), false)),
+            ])),
+        ])),
+        Box::new(Like::new(
+            Box::new(Const::Str("abc".to_string())),
+            Box::new(Const::Str("a%".to_string())),
+            false,
+        )),
+        Box::new(Arithmetic::new(
+            ArithOp::Multiply,
+            Box::new(Const::Decimal(Decimal::new(125, 2))),
+            Box::new(Cast::new(column(0), DataType::Float64)),
+        )),
+        Box::new(Case::new(
+            Some(column(0)),
+            vec![(int(1), Box::new(Const::Null))],
+            Some(Box::new(Const::Timestamp(86_400_000_000))),
+        )),
+        Box::new(registry.call("abs", vec![column(0)]).unwrap()),
+    ];
+
+    for expr in expressions {
+        let deserialized = round_trip(expr.as_ref());
+        assert!(deserialized.equal(expr.as_ref()), "{:?} != {:?}", deserialized, expr);
+        assert_eq!(deserialized.return_type(), expr.return_type());
+    }
+
+    // the implementation of a built-in function is restored
+    let abs = round_trip(&registry.call("abs", vec![int(-2)]).unwrap());
+    assert_eq!(evaluate(abs.as_ref(), &Row::new()), Some(Datum::I32(2)));
+}
+
+#[test]
+fn test_serialize_logical_plan() {
+    // Project(c1) -> Filter(c0 = $1 AND c1 IS NULL) -> Scan(c0, c1)
+    let scan = LogicalScan::new(
+        TableDesc::new(MdId::relation(2)),
+        vec![ColumnVar::new(0), ColumnVar::new(1)],
+    );
+    let scan = LogicalPlan::new(Rc::new(scan), vec![], vec![]);
+    let predicate = And::new(vec![
+        Rc::new(Equal::new(column(0), Box::new(Param::new(1)))),
+        Rc::new(IsNull::new(column(1))),
+    ]);
+    let filter = LogicalPlan::new(Rc::new(LogicalFilter::new(Rc::new(predicate))), vec![scan], vec![]);
+    let project = LogicalProject::new(vec![Rc::from(column(1))]);
+    let project = LogicalPlan::new(Rc::new(project), vec![filter], vec![]);
+
+    let json = serde_json::to_string(&project).unwrap();
+    let deserialized: LogicalPlan = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+
+    let filter = &deserialized.inputs()[0];
+    let filter = filter.operator().downcast_ref::<LogicalFilter>().unwrap();
+    assert!(filter.predicate().downcast_ref::<And>().is_some());
+    let scan = &deserialized.inputs()[0].inputs()[0];
+    let scan = scan.operator().downcast_ref::<LogicalScan>().unwrap();
+    assert_eq!(scan.table_desc(), &TableDesc::new(MdId::relation(2)));
+}
+
+#[test]
+fn test_serialize_physical_plan() {
+    // Project(c1) -> Sort(c1) -> Filter(c1 IS NULL) -> IndexScan(c0 < 10)
+    let index_desc = IndexDesc::new(
+        MdId::index(4),
+        "IDX_1".to_string(),
+        IndexType::Btree,
+        vec![ColumnVar::new(0)],
+        vec![ColumnVar::new(0), ColumnVar::new(1)],
+    );
+    let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1)];
+    let predicate = Rc::new(LessThan::new(column(0), int(10)));
+    let scan = PhysicalIndexScan::new(index_desc, TableDesc::new(MdId::relation(2)), output_columns, predicate);
+    let scan = PhysicalPlan::new(Rc::new(scan), vec![]);
+    let filter = PhysicalFilter::new(Rc::new(IsNull::new(column(1))));
+    let filter = PhysicalPlan::new(Rc::new(filter), vec![scan]);
+    let sort = PhysicalSort::new(OrderSpec {
+        order_desc: vec![Ordering::new(1)],
+    });
+    let sort = PhysicalPlan::new(Rc::new(sort), vec![filter]);
+    let project = PhysicalProject::new(vec![Rc::from(column(1))]);
+    let project = PhysicalPlan::new(Rc::new(project), vec![sort]);
+
+    let json = serde_json::to_string(&project).unwrap();
+    let deserialized: PhysicalPlan = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, project);
+}
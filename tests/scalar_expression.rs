@@ -0,0 +1,285 @@
+use cso_demo::datum::{Datum, Decimal};
+use cso_demo::eval::{evaluate, Row};
+use cso_demo::expression::{
+    like_match, like_prefix, And, ArithOp, Arithmetic, Between, Case, Cast, Coalesce, ColumnVar, Const, Equal,
+    Function, FunctionRegistry, GreaterThan, GreaterThanEqual, InList, LessThan, LessThanEqual, Like, LikePrefix, Not,
+    Or, ReturnType, ScalarExpression, Volatility,
+};
+use cso_demo::fold::fold_constants;
+use cso_demo::normalize::normalize;
+use cso_demo::selectivity::estimate_selectivity;
+use cso_demo::statistics::{ColumnStats, MostCommonValues, Statistics};
+use cso_demo::typecheck::type_check;
+use cso_demo::DataType;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+fn column(id: u32) -> Box<dyn ScalarExpression> {
+    Box::new(ColumnVar::new(id))
+}
+
+fn int(val: i32) -> Box<dyn ScalarExpression> {
+    Box::new(Const::Int32(val))
+}
+
+fn string(val: &str) -> Box<dyn ScalarExpression> {
+    Box::new(Const::Str(val.to_string()))
+}
+
+fn null() -> Box<dyn ScalarExpression> {
+    Box::new(Const::Null)
+}
+
+fn arith(op: ArithOp, left: Box<dyn ScalarExpression>, right: Box<dyn ScalarExpression>) -> Box<dyn ScalarExpression> {
+    Box::new(Arithmetic::new(op, left, right))
+}
+
+fn eval(expr: &dyn ScalarExpression) -> Option<Datum> {
+    evaluate(expr, &Row::new())
+}
+
+#[test]
+fn test_arithmetic() {
+    assert_eq!(eval(arith(ArithOp::Add, int(1), int(2)).as_ref()), Some(Datum::I32(3)));
+    assert_eq!(
+        eval(arith(ArithOp::Divide, int(7), int(2)).as_ref()),
+        Some(Datum::I32(3))
+    );
+    assert_eq!(eval(arith(ArithOp::Divide, int(7), int(0)).as_ref()), None);
+    assert_eq!(eval(arith(ArithOp::Add, int(i32::MAX), int(1)).as_ref()), None);
+    assert_eq!(
+        eval(arith(ArithOp::Multiply, int(2), null()).as_ref()),
+        Some(Datum::Null)
+    );
+
+    // the operands are converted to their common type
+    let expr = arith(
+        ArithOp::Subtract,
+        Box::new(Const::Int64(10)),
+        Box::new(Const::Float64(0.5)),
+    );
+    assert_eq!(expr.return_type(), DataType::Float64);
+    assert_eq!(eval(expr.as_ref()), Some(Datum::F64(9.5)));
+
+    // 1.25 * 2 in decimal(12, 2)
+    let expr = arith(
+        ArithOp::Multiply,
+        Box::new(Const::Decimal(Decimal::new(125, 2))),
+        int(2),
+    );
+    assert_eq!(
+        expr.return_type(),
+        DataType::Decimal {
+            precision: 12,
+            scale: 2
+        }
+    );
+    assert_eq!(eval(expr.as_ref()), Some(Datum::Decimal(Decimal::new(250, 2))));
+
+    // the type of a column is taken from its value
+    let row = Row::from([(0, Datum::I64(40))]);
+    assert_eq!(
+        evaluate(arith(ArithOp::Add, column(0), int(2)).as_ref(), &row),
+        Some(Datum::I64(42))
+    );
+
+    assert!(type_check(arith(ArithOp::Add, int(1), string("a")).as_ref()).is_err());
+}
+
+#[test]
+fn test_cast_in_list_between() {
+    let cast = Cast::new(int(3), DataType::Float64);
+    assert_eq!(cast.return_type(), DataType::Float64);
+    assert_eq!(eval(&cast), Some(Datum::F64(3.0)));
+    assert_eq!(eval(&Cast::new(string("a"), DataType::Int32)), None);
+
+    // 2 IN (1, NULL) is NULL, 1 IN (1, NULL) is true
+    let in_list = |val: i32, negated: bool| InList::new(int(val), vec![int(1), null()], negated);
+    assert_eq!(eval(&in_list(2, false)), Some(Datum::Null));
+    assert_eq!(eval(&in_list(1, false)), Some(Datum::Bool(true)));
+    assert_eq!(eval(&in_list(1, true)), Some(Datum::Bool(false)));
+    assert!(type_check(&InList::new(int(1), vec![string("a")], false)).is_err());
+
+    let between = |val: i32, negated: bool| Between::new(int(val), int(1), int(3), negated);
+    assert_eq!(eval(&between(1, false)), Some(Datum::Bool(true)));
+    assert_eq!(eval(&between(4, false)), Some(Datum::Bool(false)));
+    assert_eq!(eval(&between(4, true)), Some(Datum::Bool(true)));
+    assert_eq!(
+        eval(&Between::new(int(4), null(), int(3), false)),
+        Some(Datum::Bool(false))
+    );
+    assert_eq!(eval(&Between::new(int(2), null(), int(3), false)), Some(Datum::Null));
+}
+
+#[test]
+fn test_like() {
+    assert!(like_match("abc", "abc"));
+    assert!(like_match("abc", "a%"));
+    assert!(like_match("abc", "%b%"));
+    assert!(like_match("abc", "a_c"));
+    assert!(like_match("", "%"));
+    assert!(like_match("a%c", "a\\%c"));
+    assert!(!like_match("abc", "a\\%c"));
+    assert!(!like_match("abc", "%d%"));
+    assert!(!like_match("abc", "ab"));
+    assert!(like_match("aXbXc", "%X%c"));
+
+    assert_eq!(like_prefix("abc"), LikePrefix::Exact("abc".to_string()));
+    assert_eq!(like_prefix("ab%%"), LikePrefix::Prefix("ab".to_string()));
+    assert_eq!(like_prefix("ab_%"), LikePrefix::Partial("ab".to_string()));
+    assert_eq!(like_prefix("a\\%"), LikePrefix::Exact("a%".to_string()));
+
+    assert_eq!(
+        eval(&Like::new(string("abc"), string("a%"), false)),
+        Some(Datum::Bool(true))
+    );
+    assert_eq!(
+        eval(&Like::new(string("abc"), string("a%"), true)),
+        Some(Datum::Bool(false))
+    );
+    assert_eq!(eval(&Like::new(null(), string("a%"), false)), Some(Datum::Null));
+    assert!(type_check(&Like::new(int(1), string("a%"), false)).is_err());
+}
+
+#[test]
+fn test_case_coalesce() {
+    // CASE WHEN c0 < 0 THEN 'negative' WHEN c0 = 0 THEN 'zero' END
+    let case = Case::new(
+        None,
+        vec![
+            (Box::new(LessThan::new(column(0), int(0))), string("negative")),
+            (Box::new(Equal::new(column(0), int(0))), string("zero")),
+        ],
+        None,
+    );
+    assert_eq!(case.return_type(), DataType::String);
+    let row = |val: i32| Row::from([(0, Datum::I32(val))]);
+    assert_eq!(evaluate(&case, &row(-1)), Some(Datum::String("negative".to_string())));
+    assert_eq!(evaluate(&case, &row(0)), Some(Datum::String("zero".to_string())));
+    assert_eq!(evaluate(&case, &row(1)), Some(Datum::Null));
+
+    // CASE c0 WHEN 1 THEN 10 ELSE 2.5 END is a float
+    let case = Case::new(
+        Some(column(0)),
+        vec![(int(1), int(10))],
+        Some(Box::new(Const::Float64(2.5))),
+    );
+    assert_eq!(case.return_type(), DataType::Float64);
+    assert_eq!(evaluate(&case, &row(1)), Some(Datum::F64(10.0)));
+    assert_eq!(evaluate(&case, &row(2)), Some(Datum::F64(2.5)));
+    let rebuilt = case.with_children(case.children().into_iter().map(|child| child.clone_box()).collect());
+    assert!(rebuilt.equal(&case));
+
+    let case = Case::new(
+        None,
+        vec![(Box::new(LessThan::new(column(0), int(0))), int(1))],
+        Some(string("a")),
+    );
+    assert!(type_check(&case).is_err());
+
+    let coalesce = Coalesce::new(vec![null(), int(2), int(3)]);
+    assert_eq!(coalesce.return_type(), DataType::Int32);
+    assert_eq!(eval(&coalesce), Some(Datum::I32(2)));
+    assert_eq!(eval(&Coalesce::new(vec![null()])), Some(Datum::Null));
+}
+
+#[test]
+fn test_function_registry() {
+    let mut registry = FunctionRegistry::with_builtins();
+    assert!(registry.call("abs", vec![]).is_none());
+    assert!(registry.call("no_such_function", vec![int(1)]).is_none());
+
+    let abs = registry.call("ABS", vec![Box::new(Const::Int64(-3))]).unwrap();
+    assert_eq!(abs.return_type(), DataType::Int64);
+    assert_eq!(eval(&abs), Some(Datum::I64(3)));
+
+    // strict functions return NULL for a NULL argument
+    let upper = registry.call("upper", vec![null()]).unwrap();
+    assert_eq!(eval(&upper), Some(Datum::Null));
+
+    // calls of immutable functions with constant arguments are folded, volatile ones are not
+    let length = registry.call("length", vec![string("abc")]).unwrap();
+    assert!(fold_constants(&length).equal(&Const::Int64(3)));
+    let random = registry.call("random", vec![]).unwrap();
+    assert!(fold_constants(&random).equal(&random));
+    let sum = Arithmetic::new(ArithOp::Add, Box::new(random.clone()), Box::new(Const::Float64(1.0)));
+    assert!(fold_constants(&sum).equal(&sum));
+
+    // a function that is not strict is called with NULL arguments
+    fn first_non_null(args: &[Datum]) -> Option<Datum> {
+        args.iter().find(|arg| !arg.is_null()).cloned().or(Some(Datum::Null))
+    }
+    registry.register(
+        Function::new("first_non_null", 1, 3, ReturnType::Argument(0))
+            .with_strict(false)
+            .with_implementation(first_non_null),
+    );
+    let function = registry.get("first_non_null").unwrap();
+    assert_eq!(function.volatility(), Volatility::Immutable);
+    let call = registry.call("first_non_null", vec![null(), int(2)]).unwrap();
+    assert_eq!(eval(&call), Some(Datum::I32(2)));
+}
+
+#[test]
+fn test_normalize_negations() {
+    // NOT (c0 IN (1, 2)) is c0 NOT IN (1, 2)
+    let in_list = InList::new(column(0), vec![int(1), int(2)], false);
+    let expr = Not::new(Box::new(in_list.clone()));
+    assert!(normalize(&expr).equal(&in_list.negate()));
+
+    // c0 BETWEEN 1 AND 3 is c0 >= 1 AND c0 <= 3
+    let between = Between::new(column(0), int(1), int(3), false);
+    let expected = And::new(vec![
+        Rc::new(GreaterThanEqual::new(column(0), int(1))),
+        Rc::new(LessThanEqual::new(column(0), int(3))),
+    ]);
+    assert!(normalize(&between).equal(&expected));
+
+    // NOT (c0 BETWEEN 1 AND 3) is c0 < 1 OR c0 > 3
+    let expr = Not::new(Box::new(between));
+    let expected = Or::new(vec![
+        Box::new(LessThan::new(column(0), int(1))),
+        Box::new(GreaterThan::new(column(0), int(3))),
+    ]);
+    assert!(normalize(&expr).equal(&expected));
+}
+
+#[test]
+fn test_selectivity() {
+    // 30 apples, 20 apricots and 50 bananas
+    let mcv = MostCommonValues::new(vec![
+        (Datum::String("apple".to_string()), 30),
+        (Datum::String("apricot".to_string()), 20),
+        (Datum::String("banana".to_string()), 50),
+    ]);
+    let col_stats = ColumnStats::new(
+        0,
+        "fruit".to_string(),
+        Datum::String("apple".to_string()),
+        Datum::String("banana".to_string()),
+        0,
+        None,
+    )
+    .with_ndv(3)
+    .with_mcv(mcv);
+    let stats = Statistics::new(100, HashMap::from([(0, col_stats)]));
+    let selectivity = |expr: &dyn ScalarExpression| estimate_selectivity(expr, &stats);
+
+    let like = |pattern: &str, negated: bool| Like::new(column(0), string(pattern), negated);
+    assert!((selectivity(&like("banana", false)) - 0.5).abs() < 1e-9);
+    assert!((selectivity(&like("ap%", false)) - 0.5).abs() < 1e-9);
+    assert!((selectivity(&like("ap%", true)) - 0.5).abs() < 1e-9);
+    assert!((selectivity(&like("%an%", false)) - 0.1).abs() < 1e-9);
+
+    let in_list = InList::new(column(0), vec![string("apple"), string("banana"), null()], false);
+    assert!((selectivity(&in_list) - 0.8).abs() < 1e-9);
+    assert!((selectivity(&in_list.negate()) - 0.2).abs() < 1e-9);
+
+    let between = Between::new(column(0), string("apricot"), string("banana"), false);
+    assert!((selectivity(&between) - 0.7).abs() < 1e-9);
+
+    // a column converted to a wider type is still estimated from its statistics
+    let fruit = ColumnVar::new(0).with_data_type(DataType::String);
+    let cast = Equal::new(Box::new(Cast::new(Box::new(fruit), DataType::String)), string("apple"));
+    assert!((selectivity(&cast) - 0.3).abs() < 1e-9);
+}
@@ -3,7 +3,7 @@ use cso_demo::expression::ScalarExpression;
 use cso_demo::expression::{ColumnVar, IsNull};
 use cso_demo::metadata::CachedMdProvider;
 use cso_demo::metadata::MdAccessor;
-use cso_demo::metadata::{MdCache, Metadata};
+use cso_demo::metadata::{MdCache, MdId, Metadata};
 use cso_demo::operator::logical_filter::LogicalFilter;
 use cso_demo::operator::logical_project::LogicalProject;
 use cso_demo::operator::logical_scan::{LogicalScan, TableDesc};
@@ -18,19 +18,8 @@ use cso_demo::statistics::{Bucket, ColumnMetadata, ColumnStats, Histogram, Relat
 use cso_demo::{LogicalPlan, Optimizer, Options, PhysicalPlan};
 use std::rc::Rc;
 
-/// Table: x(a, b, c)
-/// Sql: select b, c from x where a is null order by c;
-/// Plan:
-///     Sort(c)
-///         |
-///     Project(b, c)
-///         |
-///     Filter(a is null)
-///         |
-///     Scan(a, b, c)
-
 fn logical_scan() -> LogicalPlan {
-    let mdid = 2;
+    let mdid = MdId::relation(2);
     let table_desc = TableDesc::new(mdid);
     let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
 
@@ -63,9 +52,9 @@ fn required_properties() -> Rc<PhysicalProperties> {
 
 fn md_cache() -> MdCache {
     // mdids
-    let relation_stats_id = 1;
-    let relation_md_id = 2;
-    let column_stats_id = 3;
+    let relation_stats_id = MdId::relation_stats(1);
+    let relation_md_id = MdId::relation(2);
+    let column_stats_id = MdId::column_stats(3);
 
     // relation stats
     let relation_stats = RelationStats::new("x".to_string(), 9011, false, vec![column_stats_id]);
@@ -105,7 +94,7 @@ fn metadata_accessor() -> MdAccessor {
 }
 
 fn expected_physical_plan() -> PhysicalPlan {
-    let mdid = 2;
+    let mdid = MdId::relation(2);
     let table_desc = TableDesc::new(mdid);
     let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1), ColumnVar::new(2)];
     let scan = PhysicalScan::new(table_desc, output_columns);
@@ -129,6 +118,16 @@ fn expected_physical_plan() -> PhysicalPlan {
     PhysicalPlan::new(Rc::new(sort), vec![project])
 }
 
+/// Table: x(a, b, c)
+/// Sql: select b, c from x where a is null order by c;
+/// Plan:
+///     Sort(c)
+///         |
+///     Project(b, c)
+///         |
+///     Filter(a is null)
+///         |
+///     Scan(a, b, c)
 #[test]
 fn test_sort_project_filter_scan() {
     let mut optimizer = Optimizer::new(Options::default());
@@ -0,0 +1,359 @@
+use cso_core::operator::LogicalOperator;
+use cso_demo::datum::Datum;
+use cso_demo::expression::{And, ColumnVar, Const, GreaterThan, IsNull, LessThan, ScalarExpression};
+use cso_demo::metadata::CachedMdProvider;
+use cso_demo::metadata::MdAccessor;
+use cso_demo::metadata::{MdCache, MdId, Metadata, Stats};
+use cso_demo::operator::logical_filter::LogicalFilter;
+use cso_demo::operator::logical_index_scan::LogicalIndexScan;
+use cso_demo::operator::logical_project::LogicalProject;
+use cso_demo::operator::logical_scan::{LogicalScan, TableDesc};
+use cso_demo::statistics::{
+    Bucket, ColumnMetadata, ColumnStats, Histogram, IndexInfo, IndexMd, RelationMetadata, RelationStats, Statistics,
+};
+use cso_demo::Options;
+use std::rc::Rc;
+
+fn md_cache() -> MdCache {
+    // mdids
+    let relation_stats_id = MdId::relation_stats(1);
+    let relation_md_id = MdId::relation(2);
+    let column_stats_id = MdId::column_stats(3);
+    let index_md_id = MdId::index(4);
+
+    // relation stats
+    let relation_stats = RelationStats::new("t1".to_string(), 100, false, vec![column_stats_id]);
+    let boxed_relation_stats = Box::new(relation_stats) as Box<dyn Metadata>;
+
+    // index metadata
+    let index_md = IndexMd::new(
+        index_md_id,
+        "IDX_1".to_string(),
+        vec![ColumnVar::new(0)],
+        vec![ColumnVar::new(0), ColumnVar::new(1)],
+    );
+    let boxed_index_md = Box::new(index_md) as Box<dyn Metadata>;
+
+    // relation metadata
+    let column_md = vec![
+        ColumnMetadata::new("c1".to_string(), 1, true, 4, Datum::I32(0)),
+        ColumnMetadata::new("c2".to_string(), 2, true, 4, Datum::I32(0)),
+    ];
+    let relation_md = RelationMetadata::new(
+        "t1".to_string(),
+        column_md,
+        relation_stats_id,
+        vec![IndexInfo::new(index_md_id)],
+    );
+    let boxed_relation_md = Box::new(relation_md) as Box<dyn Metadata>;
+
+    // column stats: c1 has 10 null values, the others are evenly split by 10
+    let buckets = vec![
+        Bucket::new(Datum::I32(0), Datum::I32(10), 10, 45),
+        Bucket::new(Datum::I32(10), Datum::I32(100), 90, 45),
+    ];
+    let histogram = Histogram::new(buckets);
+    let column_stats = ColumnStats::new(0, "c1".to_string(), Datum::I32(0), Datum::I32(100), 10, Some(histogram));
+    let boxed_column_stats = Box::new(column_stats) as Box<dyn Metadata>;
+
+    // metadata cache
+    let mut md_cache = MdCache::new();
+    md_cache.insert(relation_stats_id, boxed_relation_stats);
+    md_cache.insert(relation_md_id, boxed_relation_md);
+    md_cache.insert(column_stats_id, boxed_column_stats);
+    md_cache.insert(index_md_id, boxed_index_md);
+
+    md_cache
+}
+
+fn metadata_accessor() -> MdAccessor {
+    let md_provider = Rc::new(CachedMdProvider::new(md_cache()));
+    MdAccessor::new(md_provider)
+}
+
+fn less_than(id: u32, val: i32) -> Rc<dyn ScalarExpression> {
+    Rc::new(LessThan::new(Box::new(ColumnVar::new(id)), Box::new(Const::Int32(val))))
+}
+
+fn index_scan_stats(md_accessor: &MdAccessor, predicate: Rc<dyn ScalarExpression>) -> Rc<dyn Stats> {
+    let index_md = md_accessor.retrieve_metadata(&MdId::index(4)).unwrap();
+    let index_md = index_md.downcast_ref::<IndexMd>().unwrap();
+    let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1)];
+    let index_scan = LogicalIndexScan::new(TableDesc::new(MdId::relation(2)), index_md, output_columns, predicate);
+    index_scan.derive_statistics(md_accessor, &Options::default(), &[])
+}
+
+fn filter_scan_stats(md_accessor: &MdAccessor, predicate: Rc<dyn ScalarExpression>) -> Rc<dyn Stats> {
+    let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1)];
+    let scan = LogicalScan::new(TableDesc::new(MdId::relation(2)), output_columns);
+    let scan_stats = scan.derive_statistics(md_accessor, &Options::default(), &[]);
+    let filter = LogicalFilter::new(predicate);
+    filter.derive_statistics(md_accessor, &Options::default(), &[scan_stats])
+}
+
+#[test]
+fn test_index_scan_statistics() {
+    let md_accessor = metadata_accessor();
+
+    let stats = index_scan_stats(&md_accessor, less_than(0, 10));
+    assert_eq!(stats.output_row_count(), 45);
+
+    let stats = index_scan_stats(&md_accessor, Rc::new(IsNull::new(Box::new(ColumnVar::new(0)))));
+    assert_eq!(stats.output_row_count(), 10);
+
+    // c1 < 55 covers the first bucket and half of the second one
+    let stats = index_scan_stats(&md_accessor, less_than(0, 55));
+    assert_eq!(stats.output_row_count(), 68);
+
+    let stats = stats.as_any().downcast_ref::<Statistics>().unwrap();
+    let col_stats = stats.column_stats(&ColumnVar::new(0)).unwrap();
+    let histogram = col_stats.histogram().as_ref().unwrap();
+    assert_eq!(col_stats.null_count(), 0);
+    assert_eq!(histogram.total_count(), 68);
+    assert_eq!(histogram.buckets()[1].upper(), &Datum::I32(55));
+}
+
+#[test]
+fn test_index_scan_statistics_agree_with_filter() {
+    let md_accessor = metadata_accessor();
+
+    let predicates = vec![
+        less_than(0, 10),
+        less_than(0, 55),
+        Rc::new(And::new(vec![less_than(0, 55), less_than(1, 10)])) as Rc<dyn ScalarExpression>,
+    ];
+    for predicate in predicates {
+        let index_scan_stats = index_scan_stats(&md_accessor, predicate.clone());
+        let filter_scan_stats = filter_scan_stats(&md_accessor, predicate);
+        assert_eq!(
+            index_scan_stats.output_row_count(),
+            filter_scan_stats.output_row_count()
+        );
+    }
+}
+
+#[test]
+fn test_partial_index_scan_statistics_agree_with_filter() {
+    let md_accessor = metadata_accessor();
+    let greater_than = |id: u32, val: i32| -> Rc<dyn ScalarExpression> {
+        Rc::new(GreaterThan::new(
+            Box::new(ColumnVar::new(id)),
+            Box::new(Const::Int32(val)),
+        ))
+    };
+    // an index of the rows of c1 > 10
+    let index_md = IndexMd::new(MdId::index(4), "IDX_1".to_string(), vec![ColumnVar::new(0)], vec![])
+        .with_predicate(greater_than(0, 10).clone_box());
+    let index_scan_stats = |predicate: Rc<dyn ScalarExpression>, implied_conjuncts_dropped: bool| {
+        let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1)];
+        let index_scan = LogicalIndexScan::new(TableDesc::new(MdId::relation(2)), &index_md, output_columns, predicate)
+            .with_implied_conjuncts_dropped(implied_conjuncts_dropped);
+        index_scan.derive_statistics(&md_accessor, &Options::default(), &[])
+    };
+
+    // c1 > 20 implies the predicate of the index, whose selectivity is not counted again
+    let stats = index_scan_stats(greater_than(0, 20), false);
+    let filter_stats = filter_scan_stats(&md_accessor, greater_than(0, 20));
+    assert_eq!(stats.output_row_count(), filter_stats.output_row_count());
+
+    // c1 > 10 is implied by the predicate of the index, which is scanned whole and estimated in its place
+    let stats = index_scan_stats(Rc::new(And::new(vec![])), true);
+    let filter_stats = filter_scan_stats(&md_accessor, greater_than(0, 10));
+    assert_eq!(stats.output_row_count(), filter_stats.output_row_count());
+}
+
+#[test]
+fn test_column_stats_lookup() {
+    let md_accessor = metadata_accessor();
+
+    // the column vars of the scan differ from the ordinal positions of the columns in the relation
+    let output_columns = vec![ColumnVar::new(10), ColumnVar::new(11)];
+    let scan = LogicalScan::new(TableDesc::new(MdId::relation(2)), output_columns);
+    let scan_stats = scan.derive_statistics(&md_accessor, &Options::default(), &[]);
+
+    let col_stats = scan_stats.column_stats(10).unwrap();
+    let col_stats = col_stats.downcast_ref::<ColumnStats>().unwrap();
+    assert_eq!(col_stats.name(), "c1");
+    assert!(scan_stats.column_stats(0).is_none());
+    assert!(scan_stats.column_stats(11).is_none());
+
+    let filter = LogicalFilter::new(less_than(10, 10));
+    let filter_stats = filter.derive_statistics(&md_accessor, &Options::default(), std::slice::from_ref(&scan_stats));
+    let filter_stats = filter_stats.as_any().downcast_ref::<Statistics>().unwrap();
+    let col_stats = filter_stats.column_stats(&ColumnVar::new(10)).unwrap();
+    assert_eq!(col_stats.histogram().as_ref().unwrap().total_count(), 45);
+
+    let project = LogicalProject::new(vec![Rc::new(ColumnVar::new(11))]);
+    let project_stats = project.derive_statistics(&md_accessor, &Options::default(), std::slice::from_ref(&scan_stats));
+    assert_eq!(project_stats.output_row_count(), 100);
+    assert!(project_stats.column_stats(10).is_none());
+
+    let project = LogicalProject::new(vec![Rc::new(ColumnVar::new(10))]);
+    let project_stats = project.derive_statistics(&md_accessor, &Options::default(), &[scan_stats]);
+    assert!(project_stats.column_stats(10).is_some());
+}
+
+#[test]
+fn test_most_common_values_selectivity() {
+    use cso_demo::expression::{Equal, Or};
+    use cso_demo::selectivity::estimate_selectivity;
+    use cso_demo::statistics::MostCommonValues;
+    use std::collections::HashMap;
+
+    // c1 has 10 null values, value 5 takes 40 rows and the 50 others are evenly spread over 50 values
+    let mcv = MostCommonValues::new(vec![(Datum::I32(5), 40)]);
+    let histogram = Histogram::new(vec![Bucket::new(Datum::I32(0), Datum::I32(100), 50, 50)]);
+    let col_stats = ColumnStats::new(0, "c1".to_string(), Datum::I32(0), Datum::I32(100), 10, Some(histogram))
+        .with_ndv(51)
+        .with_mcv(mcv);
+    let stats = Statistics::new(100, HashMap::from([(0, col_stats.clone())]));
+
+    let equal = |val: i32| -> Box<dyn ScalarExpression> {
+        Box::new(Equal::new(Box::new(ColumnVar::new(0)), Box::new(Const::Int32(val))))
+    };
+    assert!((estimate_selectivity(equal(5).as_ref(), &stats) - 0.4).abs() < 1e-9);
+    assert!((estimate_selectivity(equal(7).as_ref(), &stats) - 0.01).abs() < 1e-9);
+
+    // c1 = 5 OR c1 = 7 OR c1 = 5 is the IN-list (5, 7)
+    let or = Or::new(vec![equal(5), equal(7), equal(5)]);
+    assert!((estimate_selectivity(&or, &stats) - 0.41).abs() < 1e-9);
+
+    let filtered = cso_demo::selectivity::derive_filter_stats(&stats, &or);
+    assert_eq!(filtered.output_row_count(), 41);
+    let filtered = filtered.column_stats(&ColumnVar::new(0)).unwrap();
+    assert_eq!(filtered.mcv().as_ref().unwrap().values(), &[(Datum::I32(5), 40)]);
+
+    // the most common values survive serialization
+    let json = serde_json::to_string(&col_stats).unwrap();
+    let deserialized: ColumnStats = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.mcv(), col_stats.mcv());
+}
+
+#[test]
+fn test_extended_statistics_selectivity() {
+    use cso_demo::expression::Equal;
+    use cso_demo::selectivity::estimate_selectivity;
+    use cso_demo::statistics::{ExtendedStats, FunctionalDependency};
+
+    // every zip code belongs to a single city: 100 cities, 1000 zip codes
+    let city_stats = ColumnStats::new(0, "city".to_string(), Datum::I32(0), Datum::I32(99), 0, None).with_ndv(100);
+    let zip_stats = ColumnStats::new(1, "zip".to_string(), Datum::I32(0), Datum::I32(999), 0, None).with_ndv(1000);
+    let column_md = vec![
+        ColumnMetadata::new("city".to_string(), 1, false, 4, Datum::I32(0)),
+        ColumnMetadata::new("zip".to_string(), 2, false, 4, Datum::I32(0)),
+    ];
+    let md_cache = |extended_stats: Vec<ExtendedStats>| {
+        let mut md_cache = MdCache::new();
+        let col_stat_mdids = vec![MdId::column_stats(3), MdId::column_stats(4)];
+        md_cache.insert(
+            MdId::relation_stats(1),
+            Box::new(RelationStats::new("t1".to_string(), 10000, false, col_stat_mdids)),
+        );
+        md_cache.insert(
+            MdId::relation(2),
+            Box::new(
+                RelationMetadata::new("t1".to_string(), column_md.clone(), MdId::relation_stats(1), vec![])
+                    .with_extended_stats(extended_stats),
+            ),
+        );
+        md_cache.insert(MdId::column_stats(3), Box::new(city_stats.clone()));
+        md_cache.insert(MdId::column_stats(4), Box::new(zip_stats.clone()));
+        md_cache
+    };
+    let scan_stats = |md_cache: MdCache| {
+        let md_accessor = MdAccessor::new(Rc::new(CachedMdProvider::new(md_cache)));
+        let scan = LogicalScan::new(
+            TableDesc::new(MdId::relation(2)),
+            vec![ColumnVar::new(10), ColumnVar::new(11)],
+        );
+        let stats = scan.derive_statistics(&md_accessor, &Options::default(), &[]);
+        stats.as_any().downcast_ref::<Statistics>().unwrap().clone()
+    };
+
+    let equal = |id: u32, val: i32| -> Rc<dyn ScalarExpression> {
+        Rc::new(Equal::new(Box::new(ColumnVar::new(id)), Box::new(Const::Int32(val))))
+    };
+    let predicate = And::new(vec![equal(10, 1), equal(11, 5)]);
+
+    // without extended statistics the columns are assumed to be independent
+    let stats = scan_stats(md_cache(vec![]));
+    assert!((estimate_selectivity(&predicate, &stats) - 0.00001).abs() < 1e-12);
+
+    // zip => city
+    let dependencies =
+        ExtendedStats::new(vec![0, 1]).with_dependencies(vec![FunctionalDependency::new(vec![1], 0, 1.0)]);
+    let stats = scan_stats(md_cache(vec![dependencies]));
+    assert_eq!(stats.extended_stats()[0].columns(), &[10, 11]);
+    assert_eq!(stats.extended_stats()[0].dependencies()[0].dependent(), 10);
+    assert!((estimate_selectivity(&predicate, &stats) - 0.001).abs() < 1e-12);
+
+    // a weaker dependency only partly correlates the columns
+    let dependencies =
+        ExtendedStats::new(vec![0, 1]).with_dependencies(vec![FunctionalDependency::new(vec![1], 0, 0.5)]);
+    let stats = scan_stats(md_cache(vec![dependencies]));
+    assert!((estimate_selectivity(&predicate, &stats) - 0.001 * (0.5 + 0.5 * 0.01)).abs() < 1e-12);
+
+    // 1000 distinct (city, zip) combinations, a repeated column of invalid extended statistics counts once
+    let repeated = ExtendedStats::new(vec![0, 1, 1]).with_ndv(1000);
+    let stats = scan_stats(md_cache(vec![repeated]));
+    assert!((estimate_selectivity(&predicate, &stats) - 0.001).abs() < 1e-12);
+    let ndv = ExtendedStats::new(vec![0, 1]).with_ndv(1000);
+    let stats = scan_stats(md_cache(vec![ndv]));
+    assert!((estimate_selectivity(&predicate, &stats) - 0.001).abs() < 1e-12);
+    let filter = LogicalFilter::new(Rc::new(predicate));
+    let md_accessor = metadata_accessor();
+    let filter_stats = filter.derive_statistics(&md_accessor, &Options::default(), &[Rc::new(stats)]);
+    assert_eq!(filter_stats.output_row_count(), 10);
+
+    // extended statistics are dropped with their columns
+    let stats = scan_stats(md_cache(vec![ExtendedStats::new(vec![0, 1]).with_ndv(1000)]));
+    assert!(stats.project(&[ColumnVar::new(10)]).extended_stats().is_empty());
+    assert_eq!(
+        stats
+            .project(&[ColumnVar::new(10), ColumnVar::new(11)])
+            .extended_stats()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_param_selectivity() {
+    use cso_demo::expression::{bind_params, Equal, Param};
+    use cso_demo::selectivity::estimate_selectivity;
+    use std::collections::HashMap;
+
+    let md_accessor = metadata_accessor();
+    let less_than_param =
+        || -> Rc<dyn ScalarExpression> { Rc::new(LessThan::new(Box::new(ColumnVar::new(0)), Box::new(Param::new(1)))) };
+    let filter_stats = |options: &Options| {
+        let output_columns = vec![ColumnVar::new(0), ColumnVar::new(1)];
+        let scan = LogicalScan::new(TableDesc::new(MdId::relation(2)), output_columns);
+        let scan_stats = scan.derive_statistics(&md_accessor, options, &[]);
+        let filter = LogicalFilter::new(less_than_param());
+        filter.derive_statistics(&md_accessor, options, &[scan_stats])
+    };
+
+    // unbound, a third of the 90 non-null values
+    assert_eq!(filter_stats(&Options::default()).output_row_count(), 30);
+
+    // bound, estimated from the histogram like c1 < 10 and c1 < 55
+    let options = Options::default().with_param_value(1, Box::new(Const::Int32(10)));
+    assert_eq!(filter_stats(&options).output_row_count(), 45);
+    let options = Options::default().with_param_value(1, Box::new(Const::Int32(55)));
+    assert_eq!(filter_stats(&options).output_row_count(), 68);
+
+    // the plan keeps the parameter, the bound value is only substituted for the estimation
+    let bound = bind_params(less_than_param().as_ref(), &options);
+    assert!(bound.equal(less_than(0, 55).as_ref()));
+    let unbound = bind_params(less_than_param().as_ref(), &Options::default());
+    assert!(unbound.equal(less_than_param().as_ref()));
+
+    // an unbound equality selects the rows of an average value
+    let histogram = Histogram::new(vec![Bucket::new(Datum::I32(0), Datum::I32(100), 90, 45)]);
+    let col_stats =
+        ColumnStats::new(0, "c1".to_string(), Datum::I32(0), Datum::I32(100), 10, Some(histogram)).with_ndv(45);
+    let stats = Statistics::new(100, HashMap::from([(0, col_stats)]));
+    let equal = Equal::new(Box::new(Param::new(2)), Box::new(ColumnVar::new(0)));
+    assert!((estimate_selectivity(&equal, &stats) - 0.02).abs() < 1e-9);
+}
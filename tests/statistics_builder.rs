@@ -0,0 +1,177 @@
+use cso_core::operator::LogicalOperator;
+use cso_demo::datum::Datum;
+use cso_demo::expression::{ColumnVar, Const, Equal, LessThan};
+use cso_demo::metadata::{CachedMdProvider, MdAccessor, MdCache, MdId, Metadata};
+use cso_demo::operator::logical_filter::LogicalFilter;
+use cso_demo::operator::logical_scan::{LogicalScan, TableDesc};
+use cso_demo::statistics::{ColumnMetadata, ColumnStats};
+use cso_demo::statistics::{ColumnStatsBuilder, HyperLogLog, RelationMetadata, RelationStatsBuilder};
+use cso_demo::Options;
+use std::rc::Rc;
+
+#[test]
+fn test_equi_depth_histogram() {
+    let mut builder = ColumnStatsBuilder::new(0, "c1".to_string()).with_bucket_count(10);
+    for i in 0..1000 {
+        builder.add(Some(Datum::I32(i)));
+    }
+    builder.add(None);
+
+    let col_stats = builder.build();
+    assert_eq!(col_stats.min(), &Datum::I32(0));
+    assert_eq!(col_stats.max(), &Datum::I32(999));
+    assert_eq!(col_stats.null_count(), 1);
+    assert_eq!(col_stats.ndv(), Some(1000));
+
+    let buckets = col_stats.histogram().as_ref().unwrap().buckets();
+    assert_eq!(buckets.len(), 10);
+    for (i, bucket) in buckets.iter().enumerate() {
+        assert_eq!(bucket.lower(), &Datum::I32(i as i32 * 100));
+        assert_eq!(bucket.upper(), &Datum::I32(i as i32 * 100 + 99));
+        assert_eq!(bucket.ndv(), 100);
+        assert_eq!(bucket.value_count(), 100);
+    }
+}
+
+#[test]
+fn test_equal_values_are_not_split() {
+    let mut builder = ColumnStatsBuilder::new(0, "c1".to_string())
+        .with_bucket_count(4)
+        .with_mcv_count(0);
+    // value 1 takes half of the column
+    for i in 0..100 {
+        let value = if i < 50 { 1 } else { i };
+        builder.add(Some(Datum::I32(value)));
+    }
+
+    let col_stats = builder.build();
+    let buckets = col_stats.histogram().as_ref().unwrap().buckets();
+    assert_eq!(buckets[0].lower(), &Datum::I32(1));
+    assert_eq!(buckets[0].upper(), &Datum::I32(1));
+    assert_eq!(buckets[0].value_count(), 50);
+    assert_eq!(buckets[0].ndv(), 1);
+    assert!(buckets.windows(2).all(|w| w[0].upper() < w[1].lower()));
+    assert_eq!(col_stats.ndv(), Some(51));
+}
+
+#[test]
+fn test_most_common_values() {
+    let mut builder = ColumnStatsBuilder::new(0, "c1".to_string()).with_bucket_count(4);
+    // value 1 takes half of the column, value 2 a tenth of it
+    for i in 0..100 {
+        let value = match i {
+            0..50 => 1,
+            50..60 => 2,
+            _ => i,
+        };
+        builder.add(Some(Datum::I32(value)));
+    }
+
+    let col_stats = builder.build();
+    let mcv = col_stats.mcv().as_ref().unwrap();
+    assert_eq!(mcv.values(), &[(Datum::I32(1), 50), (Datum::I32(2), 10)]);
+
+    // the histogram only describes the other values
+    let histogram = col_stats.histogram().as_ref().unwrap();
+    assert_eq!(histogram.total_count(), 40);
+    assert_eq!(histogram.buckets()[0].lower(), &Datum::I32(60));
+    assert_eq!(col_stats.ndv(), Some(42));
+}
+
+#[test]
+fn test_large_column_ndv_sketch() {
+    let mut builder = ColumnStatsBuilder::new(0, "c1".to_string()).with_sample_size(1000);
+    for i in 0..100_000 {
+        builder.add(Some(Datum::I32(i)));
+    }
+
+    let col_stats = builder.build();
+    let ndv = col_stats.ndv().unwrap() as f64;
+    assert!((ndv - 100_000.0).abs() / 100_000.0 < 0.05, "ndv: {ndv}");
+    assert_eq!(col_stats.histogram().as_ref().unwrap().total_count(), 100_000);
+    assert_eq!(col_stats.min(), &Datum::I32(0));
+    assert_eq!(col_stats.max(), &Datum::I32(99_999));
+}
+
+#[test]
+fn test_hyperloglog() {
+    let mut sketch_1 = HyperLogLog::default();
+    let mut sketch_2 = HyperLogLog::default();
+    for i in 0..10_000 {
+        sketch_1.insert(&i);
+        sketch_2.insert(&(i + 5_000));
+    }
+    assert!(sketch_1.estimate().abs_diff(10_000) < 500);
+
+    sketch_1.merge(&sketch_2);
+    assert!(sketch_1.estimate().abs_diff(15_000) < 750);
+
+    let json = serde_json::to_string(&sketch_1).unwrap();
+    let new_sketch: HyperLogLog = serde_json::from_str(&json).unwrap();
+    assert_eq!(new_sketch, sketch_1);
+}
+
+#[test]
+fn test_sample_is_scaled_to_table() {
+    // a sample of 1% of a table with 100000 rows, every value of c1 appears 10 times in the table
+    let mut builder = RelationStatsBuilder::new("t1".to_string(), vec!["c1".to_string(), "c2".to_string()])
+        .with_bucket_count(10)
+        .with_table_rows(100_000);
+    for i in 0..1000 {
+        let c2 = if i % 10 == 0 { None } else { Some(Datum::I32(i % 7)) };
+        builder.add_row(&[Some(Datum::I32(i)), c2]);
+    }
+
+    let (relation_stats, column_stats) = builder.build(vec![MdId::column_stats(10), MdId::column_stats(11)]);
+    assert_eq!(relation_stats.rows(), 100_000);
+    assert_eq!(
+        relation_stats.col_stat_mdids(),
+        &[MdId::column_stats(10), MdId::column_stats(11)]
+    );
+
+    let c1 = &column_stats[0];
+    assert_eq!(c1.histogram().as_ref().unwrap().total_count(), 100_000);
+    // every sampled value appears once, so c1 looks unique
+    assert_eq!(c1.ndv(), Some(100_000));
+
+    let c2 = &column_stats[1];
+    assert_eq!(c2.col_id(), 1);
+    assert_eq!(c2.null_count(), 10_000);
+    assert_eq!(c2.ndv(), Some(7));
+}
+
+#[test]
+fn test_build_into_md_cache() {
+    let mut md_cache = MdCache::new();
+    let column_md = vec![ColumnMetadata::new("c1".to_string(), 1, true, 4, Datum::I32(0))];
+    let relation_md = RelationMetadata::new("t1".to_string(), column_md, MdId::relation_stats(1), vec![]);
+    md_cache.insert(MdId::relation(2), Box::new(relation_md) as Box<dyn Metadata>);
+
+    let mut builder = RelationStatsBuilder::new("t1".to_string(), vec!["c1".to_string()]).with_bucket_count(10);
+    for i in 0..1000 {
+        builder.add_row(&[Some(Datum::I32(i % 100))]);
+    }
+    builder.build_into(&mut md_cache, MdId::relation_stats(1), vec![MdId::column_stats(3)]);
+
+    let col_stats = md_cache
+        .get(&MdId::column_stats(3))
+        .unwrap()
+        .downcast_ref::<ColumnStats>()
+        .unwrap();
+    assert_eq!(col_stats.ndv(), Some(100));
+
+    let md_accessor = MdAccessor::new(Rc::new(CachedMdProvider::new(md_cache)));
+    let scan = LogicalScan::new(TableDesc::new(MdId::relation(2)), vec![ColumnVar::new(0)]);
+    let scan_stats = scan.derive_statistics(&md_accessor, &Options::default(), &[]);
+    assert_eq!(scan_stats.output_row_count(), 1000);
+
+    let predicate = LessThan::new(Box::new(ColumnVar::new(0)), Box::new(Const::Int32(50)));
+    let filter = LogicalFilter::new(Rc::new(predicate));
+    let filter_stats = filter.derive_statistics(&md_accessor, &Options::default(), std::slice::from_ref(&scan_stats));
+    assert_eq!(filter_stats.output_row_count(), 500);
+
+    let predicate = Equal::new(Box::new(ColumnVar::new(0)), Box::new(Const::Int32(42)));
+    let filter = LogicalFilter::new(Rc::new(predicate));
+    let filter_stats = filter.derive_statistics(&md_accessor, &Options::default(), &[scan_stats]);
+    assert_eq!(filter_stats.output_row_count(), 10);
+}